@@ -57,6 +57,8 @@ fn main() {
         selected_asr_vendor: vendor.to_string(),
         status_callback: None,
         transcript_config: None,
+        vendor_switch_callback: None,
+        comparison_callback: None,
     };
 
     if let Ok(handle) = start_record_audio_with_writer(params) {
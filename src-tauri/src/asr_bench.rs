@@ -0,0 +1,492 @@
+//! ASR benchmarking over a WAV corpus: every file is streamed through each
+//! vendor at real-time pace and scored against its reference transcript.
+//! Each `name.wav` is paired with a `name.txt` reference next to it.
+
+use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_latency::{
+    LatencyStats, begin_latency_session, current_latency_report, end_latency_session,
+    note_captured_i16, note_transcript_emitted,
+};
+use crate::transcript_vendors::comparison::{edit_distance, tokenize_transcript};
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, TranscriptEvent, TranscriptEventKind, TranscriptVendors,
+    start_transcriber,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CHUNK_MS: u64 = 100;
+/// Once the audio is over, silence keeps flowing until the last commit has
+/// been quiet this long, as it would in a live capture.
+const TAIL_QUIET: Duration = Duration::from_millis(1_500);
+
+#[derive(Debug, Clone)]
+pub struct CorpusItem {
+    pub name: String,
+    pub wav_path: PathBuf,
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorCount {
+    pub errors: usize,
+    pub reference_len: usize,
+}
+
+impl ErrorCount {
+    pub fn rate(&self) -> Option<f64> {
+        if self.reference_len == 0 {
+            return None;
+        }
+        Some(self.errors as f64 / self.reference_len as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchResult {
+    pub file: String,
+    pub vendor: String,
+    pub audio_seconds: f64,
+    pub reference: String,
+    pub hypothesis: String,
+    pub words: ErrorCount,
+    pub chars: ErrorCount,
+    pub wer: Option<f64>,
+    pub cer: Option<f64>,
+    /// Median over the file's utterances.
+    pub first_draft_ms: Option<u64>,
+    pub final_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorBenchSummary {
+    pub vendor: String,
+    pub files: usize,
+    pub failures: usize,
+    /// Corpus-level rates: total errors over total reference length.
+    pub wer: Option<f64>,
+    pub cer: Option<f64>,
+    pub first_draft: LatencyStats,
+    pub final_latency: LatencyStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub vendors: Vec<VendorBenchSummary>,
+    pub results: Vec<BenchResult>,
+}
+
+/// Word errors, where every CJK character counts as a word.
+pub fn word_errors(reference: &str, hypothesis: &str) -> ErrorCount {
+    let reference = tokenize_transcript(reference);
+    let hypothesis = tokenize_transcript(hypothesis);
+    ErrorCount {
+        errors: edit_distance(&reference, &hypothesis),
+        reference_len: reference.len(),
+    }
+}
+
+/// Character errors over letters and digits; case, spaces and punctuation are ignored.
+pub fn char_errors(reference: &str, hypothesis: &str) -> ErrorCount {
+    let normalize = |text: &str| {
+        text.chars()
+            .filter(|ch| ch.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<Vec<_>>()
+    };
+    let reference = normalize(reference);
+    let hypothesis = normalize(hypothesis);
+    ErrorCount {
+        errors: edit_distance(&reference, &hypothesis),
+        reference_len: reference.len(),
+    }
+}
+
+/// Every WAV in `dir` that has a reference transcript, sorted by name.
+pub fn load_corpus(dir: &Path) -> Result<Vec<CorpusItem>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("读取语料目录 {} 失败: {err}", dir.display()))?;
+    let mut items = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("读取语料目录失败: {err}"))?
+            .path();
+        if !path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+        {
+            continue;
+        }
+        let reference_path = path.with_extension("txt");
+        let reference = match fs::read_to_string(&reference_path) {
+            Ok(reference) => reference.trim().to_string(),
+            Err(_) => {
+                eprintln!(
+                    "跳过 {}: 缺少参考文本 {}",
+                    path.display(),
+                    reference_path.display()
+                );
+                continue;
+            }
+        };
+        items.push(CorpusItem {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            wav_path: path,
+            reference,
+        });
+    }
+    items.sort_by(|left, right| left.name.cmp(&right.name));
+    Ok(items)
+}
+
+/// Reads a WAV as mono 16-bit PCM.
+pub fn read_wav(path: &Path) -> Result<(Vec<i16>, u32), String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|err| format!("打开 {} 失败: {err}", path.display()))?;
+    let spec = reader.spec();
+    let interleaved = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Int, 16) => reader
+            .samples::<i16>()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("读取 {} 失败: {err}", path.display()))?,
+        (hound::SampleFormat::Float, 32) => reader
+            .samples::<f32>()
+            .map(|sample| sample.map(|value| (value.clamp(-1.0, 1.0) * i16::MAX as f32) as i16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("读取 {} 失败: {err}", path.display()))?,
+        (format, bits) => {
+            return Err(format!(
+                "{} 的采样格式不受支持: {format:?} {bits} bit",
+                path.display()
+            ));
+        }
+    };
+    let channels = spec.channels.max(1) as usize;
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| {
+            (frame.iter().map(|&sample| sample as i32).sum::<i32>() / frame.len() as i32) as i16
+        })
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+/// Streams one file through `vendor` and scores the committed text.
+pub fn run_item(
+    vendor: TranscriptVendors,
+    item: &CorpusItem,
+    transcript_config: TranscriptRuntimeConfig,
+    tail_timeout: Duration,
+) -> BenchResult {
+    let mut result = empty_result(vendor, item);
+    begin_latency_session(&format!("bench-{}-{}", vendor.as_str(), item.name));
+    let streamed = stream_item(vendor, item, transcript_config, tail_timeout, &mut result);
+    end_latency_session();
+    if let Err(err) = streamed {
+        result.error = Some(err);
+    }
+    if let Some(report) = current_latency_report() {
+        result.first_draft_ms = report.first_draft.p50_ms;
+        result.final_ms = report.speech_end_to_final.p50_ms;
+    }
+    score(&mut result);
+    result
+}
+
+fn empty_result(vendor: TranscriptVendors, item: &CorpusItem) -> BenchResult {
+    BenchResult {
+        file: item.name.clone(),
+        vendor: vendor.as_str().to_string(),
+        audio_seconds: 0.0,
+        reference: item.reference.clone(),
+        hypothesis: String::new(),
+        words: ErrorCount::default(),
+        chars: ErrorCount::default(),
+        wer: None,
+        cer: None,
+        first_draft_ms: None,
+        final_ms: None,
+        error: None,
+    }
+}
+
+fn score(result: &mut BenchResult) {
+    result.words = word_errors(&result.reference, &result.hypothesis);
+    result.chars = char_errors(&result.reference, &result.hypothesis);
+    result.wer = result.words.rate();
+    result.cer = result.chars.rate();
+}
+
+fn stream_item(
+    vendor: TranscriptVendors,
+    item: &CorpusItem,
+    transcript_config: TranscriptRuntimeConfig,
+    tail_timeout: Duration,
+    result: &mut BenchResult,
+) -> Result<(), String> {
+    let (samples, sample_rate) = read_wav(&item.wav_path)?;
+    if sample_rate == 0 {
+        return Err(format!("{} 的采样率无效", item.wav_path.display()));
+    }
+    result.audio_seconds = samples.len() as f64 / sample_rate as f64;
+
+    let events = Arc::new(Mutex::new(Vec::<(TranscriptEvent, Instant)>::new()));
+    let failure = Arc::new(Mutex::new(None::<String>));
+    let callback = {
+        let events = events.clone();
+        Arc::new(move |event: TranscriptEvent| {
+            note_transcript_emitted(&event);
+            events.lock().unwrap().push((event, Instant::now()));
+        }) as PcmCallback
+    };
+    let status_callback = {
+        let failure = failure.clone();
+        Arc::new(move |message: String| {
+            failure.lock().unwrap().get_or_insert(message);
+        }) as StatusCallback
+    };
+    let transcriber = start_transcriber(
+        vendor,
+        sample_rate,
+        callback,
+        Some(status_callback),
+        transcript_config,
+    )?;
+
+    let chunk_len = (sample_rate as u64 * CHUNK_MS / 1000).max(1) as usize;
+    let started = Instant::now();
+    let mut sent_ms = 0;
+    let mut send = |chunk: Vec<i16>| -> Result<(), String> {
+        note_captured_i16(&chunk, sample_rate);
+        transcriber.queue_chunk(chunk)?;
+        sent_ms += CHUNK_MS;
+        // Real-time pace, so vendor endpointing behaves as it does live.
+        let due = started + Duration::from_millis(sent_ms);
+        thread::sleep(due.saturating_duration_since(Instant::now()));
+        Ok(())
+    };
+
+    let outcome = (|| {
+        for chunk in samples.chunks(chunk_len) {
+            send(chunk.to_vec())?;
+            if let Some(message) = failure.lock().unwrap().clone() {
+                return Err(message);
+            }
+        }
+        transcriber.force_endpoint()?;
+
+        let audio_done = Instant::now();
+        while audio_done.elapsed() < tail_timeout {
+            if let Some(message) = failure.lock().unwrap().clone() {
+                return Err(message);
+            }
+            let settled = events.lock().unwrap().last().is_some_and(|(event, at)| {
+                event.kind == TranscriptEventKind::Commit && at.elapsed() >= TAIL_QUIET
+            });
+            if settled {
+                return Ok(());
+            }
+            send(vec![0; chunk_len])?;
+        }
+        Err(format!("等待最终结果超时 ({}s)", tail_timeout.as_secs()))
+    })();
+    transcriber.shutdown();
+
+    result.hypothesis = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(event, _)| event.kind == TranscriptEventKind::Commit)
+        .map(|(event, _)| event.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    outcome
+}
+
+/// Same as `run_item`, against an in-process mock server that answers with
+/// the reference transcript.
+#[cfg(feature = "mock-asr")]
+pub fn run_item_with_mock(
+    vendor: TranscriptVendors,
+    item: &CorpusItem,
+    tail_timeout: Duration,
+) -> BenchResult {
+    use crate::transcript_vendors::mock_server::{MockAsrServer, MockScript};
+
+    let script = MockScript {
+        utterances: vec![item.reference.clone()],
+        ..MockScript::default()
+    };
+    match MockAsrServer::start(vendor, script) {
+        Ok(server) => run_item(vendor, item, server.transcript_config(), tail_timeout),
+        Err(err) => {
+            let mut result = empty_result(vendor, item);
+            result.error = Some(err);
+            score(&mut result);
+            result
+        }
+    }
+}
+
+pub fn summarize(results: Vec<BenchResult>) -> BenchReport {
+    let mut vendors = Vec::<String>::new();
+    for result in &results {
+        if !vendors.contains(&result.vendor) {
+            vendors.push(result.vendor.clone());
+        }
+    }
+
+    let vendors = vendors
+        .into_iter()
+        .map(|vendor| {
+            let rows = results
+                .iter()
+                .filter(|result| result.vendor == vendor)
+                .collect::<Vec<_>>();
+            let total = |pick: fn(&BenchResult) -> ErrorCount| {
+                rows.iter().fold(ErrorCount::default(), |sum, row| {
+                    let count = pick(row);
+                    ErrorCount {
+                        errors: sum.errors + count.errors,
+                        reference_len: sum.reference_len + count.reference_len,
+                    }
+                })
+            };
+            let latencies = |pick: fn(&BenchResult) -> Option<u64>| {
+                rows.iter().filter_map(|row| pick(row)).collect::<Vec<_>>()
+            };
+            VendorBenchSummary {
+                files: rows.len(),
+                failures: rows.iter().filter(|row| row.error.is_some()).count(),
+                wer: total(|row| row.words).rate(),
+                cer: total(|row| row.chars).rate(),
+                first_draft: LatencyStats::from_samples(&latencies(|row| row.first_draft_ms)),
+                final_latency: LatencyStats::from_samples(&latencies(|row| row.final_ms)),
+                vendor,
+            }
+        })
+        .collect();
+
+    BenchReport { vendors, results }
+}
+
+pub fn results_csv(results: &[BenchResult]) -> String {
+    let mut csv = String::from(
+        "file,vendor,audio_seconds,wer,cer,word_errors,reference_words,char_errors,reference_chars,first_draft_ms,final_ms,error,reference,hypothesis\n",
+    );
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    for result in results {
+        let fields = [
+            csv_field(&result.file),
+            csv_field(&result.vendor),
+            format!("{:.2}", result.audio_seconds),
+            optional(result.wer.map(|rate| format!("{rate:.4}"))),
+            optional(result.cer.map(|rate| format!("{rate:.4}"))),
+            result.words.errors.to_string(),
+            result.words.reference_len.to_string(),
+            result.chars.errors.to_string(),
+            result.chars.reference_len.to_string(),
+            optional(result.first_draft_ms.map(|ms| ms.to_string())),
+            optional(result.final_ms.map(|ms| ms.to_string())),
+            csv_field(result.error.as_deref().unwrap_or_default()),
+            csv_field(&result.reference),
+            csv_field(&result.hypothesis),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchResult, ErrorCount, char_errors, results_csv, summarize, word_errors};
+
+    fn result(vendor: &str, words: ErrorCount, final_ms: Option<u64>) -> BenchResult {
+        BenchResult {
+            file: "a".to_string(),
+            vendor: vendor.to_string(),
+            audio_seconds: 1.0,
+            reference: "hello, \"world\"".to_string(),
+            hypothesis: "hello word".to_string(),
+            words,
+            chars: ErrorCount::default(),
+            wer: words.rate(),
+            cer: None,
+            first_draft_ms: None,
+            final_ms,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn cjk_is_scored_per_character() {
+        let wer = word_errors("今天天气很好", "今天天气不好");
+        assert_eq!(wer.errors, 1);
+        assert_eq!(wer.reference_len, 6);
+
+        let wer = word_errors("Hello, world!", "hello word");
+        assert_eq!(wer.rate(), Some(0.5));
+        let cer = char_errors("Hello, world!", "hello word");
+        assert_eq!((cer.errors, cer.reference_len), (1, 10));
+        assert_eq!(word_errors("", "extra").rate(), None);
+    }
+
+    #[test]
+    fn summary_pools_errors_and_latencies_per_vendor() {
+        let report = summarize(vec![
+            result(
+                "deepgram",
+                ErrorCount {
+                    errors: 1,
+                    reference_len: 4,
+                },
+                Some(300),
+            ),
+            result(
+                "deepgram",
+                ErrorCount {
+                    errors: 0,
+                    reference_len: 6,
+                },
+                Some(500),
+            ),
+            result(
+                "gladia",
+                ErrorCount {
+                    errors: 2,
+                    reference_len: 2,
+                },
+                None,
+            ),
+        ]);
+        assert_eq!(report.vendors.len(), 2);
+        assert_eq!(report.vendors[0].wer, Some(0.1));
+        assert_eq!(report.vendors[0].final_latency.p95_ms, Some(500));
+        assert_eq!(report.vendors[1].final_latency.samples, 0);
+
+        let csv = results_csv(&report.results);
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.contains("\"hello, \"\"world\"\"\""));
+    }
+}
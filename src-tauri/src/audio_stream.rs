@@ -1,12 +1,23 @@
 #![allow(clippy::needless_bool)]
 
+use crate::llm::{note_transcript_context, reset_transcript_context};
 use crate::loopback::{RecordParams, start_record_audio_with_writer, stop_recording};
 #[cfg(target_os = "macos")]
 use crate::macos_system_audio::{
     start_macos_system_audio_transcription, stop_macos_system_audio_capture,
 };
-use crate::provider_config::TranscriptRuntimeConfig;
-use crate::transcript_vendors::TranscriptEvent;
+use crate::provider_config::{LlmRuntimeConfig, TranscriptRuntimeConfig, share_llm_keys};
+use crate::transcript_history::{begin_history_session, end_history_session, record_history_event};
+use crate::transcript_latency::{
+    begin_latency_session, end_latency_session, note_transcript_emitted,
+};
+use crate::transcript_post_process::attach_post_processing;
+use crate::transcript_translation::attach_translation_stream;
+use crate::transcript_turns::attach_turn_analyzer;
+use crate::transcript_usage::{begin_usage_session, end_usage_session};
+use crate::transcript_vendors::comparison::ComparisonUpdate;
+use crate::transcript_vendors::failover::VendorSwitchedEvent;
+use crate::transcript_vendors::{PcmCallback, TranscriptEvent};
 use cpal::traits::{DeviceTrait, HostTrait};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -223,6 +234,9 @@ pub fn stop_recognize_audio_stream_from_speaker_loopback() {
     } else {
         println!("没有正在运行的录音线程");
     }
+    end_history_session();
+    end_usage_session();
+    end_latency_session();
 }
 
 #[tauri::command]
@@ -232,8 +246,10 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
     selected_asr_vendor: String,
     capture_interval: u32,
     transcript_config: Option<TranscriptRuntimeConfig>,
+    llm_config: Option<LlmRuntimeConfig>,
 ) {
     set_recording_requested(true);
+    let transcript_config = share_llm_keys(transcript_config, llm_config.as_ref());
 
     let selected_device = parse_selected_audio_device(device_name.as_deref());
     let (device, is_input_device, device_occurrence) = match selected_device {
@@ -243,25 +259,76 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
         SelectedAudioDevice::NamedInput { name, occurrence } => (name, true, Some(occurrence)),
     };
 
+    let session_id = begin_history_session(
+        &app,
+        device_name.as_deref().unwrap_or("default"),
+        &selected_asr_vendor,
+        transcript_config.as_ref(),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("转录历史记录启动失败: {err}");
+        format!("unrecorded-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S"))
+    });
+    if let Err(err) = begin_usage_session(&app, &session_id, transcript_config.as_ref()) {
+        eprintln!("用量统计启动失败: {err}");
+    }
+    begin_latency_session(&session_id);
+    reset_transcript_context();
+
     let last_result = Arc::new(Mutex::new(None::<TranscriptEvent>));
     let transcript_app = app.clone();
     let error_app = app.clone();
+    let switch_app = app.clone();
+    let comparison_app = app.clone();
     let status_callback = Arc::new(move |message: String| {
         if let Err(err) = error_app.emit("transcription_error", message) {
             eprintln!("Failed to emit transcription error: {err}");
         }
     });
-    let pcm_callback = Arc::new(move |event: TranscriptEvent| {
-        let mut last = last_result.lock().unwrap();
-        if last.as_ref() == Some(&event) {
-            return;
+    let switch_callback = Arc::new(move |event: VendorSwitchedEvent| {
+        if let Err(err) = switch_app.emit("vendor_switched", event) {
+            eprintln!("Failed to emit vendor switch: {err}");
         }
-
-        *last = Some(event.clone());
+    });
+    let comparison_callback = Arc::new(move |update: ComparisonUpdate| {
+        if let Err(err) = comparison_app.emit("comparison_event", update) {
+            eprintln!("Failed to emit comparison event: {err}");
+        }
+    });
+    let emit_callback: PcmCallback = Arc::new(move |event: TranscriptEvent| {
+        record_history_event(&event);
+        note_transcript_context(&event);
+        note_transcript_emitted(&event);
         if let Err(err) = transcript_app.emit("transcription_event", event) {
             eprintln!("Failed to emit transcription event: {err}");
         }
     });
+    // Innermost, so questions are judged on post-processed text.
+    let emit_callback = attach_turn_analyzer(
+        &app,
+        transcript_config.as_ref(),
+        llm_config.clone(),
+        !is_input_device,
+        emit_callback,
+    );
+    let emit_callback = attach_translation_stream(
+        &app,
+        transcript_config.as_ref(),
+        llm_config.clone(),
+        emit_callback,
+    );
+    let emit_callback =
+        attach_post_processing(&app, transcript_config.as_ref(), llm_config, emit_callback);
+    let pcm_callback = Arc::new(move |event: TranscriptEvent| {
+        {
+            let mut last = last_result.lock().unwrap();
+            if last.as_ref() == Some(&event) {
+                return;
+            }
+            *last = Some(event.clone());
+        }
+        emit_callback(event);
+    });
 
     #[cfg(target_os = "macos")]
     if !is_input_device {
@@ -270,6 +337,8 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
             selected_asr_vendor,
             pcm_callback,
             Some(status_callback),
+            Some(switch_callback),
+            Some(comparison_callback),
             transcript_config,
         ) {
             Ok(handle) => {
@@ -286,6 +355,9 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
                 println!("macOS 系统音频识别已开始 ✅");
             }
             Err(err) => {
+                end_history_session();
+                end_usage_session();
+                end_latency_session();
                 eprintln!("macOS 系统音频识别启动失败 ❌ {err}");
             }
         }
@@ -306,6 +378,8 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
         selected_asr_vendor,
         status_callback: Some(status_callback),
         transcript_config,
+        vendor_switch_callback: Some(switch_callback),
+        comparison_callback: Some(comparison_callback),
     };
 
     if let Ok(handle) = start_record_audio_with_writer(params) {
@@ -319,6 +393,9 @@ pub fn start_recognize_audio_stream_from_speaker_loopback(
         *guard = Some(handle);
         println!("录音识别已开始 ✅");
     } else {
+        end_history_session();
+        end_usage_session();
+        end_latency_session();
         eprintln!("录音线程启动失败 ❌");
     }
 }
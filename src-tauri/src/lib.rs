@@ -1,5 +1,7 @@
 extern crate core;
 
+#[cfg(feature = "asr-bench")]
+pub mod asr_bench;
 mod audio_stream;
 mod constant;
 pub mod license;
@@ -8,10 +10,18 @@ mod loopback;
 #[cfg(target_os = "macos")]
 mod macos_system_audio;
 mod provider_config;
+mod proxy;
+pub mod transcript_export;
+pub mod transcript_history;
+mod transcript_latency;
+mod transcript_post_process;
+mod transcript_translation;
+mod transcript_turns;
+mod transcript_usage;
 mod transcript_vendors;
 mod utils;
 pub use audio_stream::*;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 pub use constant::*;
 use dotenv::{dotenv, from_filename};
 use license::{
@@ -21,12 +31,25 @@ use license::{
 pub use llm::*;
 use log::{error, info, warn};
 pub use loopback::*;
-use provider_config::{ProviderEnvPresets, provider_env_presets_from_env};
+use provider_config::{
+    LlmRuntimeConfig, ProviderEnvPresets, provider_env_presets_from_env, share_llm_keys,
+};
 pub use provider_config::{TranscriptRuntimeConfig, transcript_runtime_config_from_env};
 use std::path::PathBuf;
 use tauri::LogicalSize;
 use tauri::{Manager, WebviewUrl, WebviewWindowBuilder};
 use tauri_plugin_log::{Target, TargetKind};
+use transcript_export::{ExportFormat, TranscriptExport, export_session};
+use transcript_history::{
+    TranscriptSearchHit, TranscriptSession, TranscriptSessionSummary, delete_session, history_dir,
+    list_sessions, load_session, search_sessions,
+};
+use transcript_latency::{LatencyReport, current_latency_report};
+use transcript_turns::auto_answer_stream_ready;
+use transcript_usage::{
+    UsagePricing, UsageReport, build_usage_report, current_usage_store, usage_file_path,
+};
+use transcript_vendors::capabilities::{VendorCapabilityReport, vendor_capability_reports};
 pub use transcript_vendors::*;
 pub use utils::*;
 
@@ -169,23 +192,141 @@ fn get_provider_env_presets() -> ProviderEnvPresets {
     provider_env_presets_from_env()
 }
 
+#[tauri::command]
+fn get_transcript_vendor_capabilities(
+    transcript_config: Option<TranscriptRuntimeConfig>,
+    llm_config: Option<LlmRuntimeConfig>,
+) -> Vec<VendorCapabilityReport> {
+    info!("get_transcript_vendor_capabilities invoked");
+    let transcript_config = share_llm_keys(transcript_config, llm_config.as_ref());
+    vendor_capability_reports(&transcript_config.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_asr_usage(
+    app: tauri::AppHandle,
+    transcript_config: Option<TranscriptRuntimeConfig>,
+    month: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<UsageReport, String> {
+    info!("get_asr_usage invoked");
+    let parse_bound = |value: Option<String>, label: &str| {
+        value
+            .map(|value| {
+                value
+                    .parse::<NaiveDate>()
+                    .map_err(|err| format!("{label} 解析失败: {err}"))
+            })
+            .transpose()
+    };
+    let from = parse_bound(from, "from")?;
+    let to = parse_bound(to, "to")?;
+    let store = current_usage_store(&usage_file_path(&app)?)?;
+    let pricing = UsagePricing::resolve(&transcript_config.unwrap_or_default());
+    Ok(build_usage_report(
+        &store,
+        &pricing,
+        month.as_deref(),
+        from,
+        to,
+    ))
+}
+
+#[tauri::command]
+fn get_transcription_latency() -> Option<LatencyReport> {
+    info!("get_transcription_latency invoked");
+    current_latency_report()
+}
+
+#[tauri::command]
+fn list_transcript_sessions(
+    app: tauri::AppHandle,
+) -> Result<Vec<TranscriptSessionSummary>, String> {
+    info!("list_transcript_sessions invoked");
+    list_sessions(&history_dir(&app)?)
+}
+
+#[tauri::command]
+fn load_transcript_session(
+    app: tauri::AppHandle,
+    session_id: String,
+) -> Result<TranscriptSession, String> {
+    info!(
+        "load_transcript_session invoked for session_id={}",
+        session_id
+    );
+    load_session(&history_dir(&app)?, &session_id)
+}
+
+#[tauri::command]
+fn search_transcript_history(
+    app: tauri::AppHandle,
+    query: String,
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptSearchHit>, String> {
+    info!("search_transcript_history invoked");
+    let parse_bound = |value: Option<String>, label: &str| {
+        value
+            .map(|value| {
+                value
+                    .parse::<DateTime<Utc>>()
+                    .map_err(|err| format!("{label} 解析失败: {err}"))
+            })
+            .transpose()
+    };
+    let from = parse_bound(from, "from")?;
+    let to = parse_bound(to, "to")?;
+    search_sessions(&history_dir(&app)?, &query, from, to, limit)
+}
+
+#[tauri::command]
+fn delete_transcript_session(app: tauri::AppHandle, session_id: String) -> Result<(), String> {
+    info!(
+        "delete_transcript_session invoked for session_id={}",
+        session_id
+    );
+    delete_session(&history_dir(&app)?, &session_id)
+}
+
+#[tauri::command]
+fn export_transcript_session(
+    app: tauri::AppHandle,
+    session_id: String,
+    format: String,
+) -> Result<TranscriptExport, String> {
+    info!(
+        "export_transcript_session invoked for session_id={} format={}",
+        session_id, format
+    );
+    let session = load_session(&history_dir(&app)?, &session_id)?;
+    export_session(&session, ExportFormat::parse(&format)?)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     reset_app_log_files();
 
-    let mut env_loaded = false;
-    if is_dev() {
+    let env_loaded = if is_dev() {
         let env_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(".env");
         if from_filename(&env_path).is_err() {
             eprintln!("未找到环境变量文件: {:?}", env_path);
+            false
         } else {
-            env_loaded = true;
             dotenv().ok();
+            true
         }
-    } else {
+    } else if cfg!(feature = "embedded-env") {
+        #[cfg(feature = "embedded-env")]
         load_env_variables();
-        env_loaded = true;
-    }
+        true
+    } else {
+        // Release builds rely on the token service or user-supplied keys.
+        println!("发布版未内置供应商密钥，请在设置中填写密钥或配置 ASR_TOKEN_SERVICE_URL 令牌服务");
+        false
+    };
 
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
@@ -240,22 +381,22 @@ pub fn run() {
             open_license_signer,
             toggle_devtools,
             get_provider_env_presets,
+            get_transcript_vendor_capabilities,
+            get_asr_usage,
+            get_transcription_latency,
             chat_with_llm_provider,
-            siliconflow_free,
-            siliconflow_pro,
-            siliconflow_minimax_m2_5,
-            doubao_lite,
-            doubao_pro,
-            doubao_seed_flash,
-            doubao_seed,
-            zhipu,
-            deepseek_api,
-            ali_qwen_2_5,
-            ali_qwen_plus_latest,
-            ali_qwen_max,
+            clear_llm_thread,
+            cancel_llm_request,
+            list_llm_providers,
             get_audio_stream_devices_names,
             start_recognize_audio_stream_from_speaker_loopback,
             stop_recognize_audio_stream_from_speaker_loopback,
+            list_transcript_sessions,
+            load_transcript_session,
+            search_transcript_history,
+            delete_transcript_session,
+            export_transcript_session,
+            auto_answer_stream_ready,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
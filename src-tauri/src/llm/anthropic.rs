@@ -0,0 +1,193 @@
+//! Adapter for the Anthropic Messages API: `x-api-key` auth, the system prompt
+//! as a top-level `system` field, `content_block_delta` stream events and the
+//! API's own error types, mapped onto `ModelError`.
+
+use super::api::{ModelError, ModelRequest, StreamChunk};
+use serde_json::{Value, json};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// Returned when the API is temporarily overloaded.
+const OVERLOADED_STATUS: u16 = 529;
+
+/// Moves system messages out of the list into the top-level `system` field.
+fn split_system(messages: &[Value]) -> (Option<String>, Vec<Value>) {
+    let mut system = Vec::new();
+    let mut rest = Vec::new();
+    for message in messages {
+        if message["role"] == "system" {
+            if let Some(content) = message["content"].as_str() {
+                system.push(content);
+            }
+        } else {
+            rest.push(message.clone());
+        }
+    }
+    ((!system.is_empty()).then(|| system.join("\n\n")), rest)
+}
+
+fn request_body(req: &ModelRequest) -> Value {
+    let (system, messages) = split_system(&req.messages);
+    let mut body = json!({
+        "model": req.model,
+        "messages": messages,
+        "max_tokens": req.max_tokens,
+        "temperature": req.temperature,
+        "stream": true
+    });
+    if let Some(system) = system {
+        body["system"] = json!(system);
+    }
+    body
+}
+
+pub fn build_request(client: &reqwest::Client, req: &ModelRequest) -> reqwest::RequestBuilder {
+    client
+        .post(format!("{}/messages", req.base_url.trim_end_matches('/')))
+        .header("x-api-key", &req.api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .json(&request_body(req))
+}
+
+pub fn status_error(status: reqwest::StatusCode, model_name: &str, body: &str) -> ModelError {
+    match status.as_u16() {
+        401 | 403 => ModelError::Unauthorized(format!(
+            "model={model_name}, HTTP状态码: {status}, 响应体: {body}"
+        )),
+        429 => ModelError::RateLimited,
+        OVERLOADED_STATUS => ModelError::Overloaded,
+        500..=599 => ModelError::InternalServerError,
+        _ => ModelError::InvalidResponse(format!(
+            "model={model_name}, HTTP状态码: {status}, 响应体: {body}"
+        )),
+    }
+}
+
+fn error_event(error: &Value, model_name: &str) -> ModelError {
+    match error["type"].as_str() {
+        Some("overloaded_error") => ModelError::Overloaded,
+        Some("rate_limit_error") => ModelError::RateLimited,
+        Some("api_error") => ModelError::InternalServerError,
+        Some("authentication_error" | "permission_error") => {
+            ModelError::Unauthorized(format!("model={model_name}, {error}"))
+        }
+        _ => ModelError::InvalidResponse(format!("model={model_name}, API错误: {error}")),
+    }
+}
+
+/// Text deltas become chunks, `message_stop` ends the stream and `ping`,
+/// `message_start` and other bookkeeping events are skipped.
+pub fn parse_stream_event(data: &str, model_name: &str) -> Result<StreamChunk, ModelError> {
+    let event: Value =
+        serde_json::from_str(data).map_err(|e| ModelError::JsonParseError(e.to_string()))?;
+
+    match event["type"].as_str() {
+        Some("content_block_delta") if event["delta"]["type"] == "text_delta" => Ok(event["delta"]
+            ["text"]
+            .as_str()
+            .filter(|text| !text.is_empty())
+            .map(|text| StreamChunk::Text(text.to_string()))
+            .unwrap_or(StreamChunk::Skip)),
+        Some("message_stop") => Ok(StreamChunk::Done),
+        Some("error") => Err(error_event(&event["error"], model_name)),
+        _ => Ok(StreamChunk::Skip),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::api::LlmApi;
+
+    fn request(messages: Vec<Value>) -> ModelRequest {
+        ModelRequest {
+            api: LlmApi::Anthropic,
+            model: "claude-test".to_string(),
+            messages,
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            api_key: "sk-ant".to_string(),
+            max_tokens: 1024,
+            temperature: 0.5,
+            enable_thinking: Some(false),
+            proxy: None,
+        }
+    }
+
+    #[test]
+    fn moves_system_prompt_to_top_level() {
+        let body = request_body(&request(vec![
+            json!({"role": "system", "content": "prompt"}),
+            json!({"role": "user", "content": "q1"}),
+            json!({"role": "assistant", "content": "a1"}),
+            json!({"role": "user", "content": "q2"}),
+        ]));
+
+        assert_eq!(body["system"], "prompt");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["max_tokens"], 1024);
+        assert!(body.get("enable_thinking").is_none());
+
+        let body = request_body(&request(vec![json!({"role": "user", "content": "q"})]));
+        assert!(body.get("system").is_none());
+    }
+
+    #[test]
+    fn parses_text_deltas_and_stop() {
+        let delta = r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"你好"}}"#;
+        assert_eq!(
+            parse_stream_event(delta, "m").unwrap(),
+            StreamChunk::Text("你好".to_string())
+        );
+        let thinking = r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"..."}}"#;
+        assert_eq!(
+            parse_stream_event(thinking, "m").unwrap(),
+            StreamChunk::Skip
+        );
+        assert_eq!(
+            parse_stream_event(r#"{"type":"ping"}"#, "m").unwrap(),
+            StreamChunk::Skip
+        );
+        assert_eq!(
+            parse_stream_event(r#"{"type":"message_stop"}"#, "m").unwrap(),
+            StreamChunk::Done
+        );
+        assert!(matches!(
+            parse_stream_event("{", "m"),
+            Err(ModelError::JsonParseError(_))
+        ));
+    }
+
+    #[test]
+    fn maps_error_events_and_status_codes() {
+        let overloaded =
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        assert!(matches!(
+            parse_stream_event(overloaded, "m"),
+            Err(ModelError::Overloaded)
+        ));
+        let invalid =
+            r#"{"type":"error","error":{"type":"invalid_request_error","message":"bad"}}"#;
+        assert!(matches!(
+            parse_stream_event(invalid, "m"),
+            Err(ModelError::InvalidResponse(message)) if message.contains("bad")
+        ));
+
+        let status = |code| reqwest::StatusCode::from_u16(code).unwrap();
+        assert!(matches!(
+            status_error(status(529), "m", ""),
+            ModelError::Overloaded
+        ));
+        assert!(matches!(
+            status_error(status(403), "m", ""),
+            ModelError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            status_error(status(503), "m", ""),
+            ModelError::InternalServerError
+        ));
+        assert!(matches!(
+            status_error(status(400), "m", ""),
+            ModelError::InvalidResponse(_)
+        ));
+    }
+}
@@ -1,17 +1,32 @@
 #![allow(clippy::collapsible_if)]
 
+use super::anthropic;
+use crate::proxy::http_client_builder;
 use crate::utils::write_some_log;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::env;
 use std::str;
 use std::time::Duration;
 use tauri::Emitter;
 use tokio::time;
-use tokio_stream::StreamExt;
+use tokio_stream::{Stream, StreamExt};
 
 const REQUEST_TIMEOUT_SECONDS: u64 = 3;
 
+/// Wire protocol a provider speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmApi {
+    /// `/chat/completions` with OpenAI-style SSE chunks.
+    #[default]
+    OpenAi,
+    /// Anthropic Messages API (`/messages`).
+    Anthropic,
+}
+
 pub struct ModelRequest {
+    pub api: LlmApi,
     pub model: String,
     pub messages: Vec<serde_json::Value>,
     pub base_url: String,
@@ -19,6 +34,8 @@ pub struct ModelRequest {
     pub max_tokens: u32,
     pub temperature: f32,
     pub enable_thinking: Option<bool>,
+    /// Proxy the request goes through; `None` connects directly.
+    pub proxy: Option<String>,
 }
 
 #[derive(Debug)]
@@ -27,10 +44,13 @@ pub enum ModelError {
     InvalidResponse(String),
     Timeout,
     RateLimited,
+    Overloaded,
     Unauthorized(String),
     InternalServerError,
     StreamingError(String),
     JsonParseError(String),
+    /// Failed after text was already streamed, so no other model may take over.
+    Interrupted(String),
 }
 
 impl std::fmt::Display for ModelError {
@@ -40,10 +60,12 @@ impl std::fmt::Display for ModelError {
             ModelError::InvalidResponse(msg) => write!(f, "服务器响应无效: {}", msg),
             ModelError::Timeout => write!(f, "请求或首包超时(3秒)"),
             ModelError::RateLimited => write!(f, "请求频率限制，请稍后重试"),
+            ModelError::Overloaded => write!(f, "服务器过载，请稍后重试"),
             ModelError::Unauthorized(msg) => write!(f, "API密钥无效或未授权: {}", msg),
             ModelError::InternalServerError => write!(f, "服务器内部错误"),
             ModelError::StreamingError(msg) => write!(f, "流式传输错误: {}", msg),
             ModelError::JsonParseError(msg) => write!(f, "JSON解析错误: {}", msg),
+            ModelError::Interrupted(msg) => write!(f, "输出中途中断: {}", msg),
         }
     }
 }
@@ -54,31 +76,17 @@ pub async fn call_model_api(
     request_id: Option<String>,
 ) -> Result<String, ModelError> {
     let model_name = req.model.clone();
-    let client = reqwest::Client::builder()
+    let client = http_client_builder(req.proxy.as_deref())
+        .map_err(ModelError::NetworkError)?
         .connect_timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
         .build()
         .map_err(|e| ModelError::NetworkError(format!("客户端创建失败: {}", e)))?;
 
-    let mut request_body = json!({
-        "model": req.model,
-        "messages": req.messages,
-        "temperature": req.temperature,
-        "max_tokens": req.max_tokens,
-        "stream": true
-    });
-
-    if let Some(enable_thinking) = req.enable_thinking {
-        request_body["enable_thinking"] = json!(enable_thinking);
-    }
-
     // 发送请求并处理基本网络错误
-    let request = client
-        .post(format!(
-            "{}/chat/completions",
-            req.base_url.trim_end_matches('/')
-        ))
-        .header("Authorization", format!("Bearer {}", req.api_key))
-        .json(&request_body);
+    let request = match req.api {
+        LlmApi::OpenAi => build_chat_completion_request(&client, &req),
+        LlmApi::Anthropic => anthropic::build_request(&client, &req),
+    };
 
     let response = time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS), request.send())
         .await
@@ -102,6 +110,9 @@ pub async fn call_model_api(
             .text()
             .await
             .unwrap_or_else(|_| "无法读取错误响应体".to_string());
+        if req.api == LlmApi::Anthropic {
+            return Err(anthropic::status_error(status, &model_name, &error_body));
+        }
         return Err(match status.as_u16() {
             401 => ModelError::Unauthorized(format!(
                 "model={model_name}, HTTP状态码: {status}, 响应体: {error_body}"
@@ -128,13 +139,6 @@ pub async fn call_model_api(
         }
     }
 
-    let mut result = String::new();
-    let mut stream = response.bytes_stream();
-    let mut line_buffer = Vec::new();
-    let mut consecutive_errors = 0;
-    let mut received_first_chunk = false;
-    const MAX_CONSECUTIVE_ERRORS: usize = 5;
-
     // 确定事件名称 - 如果有请求ID则使用带ID的事件名
     let event_name = if let Some(id) = &request_id {
         format!("llm_stream_{}", id)
@@ -142,6 +146,53 @@ pub async fn call_model_api(
         "llm_stream".to_string()
     };
 
+    let mut on_text = |content: &str| {
+        // 发送流式数据到前端，处理发送错误
+        if let Err(e) = app.emit(&event_name, content) {
+            eprintln!("警告: 无法发送流式数据到前端: {}", e);
+            write_some_log(format!(" 无法发送流式数据到前端: {}", e).as_str())
+        }
+    };
+    read_stream(response.bytes_stream(), req.api, &model_name, &mut on_text).await
+}
+
+/// Collects the streamed text, passing each delta to `on_text`. Once any text
+/// has gone out, a failure is `Interrupted`: the caller has already shown
+/// part of this answer, so another model must not continue it.
+async fn read_stream<S, E>(
+    stream: S,
+    api: LlmApi,
+    model_name: &str,
+    on_text: &mut impl FnMut(&str),
+) -> Result<String, ModelError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut result = String::new();
+    match read_stream_into(stream, api, model_name, on_text, &mut result).await {
+        Ok(()) => Ok(result),
+        Err(err) if !result.is_empty() => Err(ModelError::Interrupted(err.to_string())),
+        Err(err) => Err(err),
+    }
+}
+
+async fn read_stream_into<S, E>(
+    mut stream: S,
+    api: LlmApi,
+    model_name: &str,
+    on_text: &mut impl FnMut(&str),
+    result: &mut String,
+) -> Result<(), ModelError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut line_buffer = Vec::new();
+    let mut consecutive_errors = 0;
+    let mut received_first_chunk = false;
+    const MAX_CONSECUTIVE_ERRORS: usize = 5;
+
     loop {
         let item = if !received_first_chunk {
             match time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS), stream.next()).await {
@@ -196,24 +247,28 @@ pub async fn call_model_api(
                         continue;
                     };
 
-                    if data == "[DONE]" {
-                        return Ok(result);
-                    }
+                    let chunk = match api {
+                        LlmApi::OpenAi => parse_chat_completion_event(data, model_name),
+                        LlmApi::Anthropic => anthropic::parse_stream_event(data, model_name),
+                    };
 
-                    match parse_chat_completion_chunk(data, &model_name) {
-                        Ok(Some(content)) => {
+                    match chunk {
+                        Ok(StreamChunk::Done) => return Ok(()),
+                        Ok(StreamChunk::Text(content)) => {
                             consecutive_errors = 0;
                             result.push_str(&content);
-
-                            // 发送流式数据到前端，处理发送错误
-                            if let Err(e) = app.emit(&event_name, content.as_str()) {
-                                eprintln!("警告: 无法发送流式数据到前端: {}", e);
-                                write_some_log(format!(" 无法发送流式数据到前端: {}", e).as_str())
-                            }
+                            on_text(&content);
                         }
-                        Ok(None) => {
+                        Ok(StreamChunk::Skip) => {
                             consecutive_errors = 0;
                         }
+                        // Anthropic `error` events end the stream, so surface them as-is.
+                        Err(e)
+                            if api == LlmApi::Anthropic
+                                && !matches!(e, ModelError::JsonParseError(_)) =>
+                        {
+                            return Err(e);
+                        }
                         Err(e) => {
                             eprintln!("JSON解析警告: {} (数据: {})", e, data);
                             consecutive_errors += 1;
@@ -246,10 +301,52 @@ pub async fn call_model_api(
     if result.is_empty() {
         Err(ModelError::StreamingError("流数据为空".to_string()))
     } else {
-        Ok(result)
+        Ok(())
     }
 }
 
+fn build_chat_completion_request(
+    client: &reqwest::Client,
+    req: &ModelRequest,
+) -> reqwest::RequestBuilder {
+    let mut request_body = json!({
+        "model": req.model,
+        "messages": req.messages,
+        "temperature": req.temperature,
+        "max_tokens": req.max_tokens,
+        "stream": true
+    });
+
+    if let Some(enable_thinking) = req.enable_thinking {
+        request_body["enable_thinking"] = json!(enable_thinking);
+    }
+
+    client
+        .post(format!(
+            "{}/chat/completions",
+            req.base_url.trim_end_matches('/')
+        ))
+        .header("Authorization", format!("Bearer {}", req.api_key))
+        .json(&request_body)
+}
+
+/// One parsed SSE `data:` payload.
+#[derive(Debug, PartialEq)]
+pub enum StreamChunk {
+    Text(String),
+    Skip,
+    Done,
+}
+
+fn parse_chat_completion_event(data: &str, model_name: &str) -> Result<StreamChunk, ModelError> {
+    if data == "[DONE]" {
+        return Ok(StreamChunk::Done);
+    }
+    Ok(parse_chat_completion_chunk(data, model_name)?
+        .map(StreamChunk::Text)
+        .unwrap_or(StreamChunk::Skip))
+}
+
 fn parse_sse_data_line(line: &str) -> Option<&str> {
     if line.is_empty() || line.starts_with(':') {
         return None;
@@ -276,17 +373,57 @@ fn parse_chat_completion_chunk(data: &str, model_name: &str) -> Result<Option<St
         .map(str::to_string))
 }
 
-pub fn get_env_key(key_name: &str) -> String {
-    env::var(key_name).unwrap_or_else(|_| {
-        eprintln!("环境变量 {} 未设置，请设置后重试", key_name);
-        std::process::exit(1);
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn run_stream(
+        api: LlmApi,
+        items: Vec<Result<Bytes, String>>,
+    ) -> (Result<String, ModelError>, String) {
+        let mut shown = String::new();
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(read_stream(
+                tokio_stream::iter(items),
+                api,
+                "test-model",
+                &mut |content: &str| shown.push_str(content),
+            ));
+        (result, shown)
+    }
+
+    #[test]
+    fn failures_after_streamed_text_are_interrupted() {
+        let text = || {
+            Ok(Bytes::from(
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n",
+            ))
+        };
+
+        let (result, shown) = run_stream(
+            LlmApi::OpenAi,
+            vec![text(), Ok(Bytes::from("data: {broken\n".repeat(5)))],
+        );
+        assert_eq!(shown, "Hel");
+        assert!(matches!(result, Err(ModelError::Interrupted(_))));
+
+        let (result, _) = run_stream(
+            LlmApi::OpenAi,
+            std::iter::once(text())
+                .chain((0..5).map(|_| Err("connection reset".to_string())))
+                .collect(),
+        );
+        assert!(matches!(result, Err(ModelError::Interrupted(_))));
+
+        let (result, shown) = run_stream(
+            LlmApi::OpenAi,
+            vec![Ok(Bytes::from("data: {broken\n".repeat(5)))],
+        );
+        assert!(shown.is_empty());
+        assert!(matches!(result, Err(ModelError::JsonParseError(_))));
+    }
+
     #[test]
     fn parse_sse_data_line_accepts_optional_space() {
         assert_eq!(
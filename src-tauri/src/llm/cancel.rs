@@ -0,0 +1,155 @@
+//! Cancellation for in-flight LLM requests, keyed by request id. Aborting the
+//! wrapped future drops the fallback loop, the SSE stream and its connection
+//! at the next poll.
+
+use futures_util::future::{AbortHandle, AbortRegistration, Abortable};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+pub const CANCELLED_MESSAGE: &str = "请求已取消";
+
+static REGISTRY: OnceLock<Mutex<HashMap<String, (u64, AbortHandle)>>> = OnceLock::new();
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmDoneStatus {
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Terminal event on `llm_done_{request_id}`; no stream chunks follow it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmDoneEvent {
+    pub status: LlmDoneStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn registry() -> &'static Mutex<HashMap<String, (u64, AbortHandle)>> {
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Removes the registry entry once the request settles, unless a newer
+/// request has reused the id in the meantime.
+struct RegistryEntry {
+    request_id: String,
+    token: u64,
+}
+
+impl Drop for RegistryEntry {
+    fn drop(&mut self) {
+        let mut registry = registry().lock().unwrap();
+        if registry
+            .get(&self.request_id)
+            .is_some_and(|(token, _)| *token == self.token)
+        {
+            registry.remove(&self.request_id);
+        }
+    }
+}
+
+fn register(request_id: &str) -> (RegistryEntry, AbortRegistration) {
+    let (handle, registration) = AbortHandle::new_pair();
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::SeqCst);
+    registry()
+        .lock()
+        .unwrap()
+        .insert(request_id.to_string(), (token, handle));
+    (
+        RegistryEntry {
+            request_id: request_id.to_string(),
+            token,
+        },
+        registration,
+    )
+}
+
+/// Aborts the request if it is still running; returns whether one was found.
+pub fn cancel_request(request_id: &str) -> bool {
+    let entry = registry().lock().unwrap().remove(request_id);
+    match entry {
+        Some((_, handle)) => {
+            handle.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Runs `future` so that `cancel_request(request_id)` can abort it.
+pub async fn with_cancellation<T>(
+    request_id: &str,
+    future: impl Future<Output = Result<T, String>>,
+) -> (LlmDoneStatus, Result<T, String>) {
+    let (_entry, registration) = register(request_id);
+    match Abortable::new(future, registration).await {
+        Ok(Ok(value)) => (LlmDoneStatus::Completed, Ok(value)),
+        Ok(Err(err)) => (LlmDoneStatus::Failed, Err(err)),
+        Err(_) => (LlmDoneStatus::Cancelled, Err(CANCELLED_MESSAGE.to_string())),
+    }
+}
+
+/// `with_cancellation` plus the terminal `llm_done_{request_id}` event.
+/// Requests without an id cannot be cancelled and emit nothing.
+pub async fn run_cancellable<T>(
+    app: &tauri::AppHandle,
+    request_id: Option<&str>,
+    future: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let Some(request_id) = request_id else {
+        return future.await;
+    };
+
+    let (status, result) = with_cancellation(request_id, future).await;
+    let event = LlmDoneEvent {
+        status,
+        error: result.as_ref().err().cloned(),
+    };
+    if let Err(err) = app.emit(&format!("llm_done_{request_id}"), event) {
+        eprintln!("Failed to emit LLM done event: {err}");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::future::{join, pending, ready};
+
+    #[test]
+    fn cancel_aborts_a_pending_request() {
+        let request = with_cancellation("cancel_test_pending", pending::<Result<(), String>>());
+        let cancel = async { cancel_request("cancel_test_pending") };
+
+        let ((status, result), found) = block_on(join(request, cancel));
+        assert!(found);
+        assert_eq!(status, LlmDoneStatus::Cancelled);
+        assert_eq!(result, Err(CANCELLED_MESSAGE.to_string()));
+        assert!(!cancel_request("cancel_test_pending"));
+    }
+
+    #[test]
+    fn settled_requests_leave_the_registry() {
+        let (status, result) = block_on(with_cancellation(
+            "cancel_test_done",
+            ready(Ok::<_, String>("answer")),
+        ));
+        assert_eq!(status, LlmDoneStatus::Completed);
+        assert_eq!(result, Ok("answer"));
+        assert!(!cancel_request("cancel_test_done"));
+
+        let (status, _) = block_on(with_cancellation(
+            "cancel_test_failed",
+            ready(Err::<(), _>("boom".to_string())),
+        ));
+        assert_eq!(status, LlmDoneStatus::Failed);
+    }
+}
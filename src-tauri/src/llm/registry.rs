@@ -0,0 +1,456 @@
+//! Data-driven LLM provider table. The bundled `providers.json` is merged with
+//! an optional user file (`LLM_PROVIDERS_FILE`, else `llm_providers.json` in
+//! the app config dir): user entries replace bundled ones with the same id and
+//! new ids are appended, so adding a model needs no code change. The merged
+//! table is cached and rebuilt when the user file's path or mtime changes.
+
+use super::api::LlmApi;
+use crate::provider_config::{
+    LLM_PROVIDERS_FILE_ENV_KEYS, LlmRuntimeConfig, resolve_optional_string,
+};
+use rand::{RngExt, rng as thread_rng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use tauri::Manager;
+
+const BUNDLED_PROVIDERS: &str = include_str!("providers.json");
+const USER_PROVIDERS_FILE_NAME: &str = "llm_providers.json";
+
+static CACHE: OnceLock<Mutex<Option<CachedRegistry>>> = OnceLock::new();
+
+/// The user file a cached table was built from, and its mtime at that point.
+struct CachedRegistry {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    registry: Arc<LlmProviderRegistry>,
+}
+
+fn default_prompt_role() -> String {
+    "system".to_string()
+}
+
+fn default_max_tokens() -> u32 {
+    4096
+}
+
+fn default_temperature() -> f32 {
+    0.7
+}
+
+/// One chat endpoint, OpenAI-compatible unless `api` says otherwise. The `*Config` fields name a camelCase
+/// `LlmRuntimeConfig` field that overrides the env keys, which override the
+/// literal default; an empty default makes the value required.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmProviderEntry {
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub api: LlmApi,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub base_url_env: Vec<String>,
+    #[serde(default)]
+    pub base_url_config: Option<String>,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub model_env: Vec<String>,
+    #[serde(default)]
+    pub model_config: Option<String>,
+    /// Several models are tried in turn from a random start instead of `model`,
+    /// and the provider then falls back only within this list.
+    #[serde(default)]
+    pub models: Vec<String>,
+    #[serde(default)]
+    pub api_key_env: Vec<String>,
+    #[serde(default)]
+    pub api_key_config: Option<String>,
+    #[serde(default = "default_prompt_role")]
+    pub prompt_role: String,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    #[serde(default = "default_temperature")]
+    pub temperature: f32,
+    #[serde(default)]
+    pub enable_thinking: Option<bool>,
+    /// Providers sharing a group back each other up, in registry order.
+    #[serde(default)]
+    pub fallback_group: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderFile {
+    providers: Vec<LlmProviderEntry>,
+}
+
+pub(super) struct ResolvedLlmProvider {
+    pub api: LlmApi,
+    pub model: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    pub prompt_role: String,
+    pub enable_thinking: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LlmProviderRegistry {
+    entries: Vec<LlmProviderEntry>,
+}
+
+impl LlmProviderRegistry {
+    pub fn bundled() -> Self {
+        Self {
+            entries: parse_providers(BUNDLED_PROVIDERS).expect("内置大模型供应商表格式错误"),
+        }
+    }
+
+    /// Bundled table plus the user file; a broken user file is reported and skipped.
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        Self::load_from(user_providers_path(app).as_deref())
+    }
+
+    /// Same as `load`, but reuses the last table until the user file changes.
+    pub fn cached(app: &tauri::AppHandle) -> Arc<Self> {
+        Self::cached_from(user_providers_path(app))
+    }
+
+    fn cached_from(path: Option<PathBuf>) -> Arc<Self> {
+        let modified = path
+            .as_deref()
+            .and_then(|path| fs::metadata(path).ok())
+            .and_then(|metadata| metadata.modified().ok());
+        let mut cache = CACHE.get_or_init(|| Mutex::new(None)).lock().unwrap();
+        if let Some(cached) = cache
+            .as_ref()
+            .filter(|cached| cached.path == path && cached.modified == modified)
+        {
+            return cached.registry.clone();
+        }
+
+        let registry = Arc::new(Self::load_from(path.as_deref()));
+        *cache = Some(CachedRegistry {
+            path,
+            modified,
+            registry: registry.clone(),
+        });
+        registry
+    }
+
+    fn load_from(path: Option<&Path>) -> Self {
+        let mut registry = Self::bundled();
+        let Some(path) = path else {
+            return registry;
+        };
+        if !path.exists() {
+            return registry;
+        }
+
+        match fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| parse_providers(&content))
+        {
+            Ok(entries) => registry.merge(entries),
+            Err(err) => eprintln!("读取大模型供应商配置 {} 失败: {err}", path.display()),
+        }
+        registry
+    }
+
+    pub fn merge(&mut self, entries: Vec<LlmProviderEntry>) {
+        for entry in entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|current| current.id == entry.id)
+            {
+                Some(current) => *current = entry,
+                None => self.entries.push(entry),
+            }
+        }
+    }
+
+    pub fn entries(&self) -> &[LlmProviderEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: &str) -> Option<&LlmProviderEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Other members of the provider's group, starting after it and wrapping.
+    pub fn fallback_order(&self, id: &str) -> Vec<&LlmProviderEntry> {
+        let Some(group) = self
+            .get(id)
+            .and_then(|entry| entry.fallback_group.as_deref())
+        else {
+            return Vec::new();
+        };
+        let start = self
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .unwrap_or(0);
+
+        self.entries
+            .iter()
+            .cycle()
+            .skip(start + 1)
+            .take(self.entries.len() - 1)
+            .filter(|entry| entry.fallback_group.as_deref() == Some(group))
+            .collect()
+    }
+}
+
+fn parse_providers(content: &str) -> Result<Vec<LlmProviderEntry>, String> {
+    let file: ProviderFile =
+        serde_json::from_str(content).map_err(|err| format!("大模型供应商配置解析失败: {err}"))?;
+    for entry in &file.providers {
+        if entry.id.trim().is_empty() {
+            return Err("大模型供应商配置缺少 id".to_string());
+        }
+    }
+    Ok(file.providers)
+}
+
+fn user_providers_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    if let Some(path) = resolve_optional_string(None, LLM_PROVIDERS_FILE_ENV_KEYS) {
+        return Some(PathBuf::from(path));
+    }
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(USER_PROVIDERS_FILE_NAME))
+}
+
+fn config_field(config: &serde_json::Value, field: Option<&str>) -> Option<String> {
+    field
+        .and_then(|field| config.get(field))
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+}
+
+fn resolve_field(
+    config: &serde_json::Value,
+    field: Option<&str>,
+    env_keys: &[String],
+    default_value: &str,
+    label: &str,
+) -> Result<String, String> {
+    let env_keys: Vec<&str> = env_keys.iter().map(String::as_str).collect();
+    resolve_optional_string(config_field(config, field).as_deref(), &env_keys)
+        .or_else(|| (!default_value.is_empty()).then(|| default_value.to_string()))
+        .ok_or_else(|| format!("缺少 {label} 配置，请在前端填写或在环境变量中提供"))
+}
+
+impl LlmProviderEntry {
+    fn display_name(&self) -> &str {
+        if self.label.is_empty() {
+            &self.id
+        } else {
+            &self.label
+        }
+    }
+
+    pub fn is_rotating(&self) -> bool {
+        self.models.len() > 1
+    }
+
+    /// Candidate models in the order they should be tried.
+    pub fn models_in_rotation(&self) -> Vec<String> {
+        if !self.is_rotating() {
+            return self.models.first().cloned().into_iter().collect();
+        }
+        let start = thread_rng().random_range(0..self.models.len());
+        self.models
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(self.models.len())
+            .cloned()
+            .collect()
+    }
+
+    /// One resolved attempt per candidate model.
+    pub(super) fn resolve(
+        &self,
+        runtime_config: &LlmRuntimeConfig,
+    ) -> Result<Vec<ResolvedLlmProvider>, String> {
+        let config = serde_json::to_value(runtime_config).unwrap_or_default();
+        let name = self.display_name();
+        let base_url = resolve_field(
+            &config,
+            self.base_url_config.as_deref(),
+            &self.base_url_env,
+            &self.base_url,
+            &format!("{name} Base URL"),
+        )?;
+        let api_key_label = self
+            .api_key_env
+            .first()
+            .cloned()
+            .unwrap_or_else(|| format!("{name} API Key"));
+        let api_key = resolve_field(
+            &config,
+            self.api_key_config.as_deref(),
+            &self.api_key_env,
+            "",
+            &api_key_label,
+        )?;
+        let models = if self.models.is_empty() {
+            vec![resolve_field(
+                &config,
+                self.model_config.as_deref(),
+                &self.model_env,
+                &self.model,
+                &format!("{name} 模型名"),
+            )?]
+        } else {
+            self.models_in_rotation()
+        };
+
+        Ok(models
+            .into_iter()
+            .map(|model| ResolvedLlmProvider {
+                api: self.api,
+                model,
+                base_url: base_url.clone(),
+                api_key: api_key.clone(),
+                max_tokens: self.max_tokens,
+                temperature: self.temperature,
+                prompt_role: self.prompt_role.clone(),
+                enable_thinking: self.enable_thinking,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_keeps_the_builtin_providers() {
+        let registry = LlmProviderRegistry::bundled();
+        let doubao_pro = registry.get("doubao_pro").unwrap();
+        assert_eq!(doubao_pro.prompt_role, "assistant");
+        assert_eq!(doubao_pro.max_tokens, 4096);
+        assert_eq!(registry.get("zhipu").unwrap().temperature, 0.618);
+        assert!(registry.get("siliconflow_pro").unwrap().is_rotating());
+        assert_eq!(registry.get("anthropic").unwrap().api, LlmApi::Anthropic);
+        assert_eq!(registry.get("openai").unwrap().api, LlmApi::OpenAi);
+        assert!(
+            registry
+                .get("doubao_seed")
+                .unwrap()
+                .fallback_group
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn fallback_order_wraps_within_the_group() {
+        let registry = LlmProviderRegistry::bundled();
+        let order: Vec<_> = registry
+            .fallback_order("openai")
+            .into_iter()
+            .map(|entry| entry.id.as_str())
+            .collect();
+        assert_eq!(order.first(), Some(&"gemini"));
+        assert_eq!(order[2], "siliconflow_pro");
+        assert!(!order.contains(&"openai"));
+        assert!(!order.contains(&"doubao_seed"));
+        assert!(registry.fallback_order("doubao_seed").is_empty());
+    }
+
+    #[test]
+    fn user_entries_replace_and_extend_the_table() {
+        let mut registry = LlmProviderRegistry::bundled();
+        let entries = parse_providers(
+            r#"{"providers": [
+                {"id": "deepseek_api", "baseUrl": "https://proxy.example/v1", "model": "deepseek-reasoner", "apiKeyEnv": ["DEEPSEEK_API_KEY"]},
+                {"id": "kimi", "label": "Kimi", "baseUrl": "https://api.moonshot.cn/v1", "model": "kimi-k2", "apiKeyConfig": "kimiApiKey", "fallbackGroup": "default"}
+            ]}"#,
+        )
+        .unwrap();
+        registry.merge(entries);
+
+        let deepseek = registry.get("deepseek_api").unwrap();
+        assert_eq!(deepseek.model, "deepseek-reasoner");
+        assert_eq!(deepseek.prompt_role, "system");
+        assert!(deepseek.fallback_group.is_none());
+        assert_eq!(registry.entries().last().unwrap().id, "kimi");
+        assert!(parse_providers(r#"{"providers": [{"id": " "}]}"#).is_err());
+    }
+
+    #[test]
+    fn cached_table_reloads_when_the_user_file_changes() {
+        let path =
+            std::env::temp_dir().join(format!("llm_providers_test_{}.json", std::process::id()));
+        let write = |model: &str, modified: SystemTime| {
+            fs::write(
+                &path,
+                format!(r#"{{"providers": [{{"id": "kimi", "model": "{model}"}}]}}"#),
+            )
+            .unwrap();
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+
+        write("kimi-k1", SystemTime::UNIX_EPOCH);
+        let first = LlmProviderRegistry::cached_from(Some(path.clone()));
+        assert_eq!(first.get("kimi").unwrap().model, "kimi-k1");
+        assert!(Arc::ptr_eq(
+            &first,
+            &LlmProviderRegistry::cached_from(Some(path.clone()))
+        ));
+
+        write("kimi-k2", SystemTime::now());
+        let reloaded = LlmProviderRegistry::cached_from(Some(path.clone()));
+        assert_eq!(reloaded.get("kimi").unwrap().model, "kimi-k2");
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn resolves_runtime_config_before_env_and_defaults() {
+        let registry = LlmProviderRegistry::bundled();
+        let config = LlmRuntimeConfig {
+            custom_open_ai_api_key: Some("sk-test".to_string()),
+            custom_open_ai_base_url: Some("https://custom.example/v1".to_string()),
+            custom_open_ai_model: Some("my-model".to_string()),
+            ..Default::default()
+        };
+        let resolved = registry
+            .get("custom_openai")
+            .unwrap()
+            .resolve(&config)
+            .unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].model, "my-model");
+        assert_eq!(resolved[0].base_url, "https://custom.example/v1");
+        assert_eq!(resolved[0].api_key, "sk-test");
+
+        let err = registry
+            .get("custom_openai")
+            .unwrap()
+            .resolve(&LlmRuntimeConfig {
+                custom_open_ai_api_key: Some("sk-test".to_string()),
+                ..Default::default()
+            })
+            .err();
+        if std::env::var("CUSTOM_OPENAI_BASE_URL").is_err() {
+            assert!(err.unwrap().contains("Base URL"));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Server-side conversation threads for `chat_with_llm_provider`. A thread
+//! keeps its answered turns in memory; each request replays them, together
+//! with the latest committed transcript lines, trimmed to a token budget.
+
+use crate::provider_config::{
+    CONTEXT_TOKEN_BUDGET_ENV_KEYS, LlmRuntimeConfig, resolve_optional_string,
+};
+use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+const DEFAULT_CONTEXT_TOKEN_BUDGET: usize = 6000;
+/// Role markers and separators each message costs on top of its text.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+const MAX_THREADS: usize = 32;
+const MAX_TURNS_PER_THREAD: usize = 64;
+const MAX_TRANSCRIPT_LINES: usize = 40;
+const TRANSCRIPT_CONTEXT_HEADER: &str = "以下是最近的对话转录，供理解上下文参考：";
+
+static THREADS: OnceLock<Mutex<HashMap<String, ConversationThread>>> = OnceLock::new();
+static TRANSCRIPT_CONTEXT: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationTurn {
+    pub question: String,
+    pub answer: String,
+}
+
+struct ConversationThread {
+    turns: Vec<ConversationTurn>,
+    last_used: Instant,
+}
+
+/// Snapshot of what a thread request may replay, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct ThreadContext {
+    pub turns: Vec<ConversationTurn>,
+    pub transcript: Vec<String>,
+}
+
+fn threads() -> &'static Mutex<HashMap<String, ConversationThread>> {
+    THREADS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn transcript_context() -> &'static Mutex<VecDeque<String>> {
+    TRANSCRIPT_CONTEXT.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+pub fn context_token_budget(runtime_config: &LlmRuntimeConfig) -> usize {
+    resolve_optional_string(
+        runtime_config.context_token_budget.as_deref(),
+        CONTEXT_TOKEN_BUDGET_ENV_KEYS,
+    )
+    .and_then(|value| value.parse::<usize>().ok())
+    .filter(|value| *value > 0)
+    .unwrap_or(DEFAULT_CONTEXT_TOKEN_BUDGET)
+}
+
+pub fn thread_context(thread_id: &str) -> ThreadContext {
+    let turns = threads()
+        .lock()
+        .unwrap()
+        .get(thread_id)
+        .map(|thread| thread.turns.clone())
+        .unwrap_or_default();
+    let transcript = transcript_context()
+        .lock()
+        .unwrap()
+        .iter()
+        .cloned()
+        .collect();
+    ThreadContext { turns, transcript }
+}
+
+/// Stores an answered turn, evicting the least recently used thread when full.
+pub fn record_turn(thread_id: &str, question: &str, answer: &str) {
+    let mut threads = threads().lock().unwrap();
+    if !threads.contains_key(thread_id)
+        && threads.len() >= MAX_THREADS
+        && let Some(oldest) = threads
+            .iter()
+            .min_by_key(|(_, thread)| thread.last_used)
+            .map(|(id, _)| id.clone())
+    {
+        threads.remove(&oldest);
+    }
+
+    let thread = threads
+        .entry(thread_id.to_string())
+        .or_insert_with(|| ConversationThread {
+            turns: Vec::new(),
+            last_used: Instant::now(),
+        });
+    thread.turns.push(ConversationTurn {
+        question: question.to_string(),
+        answer: answer.to_string(),
+    });
+    if thread.turns.len() > MAX_TURNS_PER_THREAD {
+        let excess = thread.turns.len() - MAX_TURNS_PER_THREAD;
+        thread.turns.drain(..excess);
+    }
+    thread.last_used = Instant::now();
+}
+
+pub fn clear_thread(thread_id: &str) {
+    threads().lock().unwrap().remove(thread_id);
+}
+
+/// Keeps the latest committed lines for thread requests; drafts are ignored.
+pub fn note_transcript_context(event: &TranscriptEvent) {
+    if event.kind != TranscriptEventKind::Commit {
+        return;
+    }
+    let text = event.text.trim();
+    if text.is_empty() {
+        return;
+    }
+
+    let mut lines = transcript_context().lock().unwrap();
+    lines.push_back(text.to_string());
+    while lines.len() > MAX_TRANSCRIPT_LINES {
+        lines.pop_front();
+    }
+}
+
+pub fn reset_transcript_context() {
+    transcript_context().lock().unwrap().clear();
+}
+
+fn is_wide_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2E80}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
+/// Rough count without a tokenizer: one token per CJK character and one per
+/// four other characters, which errs on the high side for both.
+pub fn estimate_tokens(text: &str) -> usize {
+    let (wide, narrow) = text.chars().fold((0usize, 0usize), |(wide, narrow), c| {
+        if is_wide_char(c) {
+            (wide + 1, narrow)
+        } else {
+            (wide, narrow + 1)
+        }
+    });
+    wide + narrow.div_ceil(4)
+}
+
+fn message_tokens(text: &str) -> usize {
+    estimate_tokens(text) + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Builds prompt, replayed turns and the question. The prompt and question are
+/// always sent; earlier turns are kept newest first and whole, then whatever
+/// budget remains goes to the newest transcript lines.
+pub fn build_thread_messages(
+    prompt_role: &str,
+    prompt: &str,
+    question: &str,
+    context: &ThreadContext,
+    budget: usize,
+) -> Vec<serde_json::Value> {
+    let mut remaining = budget.saturating_sub(message_tokens(prompt) + message_tokens(question));
+
+    let mut kept_turns = 0;
+    for turn in context.turns.iter().rev() {
+        let cost = message_tokens(&turn.question) + message_tokens(&turn.answer);
+        if cost > remaining {
+            break;
+        }
+        remaining -= cost;
+        kept_turns += 1;
+    }
+
+    let mut kept_lines = 0;
+    remaining = remaining.saturating_sub(estimate_tokens(TRANSCRIPT_CONTEXT_HEADER));
+    for line in context.transcript.iter().rev() {
+        let cost = estimate_tokens(line) + 1;
+        if cost > remaining {
+            break;
+        }
+        remaining -= cost;
+        kept_lines += 1;
+    }
+
+    let transcript = &context.transcript[context.transcript.len() - kept_lines..];
+    let prompt = if transcript.is_empty() {
+        prompt.to_string()
+    } else {
+        format!(
+            "{prompt}\n\n{TRANSCRIPT_CONTEXT_HEADER}\n{}",
+            transcript.join("\n")
+        )
+    };
+
+    let mut messages = vec![json!({"role": prompt_role, "content": prompt})];
+    for turn in &context.turns[context.turns.len() - kept_turns..] {
+        messages.push(json!({"role": "user", "content": turn.question}));
+        messages.push(json!({"role": "assistant", "content": turn.answer}));
+    }
+    messages.push(json!({"role": "user", "content": question}));
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(question: &str, answer: &str) -> ConversationTurn {
+        ConversationTurn {
+            question: question.to_string(),
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn estimates_cjk_and_latin_text() {
+        assert_eq!(estimate_tokens("你好世界"), 4);
+        assert_eq!(estimate_tokens("hello world"), 3);
+        assert_eq!(estimate_tokens(""), 0);
+    }
+
+    #[test]
+    fn replays_turns_in_order_with_transcript_in_prompt() {
+        let context = ThreadContext {
+            turns: vec![turn("q1", "a1"), turn("q2", "a2")],
+            transcript: vec!["line one".to_string(), "line two".to_string()],
+        };
+        let messages = build_thread_messages("system", "prompt", "q3", &context, 1000);
+
+        let roles: Vec<_> = messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            roles,
+            ["system", "user", "assistant", "user", "assistant", "user"]
+        );
+        assert_eq!(messages[1]["content"], "q1");
+        assert_eq!(messages[5]["content"], "q3");
+        let prompt = messages[0]["content"].as_str().unwrap();
+        assert!(prompt.starts_with("prompt\n\n"));
+        assert!(prompt.ends_with("line one\nline two"));
+    }
+
+    #[test]
+    fn trims_oldest_turns_before_the_question() {
+        let long_answer = "答".repeat(100);
+        let context = ThreadContext {
+            turns: vec![turn("old", &long_answer), turn("new", "short")],
+            transcript: vec!["转录".repeat(50)],
+        };
+        let budget = message_tokens("prompt") + message_tokens("next") + 40;
+        let messages = build_thread_messages("system", "prompt", "next", &context, budget);
+
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0]["content"], "prompt");
+        assert_eq!(messages[1]["content"], "new");
+        assert_eq!(messages[3]["content"], "next");
+
+        let messages = build_thread_messages("system", "prompt", "next", &context, 1);
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn records_turns_per_thread_and_clears() {
+        clear_thread("threads_test");
+        record_turn("threads_test", "q1", "a1");
+        record_turn("threads_test", "q2", "a2");
+        assert_eq!(
+            thread_context("threads_test").turns,
+            vec![turn("q1", "a1"), turn("q2", "a2")]
+        );
+        assert!(thread_context("threads_test_other").turns.is_empty());
+
+        clear_thread("threads_test");
+        assert!(thread_context("threads_test").turns.is_empty());
+    }
+}
@@ -3,10 +3,11 @@
 
 use crate::RESAMPLE_RATE;
 use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_latency::note_captured_f32;
+use crate::transcript_vendors::comparison::ComparisonCallback;
+use crate::transcript_vendors::failover::VendorSwitchCallback;
 use crate::transcript_vendors::{
-    PcmCallback, SelectedDeepgramTranscriber, StatusCallback, StreamingTranscriber,
-    TranscriptVendors, assemblyai::AssemblyAiTranscriber, gladia::GladiaTranscriber,
-    revai::RevAiTranscriber, speechmatics::SpeechmaticsTranscriber,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, start_session_transcriber,
 };
 use crate::utils::{is_dev, resample_audio_with_rubato, select_output_config, write_some_log};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
@@ -111,6 +112,8 @@ pub struct RecordParams {
     pub selected_asr_vendor: String,
     pub status_callback: Option<StatusCallback>,
     pub transcript_config: Option<TranscriptRuntimeConfig>,
+    pub vendor_switch_callback: Option<VendorSwitchCallback>,
+    pub comparison_callback: Option<ComparisonCallback>,
 }
 
 pub fn record_audio_worker(mut params: RecordParams) -> Result<(), String> {
@@ -208,63 +211,17 @@ pub fn record_audio_worker(mut params: RecordParams) -> Result<(), String> {
     let callback = params.pcm_callback.clone();
     let status_callback = params.status_callback.clone();
     let transcript_config = params.transcript_config.clone().unwrap_or_default();
-    let asr_transcriber: Option<Arc<dyn StreamingTranscriber>> = match (asr_vendor, callback) {
-        (TranscriptVendors::AssemblyAI, Some(callback)) => {
-            let transcriber = AssemblyAiTranscriber::start(
-                stream_sample_rate,
-                callback,
-                status_callback.clone(),
-                transcript_config.clone(),
-            )
-            .map_err(|e| format!("Failed to start AssemblyAI stream: {e}"))?;
-            let transcriber: Arc<dyn StreamingTranscriber> = Arc::new(transcriber);
-            Some(transcriber)
-        }
-        (TranscriptVendors::RevAI, Some(callback)) => {
-            let transcriber = RevAiTranscriber::start(
-                stream_sample_rate,
-                callback,
-                status_callback.clone(),
-                transcript_config.clone(),
-            )
-            .map_err(|e| format!("Failed to start RevAI stream: {e}"))?;
-            let transcriber: Arc<dyn StreamingTranscriber> = Arc::new(transcriber);
-            Some(transcriber)
-        }
-        (TranscriptVendors::DeepGram, Some(callback)) => {
-            let transcriber = SelectedDeepgramTranscriber::start(
-                stream_sample_rate,
-                callback,
-                status_callback.clone(),
-                transcript_config.clone(),
-            )
-            .map_err(|e| format!("Failed to start Deepgram stream: {e}"))?;
-            let transcriber: Arc<dyn StreamingTranscriber> = Arc::new(transcriber);
-            Some(transcriber)
-        }
-        (TranscriptVendors::SpeechMatics, Some(callback)) => {
-            let transcriber = SpeechmaticsTranscriber::start(
-                stream_sample_rate,
-                callback,
-                status_callback.clone(),
-                transcript_config.clone(),
-            )
-            .map_err(|e| format!("Failed to start Speechmatics stream: {e}"))?;
-            let transcriber: Arc<dyn StreamingTranscriber> = Arc::new(transcriber);
-            Some(transcriber)
-        }
-        (TranscriptVendors::GlaDia, Some(callback)) => {
-            let transcriber = GladiaTranscriber::start(
-                stream_sample_rate,
-                callback,
-                status_callback.clone(),
-                transcript_config.clone(),
-            )
-            .map_err(|e| format!("Failed to start Gladia stream: {e}"))?;
-            let transcriber: Arc<dyn StreamingTranscriber> = Arc::new(transcriber);
-            Some(transcriber)
-        }
-        _ => None,
+    let asr_transcriber: Option<Arc<dyn StreamingTranscriber>> = match callback {
+        Some(callback) => Some(start_session_transcriber(
+            asr_vendor,
+            stream_sample_rate,
+            callback,
+            status_callback.clone(),
+            params.vendor_switch_callback.clone(),
+            params.comparison_callback.clone(),
+            transcript_config,
+        )?),
+        None => None,
     };
     let stream = match config.sample_format() {
         cpal::SampleFormat::I16 => device
@@ -380,6 +337,7 @@ fn handle_input_data<T, U>(
         }
         return;
     }
+    note_captured_f32(&input_mono, input_sample_rate as u32);
     if auto_chunk_buffer {
         if let Some(transcriber) = transcriber.as_ref() {
             let chunk_i16 =
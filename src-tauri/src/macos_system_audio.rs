@@ -2,10 +2,11 @@
 
 use crate::RECORDING;
 use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_latency::note_captured_i16;
+use crate::transcript_vendors::comparison::ComparisonCallback;
+use crate::transcript_vendors::failover::VendorSwitchCallback;
 use crate::transcript_vendors::{
-    PcmCallback, SelectedDeepgramTranscriber, StatusCallback, StreamingTranscriber,
-    TranscriptVendors, assemblyai::AssemblyAiTranscriber, gladia::GladiaTranscriber,
-    revai::RevAiTranscriber, speechmatics::SpeechmaticsTranscriber,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, start_session_transcriber,
 };
 use crate::utils::write_some_log;
 use macos_audio_capture::{
@@ -36,6 +37,8 @@ pub fn start_macos_system_audio_transcription(
     selected_asr_vendor: String,
     pcm_callback: PcmCallback,
     status_callback: Option<StatusCallback>,
+    switch_callback: Option<VendorSwitchCallback>,
+    comparison_callback: Option<ComparisonCallback>,
     transcript_config: Option<TranscriptRuntimeConfig>,
 ) -> Result<JoinHandle<()>, String> {
     let transcript_config = transcript_config.unwrap_or_default();
@@ -45,6 +48,8 @@ pub fn start_macos_system_audio_transcription(
         &selected_asr_vendor,
         pcm_callback,
         status_callback.clone(),
+        switch_callback,
+        comparison_callback,
         transcript_config,
     )?;
 
@@ -72,58 +77,20 @@ fn build_transcriber(
     selected_asr_vendor: &str,
     pcm_callback: PcmCallback,
     status_callback: Option<StatusCallback>,
+    switch_callback: Option<VendorSwitchCallback>,
+    comparison_callback: Option<ComparisonCallback>,
     transcript_config: TranscriptRuntimeConfig,
 ) -> Result<Arc<dyn StreamingTranscriber>, String> {
     let vendor: TranscriptVendors = selected_asr_vendor.parse()?;
-    let transcriber: Arc<dyn StreamingTranscriber> = match vendor {
-        TranscriptVendors::AssemblyAI => Arc::new(
-            AssemblyAiTranscriber::start(
-                MACOS_CAPTURE_SAMPLE_RATE,
-                pcm_callback,
-                status_callback,
-                transcript_config,
-            )
-            .map_err(|err| format!("Failed to start AssemblyAI stream: {err}"))?,
-        ),
-        TranscriptVendors::RevAI => Arc::new(
-            RevAiTranscriber::start(
-                MACOS_CAPTURE_SAMPLE_RATE,
-                pcm_callback,
-                status_callback,
-                transcript_config,
-            )
-            .map_err(|err| format!("Failed to start RevAI stream: {err}"))?,
-        ),
-        TranscriptVendors::DeepGram => Arc::new(
-            SelectedDeepgramTranscriber::start(
-                MACOS_CAPTURE_SAMPLE_RATE,
-                pcm_callback,
-                status_callback,
-                transcript_config,
-            )
-            .map_err(|err| format!("Failed to start Deepgram stream: {err}"))?,
-        ),
-        TranscriptVendors::SpeechMatics => Arc::new(
-            SpeechmaticsTranscriber::start(
-                MACOS_CAPTURE_SAMPLE_RATE,
-                pcm_callback,
-                status_callback,
-                transcript_config,
-            )
-            .map_err(|err| format!("Failed to start Speechmatics stream: {err}"))?,
-        ),
-        TranscriptVendors::GlaDia => Arc::new(
-            GladiaTranscriber::start(
-                MACOS_CAPTURE_SAMPLE_RATE,
-                pcm_callback,
-                status_callback,
-                transcript_config,
-            )
-            .map_err(|err| format!("Failed to start Gladia stream: {err}"))?,
-        ),
-    };
-
-    Ok(transcriber)
+    start_session_transcriber(
+        vendor,
+        MACOS_CAPTURE_SAMPLE_RATE,
+        pcm_callback,
+        status_callback,
+        switch_callback,
+        comparison_callback,
+        transcript_config,
+    )
 }
 
 fn run_capture_loop(
@@ -169,9 +136,11 @@ fn run_capture_loop(
             continue;
         }
 
+        let captured_from = pcm_samples.len();
         for chunk in pcm_bytes[..complete_bytes_len].chunks_exact(2) {
             pcm_samples.push(i16::from_le_bytes([chunk[0], chunk[1]]));
         }
+        note_captured_i16(&pcm_samples[captured_from..], MACOS_CAPTURE_SAMPLE_RATE);
         pcm_bytes.drain(..complete_bytes_len);
 
         while pcm_samples.len() >= chunk_samples {
@@ -10,11 +10,29 @@ pub const DEEPSEEK_ENV_KEYS: &[&str] = &["DEEPSEEK_API_KEY", "DEEPSEEK"];
 pub const ALI_QWEN_ENV_KEYS: &[&str] = &["ALI_QWEN_QWQ_API_KEY", "ALI_QWEN_QWQ"];
 pub const OPENAI_ENV_KEYS: &[&str] = &["OPENAI_API_KEY", "OPENAI"];
 pub const GEMINI_ENV_KEYS: &[&str] = &["GEMINI_API_KEY", "GOOGLE_GENAI_API_KEY"];
+pub const ANTHROPIC_ENV_KEYS: &[&str] = &["ANTHROPIC_API_KEY"];
 pub const ASSEMBLY_ENV_KEYS: &[&str] = &["ASSEMBLY_API_KEY"];
 pub const DEEPGRAM_ENV_KEYS: &[&str] = &["DEEPGRAM_API_KEY"];
 pub const GLADIA_ENV_KEYS: &[&str] = &["GLADIA_API_KEY"];
 pub const SPEECHMATICS_ENV_KEYS: &[&str] = &["SPEECHMATICS_API_KEY"];
 pub const REVAI_ENV_KEYS: &[&str] = &["REVAI_API_KEY"];
+pub const OPENAI_COMPATIBLE_ASR_ENV_KEYS: &[&str] = &["OPENAI_COMPATIBLE_ASR_API_KEY"];
+pub const VOLCENGINE_ASR_APP_ENV_KEYS: &[&str] = &["VOLCENGINE_ASR_APP_ID", "VOLC_ASR_APP_ID"];
+pub const VOLCENGINE_ASR_ACCESS_ENV_KEYS: &[&str] =
+    &["VOLCENGINE_ASR_ACCESS_TOKEN", "VOLC_ASR_ACCESS_TOKEN"];
+pub const PROXY_URL_ENV_KEYS: &[&str] = &[
+    "PROXY_URL",
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+];
+pub const PROXY_OVERRIDES_ENV_KEYS: &[&str] = &["PROXY_OVERRIDES"];
+pub const NO_PROXY_ENV_KEYS: &[&str] = &["NO_PROXY", "no_proxy"];
+pub const LLM_PROVIDERS_FILE_ENV_KEYS: &[&str] = &["LLM_PROVIDERS_FILE"];
+pub const CONTEXT_TOKEN_BUDGET_ENV_KEYS: &[&str] = &["LLM_CONTEXT_TOKEN_BUDGET"];
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,10 +48,19 @@ pub struct LlmRuntimeConfig {
     pub gemini_api_key: Option<String>,
     pub gemini_base_url: Option<String>,
     pub gemini_model: Option<String>,
+    pub anthropic_api_key: Option<String>,
+    pub anthropic_base_url: Option<String>,
+    pub anthropic_model: Option<String>,
     pub custom_open_ai_name: Option<String>,
     pub custom_open_ai_api_key: Option<String>,
     pub custom_open_ai_base_url: Option<String>,
     pub custom_open_ai_model: Option<String>,
+    /// See `proxy::ProxySettings`; shared with the transcript settings.
+    pub proxy_url: Option<String>,
+    pub proxy_overrides: Option<String>,
+    pub no_proxy: Option<String>,
+    /// Token budget for replayed thread history and transcript context.
+    pub context_token_budget: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -41,16 +68,75 @@ pub struct LlmRuntimeConfig {
 pub struct TranscriptRuntimeConfig {
     pub deepgram_api_key: Option<String>,
     pub deepgram_language: Option<String>,
+    pub deepgram_ws_url: Option<String>,
+    /// `api` (raw websocket) or `sdk` (deepgram crate); defaults to the build's feature.
+    pub deepgram_backend: Option<String>,
     pub assembly_api_key: Option<String>,
+    pub assembly_ws_url: Option<String>,
     pub gladia_api_key: Option<String>,
     pub gladia_language: Option<String>,
     pub gladia_model: Option<String>,
+    pub gladia_api_url: Option<String>,
     pub speechmatics_api_key: Option<String>,
     pub speechmatics_language: Option<String>,
     pub speechmatics_rt_url: Option<String>,
     pub revai_api_key: Option<String>,
     pub revai_language: Option<String>,
     pub revai_metadata: Option<String>,
+    pub revai_ws_url: Option<String>,
+    pub revai_custom_vocabulary_id: Option<String>,
+    pub openai_compatible_api_key: Option<String>,
+    pub openai_compatible_base_url: Option<String>,
+    pub openai_compatible_model: Option<String>,
+    pub openai_compatible_language: Option<String>,
+    pub openai_realtime_api_key: Option<String>,
+    pub openai_realtime_base_url: Option<String>,
+    pub openai_realtime_model: Option<String>,
+    pub openai_realtime_language: Option<String>,
+    pub dashscope_api_key: Option<String>,
+    pub dashscope_model: Option<String>,
+    pub dashscope_language: Option<String>,
+    pub dashscope_ws_url: Option<String>,
+    pub volcengine_app_key: Option<String>,
+    pub volcengine_access_key: Option<String>,
+    pub volcengine_resource_id: Option<String>,
+    pub volcengine_ws_url: Option<String>,
+    pub asr_fallback_vendors: Option<String>,
+    /// Token service that mints short-lived vendor tokens; master keys stay there.
+    pub asr_token_service_url: Option<String>,
+    /// Bearer credential the token service expects from this client.
+    pub asr_token_service_auth: Option<String>,
+    /// Exchange the local key for a per-session vendor token before connecting.
+    pub asr_vendor_tokens: Option<bool>,
+    /// Proxy for vendor websockets and HTTP calls; see `proxy::ProxySettings`.
+    pub proxy_url: Option<String>,
+    /// `provider=url` pairs; `direct` bypasses the global proxy.
+    pub proxy_overrides: Option<String>,
+    /// Hosts and domain suffixes that never use a proxy.
+    pub no_proxy: Option<String>,
+    pub asr_comparison_vendors: Option<String>,
+    pub asr_vocabulary: Option<String>,
+    pub asr_language_mode: Option<String>,
+    pub asr_languages: Option<String>,
+    pub translation_mode: Option<String>,
+    pub translation_target_languages: Option<String>,
+    pub translation_llm_provider: Option<String>,
+    pub transcript_chinese_variant: Option<String>,
+    pub transcript_punctuation: Option<String>,
+    pub transcript_punctuation_llm_provider: Option<String>,
+    pub transcript_remove_fillers: Option<bool>,
+    pub transcript_normalize_numbers: Option<bool>,
+    pub transcript_replacements: Option<String>,
+    /// `off`, `event` (emit `auto_question`) or `answer` (also ask the LLM).
+    pub auto_question_mode: Option<String>,
+    pub auto_question_silence_ms: Option<String>,
+    pub auto_question_llm_provider: Option<String>,
+    /// Optional cheap provider that confirms heuristic matches.
+    pub auto_question_classifier_provider: Option<String>,
+    pub auto_question_prompt: Option<String>,
+    pub asr_prices: Option<String>,
+    pub asr_monthly_budget: Option<String>,
+    pub record_draft_history: Option<bool>,
     pub macos_system_audio_backend: Option<String>,
 }
 
@@ -117,27 +203,112 @@ pub fn llm_runtime_config_from_env() -> LlmRuntimeConfig {
         gemini_api_key: resolve_optional_string(None, GEMINI_ENV_KEYS),
         gemini_base_url: resolve_optional_string(None, &["GEMINI_BASE_URL"]),
         gemini_model: resolve_optional_string(None, &["GEMINI_MODEL"]),
+        anthropic_api_key: resolve_optional_string(None, ANTHROPIC_ENV_KEYS),
+        anthropic_base_url: resolve_optional_string(None, &["ANTHROPIC_BASE_URL"]),
+        anthropic_model: resolve_optional_string(None, &["ANTHROPIC_MODEL"]),
         custom_open_ai_name: resolve_optional_string(None, &["CUSTOM_OPENAI_NAME"]),
         custom_open_ai_api_key: resolve_optional_string(None, &["CUSTOM_OPENAI_API_KEY"]),
         custom_open_ai_base_url: resolve_optional_string(None, &["CUSTOM_OPENAI_BASE_URL"]),
         custom_open_ai_model: resolve_optional_string(None, &["CUSTOM_OPENAI_MODEL"]),
+        proxy_url: resolve_optional_string(None, PROXY_URL_ENV_KEYS),
+        proxy_overrides: resolve_optional_string(None, PROXY_OVERRIDES_ENV_KEYS),
+        no_proxy: resolve_optional_string(None, NO_PROXY_ENV_KEYS),
+        context_token_budget: resolve_optional_string(None, CONTEXT_TOKEN_BUDGET_ENV_KEYS),
     }
 }
 
+pub fn resolve_optional_bool(override_value: Option<bool>, env_keys: &[&str]) -> Option<bool> {
+    override_value.or_else(|| {
+        resolve_optional_string(None, env_keys)
+            .map(|value| matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+    })
+}
+
 pub fn transcript_runtime_config_from_env() -> TranscriptRuntimeConfig {
     TranscriptRuntimeConfig {
         deepgram_api_key: resolve_deepgram_api_key(None),
         deepgram_language: resolve_optional_string(None, &["DEEPGRAM_LANGUAGE"]),
+        deepgram_ws_url: resolve_optional_string(None, &["DEEPGRAM_WS_URL"]),
+        deepgram_backend: resolve_optional_string(None, &["DEEPGRAM_BACKEND"]),
         assembly_api_key: resolve_optional_string(None, ASSEMBLY_ENV_KEYS),
+        assembly_ws_url: resolve_optional_string(None, &["ASSEMBLY_WS_URL"]),
         gladia_api_key: resolve_optional_string(None, GLADIA_ENV_KEYS),
         gladia_language: resolve_optional_string(None, &["GLADIA_LANGUAGE"]),
         gladia_model: resolve_optional_string(None, &["GLADIA_MODEL"]),
+        gladia_api_url: resolve_optional_string(None, &["GLADIA_API_URL"]),
         speechmatics_api_key: resolve_optional_string(None, SPEECHMATICS_ENV_KEYS),
         speechmatics_language: resolve_optional_string(None, &["SPEECHMATICS_LANGUAGE"]),
         speechmatics_rt_url: resolve_optional_string(None, &["SPEECHMATICS_RT_URL"]),
         revai_api_key: resolve_optional_string(None, REVAI_ENV_KEYS),
         revai_language: resolve_optional_string(None, &["REVAI_LANGUAGE"]),
         revai_metadata: resolve_optional_string(None, &["REVAI_METADATA"]),
+        revai_ws_url: resolve_optional_string(None, &["REVAI_WS_URL"]),
+        revai_custom_vocabulary_id: resolve_optional_string(None, &["REVAI_CUSTOM_VOCABULARY_ID"]),
+        openai_compatible_api_key: resolve_optional_string(None, OPENAI_COMPATIBLE_ASR_ENV_KEYS),
+        openai_compatible_base_url: resolve_optional_string(
+            None,
+            &["OPENAI_COMPATIBLE_ASR_BASE_URL"],
+        ),
+        openai_compatible_model: resolve_optional_string(None, &["OPENAI_COMPATIBLE_ASR_MODEL"]),
+        openai_compatible_language: resolve_optional_string(
+            None,
+            &["OPENAI_COMPATIBLE_ASR_LANGUAGE"],
+        ),
+        openai_realtime_api_key: resolve_optional_string(None, OPENAI_ENV_KEYS),
+        openai_realtime_base_url: resolve_optional_string(
+            None,
+            &["OPENAI_REALTIME_BASE_URL", "OPENAI_BASE_URL"],
+        ),
+        openai_realtime_model: resolve_optional_string(None, &["OPENAI_REALTIME_MODEL"]),
+        openai_realtime_language: resolve_optional_string(None, &["OPENAI_REALTIME_LANGUAGE"]),
+        dashscope_api_key: resolve_optional_string(None, ALI_QWEN_ENV_KEYS),
+        dashscope_model: resolve_optional_string(None, &["DASHSCOPE_ASR_MODEL"]),
+        dashscope_language: resolve_optional_string(None, &["DASHSCOPE_ASR_LANGUAGE"]),
+        dashscope_ws_url: resolve_optional_string(None, &["DASHSCOPE_WS_URL"]),
+        volcengine_app_key: resolve_optional_string(None, VOLCENGINE_ASR_APP_ENV_KEYS),
+        volcengine_access_key: resolve_optional_string(None, VOLCENGINE_ASR_ACCESS_ENV_KEYS),
+        volcengine_resource_id: resolve_optional_string(None, &["VOLCENGINE_ASR_RESOURCE_ID"]),
+        volcengine_ws_url: resolve_optional_string(None, &["VOLCENGINE_ASR_WS_URL"]),
+        asr_fallback_vendors: resolve_optional_string(None, &["ASR_FALLBACK_VENDORS"]),
+        asr_token_service_url: resolve_optional_string(None, &["ASR_TOKEN_SERVICE_URL"]),
+        asr_token_service_auth: resolve_optional_string(None, &["ASR_TOKEN_SERVICE_AUTH"]),
+        asr_vendor_tokens: resolve_optional_bool(None, &["ASR_VENDOR_TOKENS"]),
+        proxy_url: resolve_optional_string(None, PROXY_URL_ENV_KEYS),
+        proxy_overrides: resolve_optional_string(None, PROXY_OVERRIDES_ENV_KEYS),
+        no_proxy: resolve_optional_string(None, NO_PROXY_ENV_KEYS),
+        asr_comparison_vendors: resolve_optional_string(None, &["ASR_COMPARISON_VENDORS"]),
+        asr_vocabulary: resolve_optional_string(None, &["ASR_VOCABULARY"]),
+        asr_language_mode: resolve_optional_string(None, &["ASR_LANGUAGE_MODE"]),
+        asr_languages: resolve_optional_string(None, &["ASR_LANGUAGES"]),
+        translation_mode: resolve_optional_string(None, &["TRANSLATION_MODE"]),
+        translation_target_languages: resolve_optional_string(
+            None,
+            &["TRANSLATION_TARGET_LANGUAGES"],
+        ),
+        translation_llm_provider: resolve_optional_string(None, &["TRANSLATION_LLM_PROVIDER"]),
+        transcript_chinese_variant: resolve_optional_string(None, &["TRANSCRIPT_CHINESE_VARIANT"]),
+        transcript_punctuation: resolve_optional_string(None, &["TRANSCRIPT_PUNCTUATION"]),
+        transcript_punctuation_llm_provider: resolve_optional_string(
+            None,
+            &["TRANSCRIPT_PUNCTUATION_LLM_PROVIDER"],
+        ),
+        transcript_remove_fillers: resolve_optional_bool(None, &["TRANSCRIPT_REMOVE_FILLERS"]),
+        transcript_normalize_numbers: resolve_optional_bool(
+            None,
+            &["TRANSCRIPT_NORMALIZE_NUMBERS"],
+        ),
+        transcript_replacements: resolve_optional_string(None, &["TRANSCRIPT_REPLACEMENTS"]),
+        auto_question_mode: resolve_optional_string(None, &["AUTO_QUESTION_MODE"]),
+        auto_question_silence_ms: resolve_optional_string(None, &["AUTO_QUESTION_SILENCE_MS"]),
+        auto_question_llm_provider: resolve_optional_string(None, &["AUTO_QUESTION_LLM_PROVIDER"]),
+        auto_question_classifier_provider: resolve_optional_string(
+            None,
+            &["AUTO_QUESTION_CLASSIFIER_PROVIDER"],
+        ),
+        auto_question_prompt: resolve_optional_string(None, &["AUTO_QUESTION_PROMPT"]),
+        asr_prices: resolve_optional_string(None, &["ASR_PRICES"]),
+        asr_monthly_budget: resolve_optional_string(None, &["ASR_MONTHLY_BUDGET"]),
+        record_draft_history: resolve_optional_bool(None, &["RECORD_DRAFT_HISTORY"]),
         macos_system_audio_backend: resolve_optional_string(None, &["MACOS_SYSTEM_AUDIO_BACKEND"]),
     }
 }
@@ -148,3 +319,52 @@ pub fn provider_env_presets_from_env() -> ProviderEnvPresets {
         transcript: transcript_runtime_config_from_env(),
     }
 }
+
+/// DashScope ASR and the Qwen LLM share one Alibaba Cloud key, so an empty
+/// ASR key falls back to the one filled in the LLM settings.
+pub fn share_llm_keys(
+    transcript_config: Option<TranscriptRuntimeConfig>,
+    llm_config: Option<&LlmRuntimeConfig>,
+) -> Option<TranscriptRuntimeConfig> {
+    let llm_key =
+        llm_config.and_then(|config| normalize_optional_string(config.ali_qwen_api_key.as_deref()));
+    let Some(llm_key) = llm_key else {
+        return transcript_config;
+    };
+
+    let mut transcript_config = transcript_config.unwrap_or_default();
+    if normalize_optional_string(transcript_config.dashscope_api_key.as_deref()).is_none() {
+        transcript_config.dashscope_api_key = Some(llm_key);
+    }
+    Some(transcript_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LlmRuntimeConfig, TranscriptRuntimeConfig, share_llm_keys};
+
+    #[test]
+    fn dashscope_key_falls_back_to_qwen_llm_key() {
+        let llm_config = LlmRuntimeConfig {
+            ali_qwen_api_key: Some("sk-qwen".to_string()),
+            ..Default::default()
+        };
+
+        let shared = share_llm_keys(None, Some(&llm_config)).unwrap();
+        assert_eq!(shared.dashscope_api_key.as_deref(), Some("sk-qwen"));
+
+        let blank = TranscriptRuntimeConfig {
+            dashscope_api_key: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let shared = share_llm_keys(Some(blank), Some(&llm_config)).unwrap();
+        assert_eq!(shared.dashscope_api_key.as_deref(), Some("sk-qwen"));
+
+        let own = TranscriptRuntimeConfig {
+            dashscope_api_key: Some("sk-asr".to_string()),
+            ..Default::default()
+        };
+        let shared = share_llm_keys(Some(own), Some(&llm_config)).unwrap();
+        assert_eq!(shared.dashscope_api_key.as_deref(), Some("sk-asr"));
+    }
+}
@@ -0,0 +1,345 @@
+//! Renders a recorded transcript session into subtitle and document formats.
+//! Cue timing prefers the vendor's utterance timing and otherwise falls back
+//! to event arrival offsets recorded by the history store.
+
+use crate::transcript_history::{TranscriptSegment, TranscriptSession};
+use crate::transcript_vendors::TranscriptEventKind;
+use serde::Serialize;
+
+/// Bumped whenever the JSON export layout changes incompatibly.
+pub const EXPORT_JSON_SCHEMA_VERSION: u32 = 1;
+// Cap cues so long silences don't stretch one line.
+const MAX_CUE_MS: u64 = 10_000;
+const MIN_CUE_MS: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Markdown,
+    Text,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 5] = [
+        ExportFormat::Srt,
+        ExportFormat::Vtt,
+        ExportFormat::Markdown,
+        ExportFormat::Text,
+        ExportFormat::Json,
+    ];
+
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "srt" => Ok(ExportFormat::Srt),
+            "vtt" | "webvtt" => Ok(ExportFormat::Vtt),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "txt" | "text" => Ok(ExportFormat::Text),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!("不支持的导出格式: {other}")),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Srt => "srt",
+            ExportFormat::Vtt => "vtt",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Text => "txt",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptExport {
+    pub format: ExportFormat,
+    pub file_name: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptCue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonExport<'a> {
+    schema_version: u32,
+    session: &'a TranscriptSession,
+}
+
+pub fn export_session(
+    session: &TranscriptSession,
+    format: ExportFormat,
+) -> Result<TranscriptExport, String> {
+    let content = match format {
+        ExportFormat::Srt => render_srt(&build_cues(session)),
+        ExportFormat::Vtt => render_vtt(&build_cues(session)),
+        ExportFormat::Markdown => render_markdown(session),
+        ExportFormat::Text => render_text(session),
+        ExportFormat::Json => serde_json::to_string_pretty(&JsonExport {
+            schema_version: EXPORT_JSON_SCHEMA_VERSION,
+            session,
+        })
+        .map_err(|err| format!("导出 JSON 失败: {err}"))?,
+    };
+
+    Ok(TranscriptExport {
+        format,
+        file_name: format!("{}.{}", session.metadata.id, format.extension()),
+        content,
+    })
+}
+
+/// One cue per committed utterance, timed by arrival: a cue starts at the first
+/// draft of the utterance, or where the previous cue ended.
+pub fn build_cues(session: &TranscriptSession) -> Vec<TranscriptCue> {
+    let mut cues = Vec::new();
+    let mut previous_end = 0;
+    let mut first_draft_at = None;
+
+    for segment in &session.segments {
+        match segment.kind {
+            TranscriptEventKind::Draft => {
+                first_draft_at.get_or_insert(segment.offset_ms);
+            }
+            TranscriptEventKind::Commit => {
+                let text = segment.text.trim();
+                if text.is_empty() {
+                    first_draft_at = None;
+                    continue;
+                }
+
+                let (start_ms, end_ms) = cue_bounds(segment, first_draft_at, previous_end);
+                cues.push(TranscriptCue {
+                    start_ms,
+                    end_ms,
+                    text: text.to_string(),
+                });
+                previous_end = end_ms;
+                first_draft_at = None;
+            }
+            TranscriptEventKind::Translation => {}
+        }
+    }
+    cues
+}
+
+fn cue_bounds(
+    segment: &TranscriptSegment,
+    first_draft_at: Option<u64>,
+    previous_end: u64,
+) -> (u64, u64) {
+    let end_ms = segment.offset_ms;
+    let start_ms = first_draft_at
+        .unwrap_or(previous_end)
+        .max(end_ms.saturating_sub(MAX_CUE_MS));
+    (start_ms, end_ms.max(start_ms + MIN_CUE_MS))
+}
+
+pub fn render_srt(cues: &[TranscriptCue]) -> String {
+    cues.iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                index + 1,
+                format_timestamp(cue.start_ms, ','),
+                format_timestamp(cue.end_ms, ','),
+                cue.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn render_vtt(cues: &[TranscriptCue]) -> String {
+    let mut output = String::from("WEBVTT\n");
+    for cue in cues {
+        output.push_str(&format!(
+            "\n{} --> {}\n{}\n",
+            format_timestamp(cue.start_ms, '.'),
+            format_timestamp(cue.end_ms, '.'),
+            // "-->" would end the cue early in WebVTT.
+            cue.text.replace("-->", "->")
+        ));
+    }
+    output
+}
+
+pub fn render_markdown(session: &TranscriptSession) -> String {
+    let metadata = &session.metadata;
+    let mut output = format!("# Transcript {}\n\n", metadata.id);
+    output.push_str(&format!("- Device: {}\n", metadata.device));
+    output.push_str(&format!("- Vendor: {}\n", metadata.vendor));
+    if let Some(language) = &metadata.language {
+        output.push_str(&format!("- Language: {language}\n"));
+    }
+    output.push_str(&format!(
+        "- Started: {}\n",
+        metadata.started_at.to_rfc3339()
+    ));
+    if let Some(ended_at) = session.ended_at {
+        output.push_str(&format!("- Ended: {}\n", ended_at.to_rfc3339()));
+    }
+
+    // The audio source is the only speaker identity we have; vendor switches
+    // mid-session are noted inline so readers know which engine heard what.
+    let speaker = metadata.device.as_str();
+    let mut current_vendor: Option<&str> = None;
+    for cue_source in session
+        .segments
+        .iter()
+        .filter(|segment| segment.kind == TranscriptEventKind::Commit)
+        .filter(|segment| !segment.text.trim().is_empty())
+    {
+        if current_vendor != Some(cue_source.vendor.as_str()) {
+            output.push_str(&format!("\n## {speaker} ({})\n", cue_source.vendor));
+            current_vendor = Some(cue_source.vendor.as_str());
+        }
+        output.push_str(&format!(
+            "\n**[{}]** {}\n",
+            format_clock(cue_source.offset_ms),
+            cue_source.text.trim()
+        ));
+    }
+    output
+}
+
+pub fn render_text(session: &TranscriptSession) -> String {
+    let mut output = session
+        .segments
+        .iter()
+        .filter(|segment| segment.kind == TranscriptEventKind::Commit)
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    output.push('\n');
+    output
+}
+
+fn format_timestamp(ms: u64, fraction_separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        fraction_separator,
+        ms % 1000
+    )
+}
+
+fn format_clock(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExportFormat, build_cues, export_session, render_srt, render_vtt};
+    use crate::transcript_history::{
+        TranscriptSegment, TranscriptSession, TranscriptSessionMetadata,
+    };
+    use crate::transcript_vendors::TranscriptEventKind;
+    use chrono::Utc;
+
+    fn segment(kind: TranscriptEventKind, text: &str, offset_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            kind,
+            vendor: "Deepgram".to_string(),
+            text: text.to_string(),
+            at: Utc::now(),
+            offset_ms,
+            language: None,
+        }
+    }
+
+    fn session(segments: Vec<TranscriptSegment>) -> TranscriptSession {
+        TranscriptSession {
+            metadata: TranscriptSessionMetadata {
+                id: "20260101T000000-0001".to_string(),
+                device: "default".to_string(),
+                vendor: "deepgram".to_string(),
+                language: Some("en".to_string()),
+                started_at: Utc::now(),
+            },
+            ended_at: None,
+            segments,
+        }
+    }
+
+    #[test]
+    fn cues_follow_arrival_offsets() {
+        let cues = build_cues(&session(vec![
+            segment(TranscriptEventKind::Draft, "hello", 1_200),
+            segment(TranscriptEventKind::Commit, "hello world", 2_500),
+            segment(TranscriptEventKind::Commit, "no drafts here", 30_000),
+        ]));
+
+        assert_eq!((cues[0].start_ms, cues[0].end_ms), (1_200, 2_500));
+        // Long silence before the second commit is capped rather than spanned.
+        assert_eq!((cues[1].start_ms, cues[1].end_ms), (20_000, 30_000));
+    }
+
+    #[test]
+    fn renders_srt_and_vtt_timestamps() {
+        let cues = build_cues(&session(vec![segment(
+            TranscriptEventKind::Commit,
+            "What is Kubernetes?",
+            3_723_456,
+        )]));
+
+        assert_eq!(
+            render_srt(&cues),
+            "1\n01:01:53,456 --> 01:02:03,456\nWhat is Kubernetes?\n"
+        );
+        assert!(render_vtt(&cues).starts_with("WEBVTT\n\n01:01:53.456 --> 01:02:03.456\n"));
+    }
+
+    #[test]
+    fn json_export_round_trips_session() {
+        let original = session(vec![
+            segment(TranscriptEventKind::Draft, "what", 100),
+            segment(TranscriptEventKind::Commit, "what is tokio", 900),
+        ]);
+        let export = export_session(&original, ExportFormat::Json).unwrap();
+        assert_eq!(export.file_name, "20260101T000000-0001.json");
+
+        let value: serde_json::Value = serde_json::from_str(&export.content).unwrap();
+        assert_eq!(value["schemaVersion"], 1);
+        let restored: TranscriptSession = serde_json::from_value(value["session"].clone()).unwrap();
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn text_and_markdown_only_include_commits() {
+        let recorded = session(vec![
+            segment(TranscriptEventKind::Draft, "partial", 100),
+            segment(TranscriptEventKind::Commit, "first line", 900),
+            segment(TranscriptEventKind::Commit, "  ", 1_000),
+            segment(TranscriptEventKind::Commit, "second line", 2_000),
+        ]);
+
+        let text = export_session(&recorded, ExportFormat::Text).unwrap();
+        assert_eq!(text.content, "first line\nsecond line\n");
+
+        let markdown = export_session(&recorded, ExportFormat::Markdown).unwrap();
+        assert!(markdown.content.contains("## default (Deepgram)"));
+        assert!(markdown.content.contains("**[00:00:00]** first line"));
+        assert!(!markdown.content.contains("partial"));
+        assert_eq!(ExportFormat::parse("WebVTT").unwrap(), ExportFormat::Vtt);
+        assert!(ExportFormat::parse("docx").is_err());
+    }
+}
@@ -0,0 +1,577 @@
+//! Append-only transcript history. Each recording session is one JSONL file
+//! under the app data dir: a `session` header line, one `segment` line per
+//! transcript event and an `end` line once recording stops.
+
+use crate::provider_config::{
+    TranscriptRuntimeConfig, resolve_optional_bool, resolve_optional_string,
+};
+use crate::transcript_vendors::language::LanguageSetting;
+use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const HISTORY_DIR_NAME: &str = "transcript_history";
+const SESSION_FILE_EXTENSION: &str = "jsonl";
+const PREVIEW_CHARS: usize = 80;
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+static ACTIVE_RECORDER: OnceLock<Mutex<Option<TranscriptHistoryRecorder>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSessionMetadata {
+    pub id: String,
+    pub device: String,
+    pub vendor: String,
+    pub language: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub kind: TranscriptEventKind,
+    pub vendor: String,
+    pub text: String,
+    pub at: DateTime<Utc>,
+    /// Milliseconds since the session started, by arrival time.
+    pub offset_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum HistoryLine {
+    Session(TranscriptSessionMetadata),
+    Segment(TranscriptSegment),
+    End {
+        #[serde(rename = "endedAt")]
+        ended_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSessionSummary {
+    #[serde(flatten)]
+    pub metadata: TranscriptSessionMetadata,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub commit_count: usize,
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSession {
+    #[serde(flatten)]
+    pub metadata: TranscriptSessionMetadata,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSearchHit {
+    pub session: TranscriptSessionMetadata,
+    pub segment: TranscriptSegment,
+}
+
+pub struct TranscriptHistoryRecorder {
+    file: File,
+    started: Instant,
+    record_drafts: bool,
+}
+
+impl TranscriptHistoryRecorder {
+    pub fn start(
+        dir: &Path,
+        metadata: TranscriptSessionMetadata,
+        record_drafts: bool,
+    ) -> Result<Self, String> {
+        validate_session_id(&metadata.id)?;
+        fs::create_dir_all(dir).map_err(|err| format!("创建转录历史目录失败: {err}"))?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(session_file_path(dir, &metadata.id))
+            .map_err(|err| format!("创建转录历史文件失败: {err}"))?;
+
+        let mut recorder = Self {
+            file,
+            started: Instant::now(),
+            record_drafts,
+        };
+        recorder.write_line(&HistoryLine::Session(metadata))?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, event: &TranscriptEvent) -> Result<(), String> {
+        if event.kind == TranscriptEventKind::Draft && !self.record_drafts {
+            return Ok(());
+        }
+
+        self.write_line(&HistoryLine::Segment(TranscriptSegment {
+            kind: event.kind.clone(),
+            vendor: event.vendor.clone(),
+            text: event.text.clone(),
+            at: Utc::now(),
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            language: event.language.clone(),
+        }))
+    }
+
+    pub fn finish(mut self) -> Result<(), String> {
+        self.write_line(&HistoryLine::End {
+            ended_at: Utc::now(),
+        })
+    }
+
+    fn write_line(&mut self, line: &HistoryLine) -> Result<(), String> {
+        let mut encoded = serde_json::to_string(line).map_err(|err| err.to_string())?;
+        encoded.push('\n');
+        // One write per line keeps the file readable if the app dies mid-session.
+        self.file
+            .write_all(encoded.as_bytes())
+            .and_then(|_| self.file.flush())
+            .map_err(|err| format!("写入转录历史失败: {err}"))
+    }
+}
+
+fn active_recorder() -> &'static Mutex<Option<TranscriptHistoryRecorder>> {
+    ACTIVE_RECORDER.get_or_init(|| Mutex::new(None))
+}
+
+/// Starts recording a new session, closing any session left open.
+pub fn begin_history_session(
+    app: &AppHandle,
+    device: &str,
+    vendor: &str,
+    transcript_config: Option<&TranscriptRuntimeConfig>,
+) -> Result<String, String> {
+    let started_at = Utc::now();
+    let metadata = TranscriptSessionMetadata {
+        id: new_session_id(started_at),
+        device: device.to_string(),
+        vendor: vendor.to_string(),
+        language: transcript_config.and_then(|config| session_language(vendor, config)),
+        started_at,
+    };
+    let id = metadata.id.clone();
+    let record_drafts = resolve_optional_bool(
+        transcript_config.and_then(|config| config.record_draft_history),
+        &["RECORD_DRAFT_HISTORY"],
+    )
+    .unwrap_or(false);
+    let recorder = TranscriptHistoryRecorder::start(&history_dir(app)?, metadata, record_drafts)?;
+
+    if let Some(previous) = active_recorder().lock().unwrap().replace(recorder) {
+        previous.finish()?;
+    }
+    Ok(id)
+}
+
+pub fn record_history_event(event: &TranscriptEvent) {
+    let mut guard = active_recorder().lock().unwrap();
+    let Some(recorder) = guard.as_mut() else {
+        return;
+    };
+    if let Err(err) = recorder.record(event) {
+        eprintln!("{err}");
+    }
+}
+
+pub fn end_history_session() {
+    let Some(recorder) = active_recorder().lock().unwrap().take() else {
+        return;
+    };
+    if let Err(err) = recorder.finish() {
+        eprintln!("{err}");
+    }
+}
+
+pub fn history_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("获取应用数据目录失败: {}", err))?;
+    Ok(app_dir.join(HISTORY_DIR_NAME))
+}
+
+pub fn list_sessions(dir: &Path) -> Result<Vec<TranscriptSessionSummary>, String> {
+    Ok(load_sessions(dir)?.into_iter().map(summarize).collect())
+}
+
+/// Every readable session, newest first; unreadable files are logged and skipped.
+fn load_sessions(dir: &Path) -> Result<Vec<TranscriptSession>, String> {
+    let mut sessions = Vec::new();
+    for id in session_ids(dir)? {
+        match load_session(dir, &id) {
+            Ok(session) => sessions.push(session),
+            Err(err) => eprintln!("Skipping transcript history {id}: {err}"),
+        }
+    }
+    sessions.sort_by_key(|session| Reverse(session.metadata.started_at));
+    Ok(sessions)
+}
+
+pub fn load_session(dir: &Path, id: &str) -> Result<TranscriptSession, String> {
+    validate_session_id(id)?;
+    load_session_file(&session_file_path(dir, id))
+}
+
+pub fn load_session_file(path: &Path) -> Result<TranscriptSession, String> {
+    let file = File::open(path).map_err(|err| format!("读取转录历史失败: {err}"))?;
+    let id = path.display();
+
+    let mut metadata = None;
+    let mut ended_at = None;
+    let mut segments = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|err| format!("读取转录历史失败: {err}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A crash can leave a partial last line behind; skip what cannot be parsed.
+        match serde_json::from_str::<HistoryLine>(&line) {
+            Ok(HistoryLine::Session(value)) => metadata = Some(value),
+            Ok(HistoryLine::Segment(segment)) => segments.push(segment),
+            Ok(HistoryLine::End { ended_at: value }) => ended_at = Some(value),
+            Err(err) => eprintln!("Skipping malformed transcript history line in {id}: {err}"),
+        }
+    }
+
+    let metadata = metadata.ok_or_else(|| format!("转录历史 {id} 缺少会话信息"))?;
+    Ok(TranscriptSession {
+        metadata,
+        ended_at,
+        segments,
+    })
+}
+
+/// Case-insensitive keyword search over committed text, newest sessions first.
+pub fn search_sessions(
+    dir: &Path,
+    query: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptSearchHit>, String> {
+    let terms = query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let mut hits = Vec::new();
+    for session in load_sessions(dir)? {
+        for segment in session.segments {
+            if segment.kind != TranscriptEventKind::Commit {
+                continue;
+            }
+            if from.is_some_and(|from| segment.at < from) || to.is_some_and(|to| segment.at > to) {
+                continue;
+            }
+            let text = segment.text.to_lowercase();
+            if terms.iter().all(|term| text.contains(term.as_str())) {
+                hits.push(TranscriptSearchHit {
+                    session: session.metadata.clone(),
+                    segment,
+                });
+                if hits.len() >= limit {
+                    return Ok(hits);
+                }
+            }
+        }
+    }
+    Ok(hits)
+}
+
+pub fn delete_session(dir: &Path, id: &str) -> Result<(), String> {
+    validate_session_id(id)?;
+    fs::remove_file(session_file_path(dir, id)).map_err(|err| format!("删除转录历史失败: {err}"))
+}
+
+fn summarize(session: TranscriptSession) -> TranscriptSessionSummary {
+    let commits = session
+        .segments
+        .iter()
+        .filter(|segment| segment.kind == TranscriptEventKind::Commit)
+        .collect::<Vec<_>>();
+    let preview = commits
+        .first()
+        .map(|segment| segment.text.chars().take(PREVIEW_CHARS).collect())
+        .unwrap_or_default();
+
+    TranscriptSessionSummary {
+        commit_count: commits.len(),
+        metadata: session.metadata,
+        ended_at: session.ended_at,
+        preview,
+    }
+}
+
+fn session_ids(dir: &Path) -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("读取转录历史目录失败: {err}")),
+    };
+
+    Ok(entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == SESSION_FILE_EXTENSION)
+        })
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .filter(|id| validate_session_id(id).is_ok())
+        .collect())
+}
+
+fn session_file_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{SESSION_FILE_EXTENSION}"))
+}
+
+fn new_session_id(started_at: DateTime<Utc>) -> String {
+    format!(
+        "{}-{:04x}",
+        started_at.format("%Y%m%dT%H%M%S"),
+        started_at.timestamp_subsec_nanos() & 0xffff
+    )
+}
+
+/// Ids become file names, so only allow what `new_session_id` produces.
+fn validate_session_id(id: &str) -> Result<(), String> {
+    if !id.is_empty()
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        Ok(())
+    } else {
+        Err(format!("无效的转录会话 ID: {id}"))
+    }
+}
+
+/// The language setting the vendor was started with, as the unified
+/// `ASR_LANGUAGE_MODE` resolves it.
+fn session_language(vendor: &str, config: &TranscriptRuntimeConfig) -> Option<String> {
+    let (vendor_language, env_keys): (Option<&String>, &[&str]) =
+        match vendor.trim().to_ascii_lowercase().as_str() {
+            "deepgram" => (config.deepgram_language.as_ref(), &["DEEPGRAM_LANGUAGE"]),
+            "gladia" => (config.gladia_language.as_ref(), &["GLADIA_LANGUAGE"]),
+            "speechmatics" => (
+                config.speechmatics_language.as_ref(),
+                &["SPEECHMATICS_LANGUAGE"],
+            ),
+            "revai" => (config.revai_language.as_ref(), &["REVAI_LANGUAGE"]),
+            "openai_compatible" => (
+                config.openai_compatible_language.as_ref(),
+                &["OPENAI_COMPATIBLE_ASR_LANGUAGE"],
+            ),
+            "openai_realtime" => (
+                config.openai_realtime_language.as_ref(),
+                &["OPENAI_REALTIME_LANGUAGE"],
+            ),
+            "dashscope" => (
+                config.dashscope_language.as_ref(),
+                &["DASHSCOPE_ASR_LANGUAGE"],
+            ),
+            _ => (None, &[]),
+        };
+    let vendor_language = resolve_optional_string(vendor_language.map(String::as_str), env_keys);
+
+    match LanguageSetting::resolve(config, vendor_language) {
+        LanguageSetting::VendorDefault => None,
+        LanguageSetting::Fixed(language) => Some(language),
+        LanguageSetting::AutoDetect(languages) if languages.is_empty() => Some("auto".to_string()),
+        LanguageSetting::AutoDetect(languages) => Some(format!("auto: {}", languages.join(", "))),
+        LanguageSetting::CodeSwitching(languages) => {
+            Some(format!("multi: {}", languages.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TranscriptHistoryRecorder, TranscriptSessionMetadata, delete_session, list_sessions,
+        load_session, search_sessions, session_language, validate_session_id,
+    };
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind};
+    use chrono::{Duration, Utc};
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn temp_history_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("transcript-history-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn metadata(id: &str) -> TranscriptSessionMetadata {
+        TranscriptSessionMetadata {
+            id: id.to_string(),
+            device: "default".to_string(),
+            vendor: "deepgram".to_string(),
+            language: Some("en".to_string()),
+            started_at: Utc::now(),
+        }
+    }
+
+    fn event(kind: TranscriptEventKind, text: &str) -> TranscriptEvent {
+        TranscriptEvent {
+            vendor: "Deepgram".to_string(),
+            kind,
+            text: text.to_string(),
+            language: None,
+            utterance_id: None,
+        }
+    }
+
+    #[test]
+    fn records_commits_and_skips_drafts_by_default() {
+        let dir = temp_history_dir("record");
+        let mut recorder =
+            TranscriptHistoryRecorder::start(&dir, metadata("session-a"), false).unwrap();
+        recorder
+            .record(&event(TranscriptEventKind::Draft, "how do"))
+            .unwrap();
+        recorder
+            .record(&event(
+                TranscriptEventKind::Commit,
+                "How do we scale Kubernetes?",
+            ))
+            .unwrap();
+        recorder.finish().unwrap();
+
+        let session = load_session(&dir, "session-a").unwrap();
+        assert_eq!(session.metadata.language.as_deref(), Some("en"));
+        assert!(session.ended_at.is_some());
+        assert_eq!(session.segments.len(), 1);
+        assert_eq!(session.segments[0].text, "How do we scale Kubernetes?");
+
+        let summaries = list_sessions(&dir).unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].commit_count, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn search_matches_all_terms_case_insensitively_within_range() {
+        let dir = temp_history_dir("search");
+        let mut recorder =
+            TranscriptHistoryRecorder::start(&dir, metadata("session-b"), false).unwrap();
+        recorder
+            .record(&event(
+                TranscriptEventKind::Commit,
+                "What about kubernetes autoscaling?",
+            ))
+            .unwrap();
+        recorder
+            .record(&event(TranscriptEventKind::Commit, "Let's talk pricing"))
+            .unwrap();
+        recorder.finish().unwrap();
+
+        // A corrupt file is skipped instead of failing the whole search.
+        std::fs::write(dir.join("session-broken.jsonl"), "not json").unwrap();
+
+        let hits = search_sessions(&dir, "Kubernetes AUTOSCALING", None, None, None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session.id, "session-b");
+
+        let future = Utc::now() + Duration::hours(1);
+        assert!(
+            search_sessions(&dir, "kubernetes", Some(future), None, None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            search_sessions(&dir, "  ", None, None, None)
+                .unwrap()
+                .is_empty()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_skips_truncated_trailing_line() {
+        let dir = temp_history_dir("truncated");
+        let mut recorder =
+            TranscriptHistoryRecorder::start(&dir, metadata("session-c"), false).unwrap();
+        recorder
+            .record(&event(TranscriptEventKind::Commit, "first"))
+            .unwrap();
+        drop(recorder);
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.join("session-c.jsonl"))
+            .unwrap();
+        file.write_all(br#"{"type":"segment","kind":"com"#).unwrap();
+
+        let session = load_session(&dir, "session-c").unwrap();
+        assert_eq!(session.segments.len(), 1);
+        assert!(session.ended_at.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn delete_rejects_path_like_ids() {
+        let dir = temp_history_dir("delete");
+        TranscriptHistoryRecorder::start(&dir, metadata("session-d"), false)
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        assert!(validate_session_id("../license").is_err());
+        assert!(delete_session(&dir, "../session-d").is_err());
+        delete_session(&dir, "session-d").unwrap();
+        assert!(list_sessions(&dir).unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn session_language_follows_unified_language_mode() {
+        let config = TranscriptRuntimeConfig {
+            deepgram_language: Some("en".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(session_language("deepgram", &config).as_deref(), Some("en"));
+
+        let config = TranscriptRuntimeConfig {
+            deepgram_language: Some("en".to_string()),
+            asr_language_mode: Some("code-switching".to_string()),
+            asr_languages: Some("zh, en".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            session_language("deepgram", &config).as_deref(),
+            Some("multi: zh, en")
+        );
+
+        let config = TranscriptRuntimeConfig {
+            asr_language_mode: Some("fixed".to_string()),
+            asr_languages: Some("ja".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            session_language("assemblyai", &config).as_deref(),
+            Some("ja")
+        );
+    }
+}
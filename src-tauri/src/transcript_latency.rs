@@ -0,0 +1,454 @@
+//! End-to-end transcript latency. Audio is timestamped when it is captured
+//! and when it reaches the vendor socket, and every Draft/Commit when it is
+//! emitted. Vendors that report audio-time offsets for their finals get their
+//! speech end mapped back to the capture clock; the others fall back to the
+//! last chunk the local speech detector saw.
+
+use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind, TranscriptVendors};
+use log::info;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Capture marks older than this can no longer be correlated.
+const CAPTURE_HISTORY: Duration = Duration::from_secs(120);
+/// A correlated latency above this means the vendor's offsets drifted from
+/// ours, e.g. after failover replayed buffered audio.
+const MAX_PLAUSIBLE_LATENCY: Duration = Duration::from_secs(30);
+const SPEECH_PEAK_THRESHOLD: u16 = 900;
+
+static ACTIVE_TRACKER: OnceLock<Mutex<Option<LatencyTracker>>> = OnceLock::new();
+static LAST_REPORT: OnceLock<Mutex<Option<LatencyReport>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+}
+
+impl LatencyStats {
+    pub(crate) fn from_samples(values: &[u64]) -> Self {
+        Self {
+            samples: values.len(),
+            p50_ms: percentile(values, 50.0),
+            p95_ms: percentile(values, 95.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyReport {
+    pub session_id: String,
+    /// False once the session has ended; the report is then final.
+    pub active: bool,
+    /// Speech start to the first Draft (or Commit) of the utterance.
+    pub first_draft: LatencyStats,
+    /// Speech end to the Commit of the utterance.
+    pub speech_end_to_final: LatencyStats,
+    /// Capture to the vendor socket, i.e. time spent in local queues.
+    pub capture_to_send: LatencyStats,
+    /// Finals timed from the vendor's own audio offsets.
+    pub correlated_finals: usize,
+    /// Finals timed from local speech detection instead.
+    pub estimated_finals: usize,
+}
+
+struct CaptureMark {
+    /// Captured audio position at the end of the chunk, in seconds.
+    end_secs: f64,
+    at: Instant,
+}
+
+#[derive(Default)]
+struct VendorTimeline {
+    /// Captured audio position the vendor's offset 0 corresponds to.
+    base_secs: f64,
+    /// Audio sent so far, keepalives included, which is the vendor's clock.
+    sent_secs: f64,
+    /// Vendor offset each keepalive started at, and its length.
+    keepalives: Vec<(f64, f64)>,
+    /// End of the audio covered by the latest final result.
+    result_end_secs: Option<f64>,
+}
+
+impl VendorTimeline {
+    /// Maps a vendor offset onto the captured audio, skipping keepalive silence.
+    fn captured_position(&self, offset: f64) -> f64 {
+        let keepalive = self
+            .keepalives
+            .iter()
+            .filter(|(start, _)| *start < offset)
+            .map(|(start, secs)| secs.min(offset - start))
+            .sum::<f64>();
+        self.base_secs + offset - keepalive
+    }
+}
+
+pub struct LatencyTracker {
+    session_id: String,
+    captures: VecDeque<CaptureMark>,
+    captured_secs: f64,
+    vendors: HashMap<TranscriptVendors, VendorTimeline>,
+    utterance_started_at: Option<Instant>,
+    last_speech_at: Option<Instant>,
+    draft_seen: bool,
+    first_draft_ms: Vec<u64>,
+    final_ms: Vec<u64>,
+    send_ms: Vec<u64>,
+    correlated_finals: usize,
+}
+
+impl LatencyTracker {
+    pub fn new(session_id: &str) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            captures: VecDeque::new(),
+            captured_secs: 0.0,
+            vendors: HashMap::new(),
+            utterance_started_at: None,
+            last_speech_at: None,
+            draft_seen: false,
+            first_draft_ms: Vec::new(),
+            final_ms: Vec::new(),
+            send_ms: Vec::new(),
+            correlated_finals: 0,
+        }
+    }
+
+    pub fn note_captured(
+        &mut self,
+        samples: usize,
+        sample_rate: u32,
+        has_speech: bool,
+        now: Instant,
+    ) {
+        if samples == 0 || sample_rate == 0 {
+            return;
+        }
+        self.captured_secs += samples as f64 / sample_rate as f64;
+        self.captures.push_back(CaptureMark {
+            end_secs: self.captured_secs,
+            at: now,
+        });
+        while self
+            .captures
+            .front()
+            .is_some_and(|mark| now.saturating_duration_since(mark.at) > CAPTURE_HISTORY)
+        {
+            self.captures.pop_front();
+        }
+
+        if has_speech {
+            self.utterance_started_at.get_or_insert(now);
+            self.last_speech_at = Some(now);
+        }
+    }
+
+    pub fn note_sent(
+        &mut self,
+        vendor: TranscriptVendors,
+        samples: usize,
+        sample_rate: u32,
+        keepalive: bool,
+        now: Instant,
+    ) {
+        if samples == 0 || sample_rate == 0 {
+            return;
+        }
+        let secs = samples as f64 / sample_rate as f64;
+        let captured_secs = self.captured_secs;
+        // The first chunk a vendor gets is the newest captured audio.
+        let timeline = self
+            .vendors
+            .entry(vendor)
+            .or_insert_with(|| VendorTimeline {
+                base_secs: (captured_secs - secs).max(0.0),
+                ..VendorTimeline::default()
+            });
+        if keepalive {
+            timeline.keepalives.push((timeline.sent_secs, secs));
+            timeline.sent_secs += secs;
+            return;
+        }
+
+        timeline.sent_secs += secs;
+        let position = timeline.captured_position(timeline.sent_secs);
+        if let Some(captured_at) = self.captured_at(position) {
+            self.send_ms
+                .push(now.saturating_duration_since(captured_at).as_millis() as u64);
+        }
+    }
+
+    /// Forgets the vendor's clock; the next chunk sent starts a new one.
+    pub fn note_stream_started(&mut self, vendor: TranscriptVendors) {
+        self.vendors.remove(&vendor);
+    }
+
+    /// Records the audio offset, in seconds, where the vendor's latest final ends.
+    pub fn note_result_offset(&mut self, vendor: TranscriptVendors, end_secs: f64) {
+        if let Some(timeline) = self.vendors.get_mut(&vendor) {
+            timeline.result_end_secs = Some(end_secs);
+        }
+    }
+
+    pub fn note_event(&mut self, event: &TranscriptEvent, now: Instant) {
+        if event.kind == TranscriptEventKind::Translation {
+            return;
+        }
+        if !self.draft_seen {
+            self.draft_seen = true;
+            if let Some(started) = self.utterance_started_at {
+                self.first_draft_ms
+                    .push(now.saturating_duration_since(started).as_millis() as u64);
+            }
+        }
+        if event.kind != TranscriptEventKind::Commit {
+            return;
+        }
+
+        let correlated = vendor_from_display_name(&event.vendor)
+            .and_then(|vendor| self.vendors.get_mut(&vendor))
+            .and_then(|timeline| {
+                let end_secs = timeline.result_end_secs.take()?;
+                Some(timeline.captured_position(end_secs))
+            })
+            .and_then(|position| self.captured_at(position))
+            .map(|captured_at| now.saturating_duration_since(captured_at))
+            .filter(|latency| *latency <= MAX_PLAUSIBLE_LATENCY);
+        let estimated = self
+            .utterance_started_at
+            .and(self.last_speech_at)
+            .map(|speech_end| now.saturating_duration_since(speech_end));
+
+        if let Some(latency) = correlated {
+            self.correlated_finals += 1;
+            self.final_ms.push(latency.as_millis() as u64);
+        } else if let Some(latency) = estimated {
+            self.final_ms.push(latency.as_millis() as u64);
+        }
+        self.utterance_started_at = None;
+        self.draft_seen = false;
+    }
+
+    /// When the audio at captured position `position` was captured.
+    fn captured_at(&self, position: f64) -> Option<Instant> {
+        if position > self.captured_secs {
+            return None;
+        }
+        let index = self
+            .captures
+            .partition_point(|mark| mark.end_secs < position);
+        self.captures.get(index).map(|mark| mark.at)
+    }
+
+    pub fn report(&self, active: bool) -> LatencyReport {
+        LatencyReport {
+            session_id: self.session_id.clone(),
+            active,
+            first_draft: LatencyStats::from_samples(&self.first_draft_ms),
+            speech_end_to_final: LatencyStats::from_samples(&self.final_ms),
+            capture_to_send: LatencyStats::from_samples(&self.send_ms),
+            correlated_finals: self.correlated_finals,
+            estimated_finals: self.final_ms.len() - self.correlated_finals,
+        }
+    }
+}
+
+/// Events carry the vendor's display name ("SpeechMatics", "OpenAIRealtime").
+fn vendor_from_display_name(name: &str) -> Option<TranscriptVendors> {
+    let name = name.to_lowercase();
+    TranscriptVendors::ALL
+        .into_iter()
+        .find(|vendor| vendor.as_str().replace('_', "") == name)
+}
+
+/// Nearest-rank percentile.
+fn percentile(values: &[u64], pct: f64) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len()) - 1).copied()
+}
+
+fn active_tracker() -> &'static Mutex<Option<LatencyTracker>> {
+    ACTIVE_TRACKER.get_or_init(|| Mutex::new(None))
+}
+
+fn last_report() -> &'static Mutex<Option<LatencyReport>> {
+    LAST_REPORT.get_or_init(|| Mutex::new(None))
+}
+
+fn with_tracker(update: impl FnOnce(&mut LatencyTracker)) {
+    if let Some(tracker) = active_tracker().lock().unwrap().as_mut() {
+        update(tracker);
+    }
+}
+
+/// Starts timing a recording session, closing any session left open.
+pub fn begin_latency_session(session_id: &str) {
+    end_latency_session();
+    *active_tracker().lock().unwrap() = Some(LatencyTracker::new(session_id));
+}
+
+/// Ends the session and logs its percentiles.
+pub fn end_latency_session() {
+    let Some(tracker) = active_tracker().lock().unwrap().take() else {
+        return;
+    };
+    let report = tracker.report(false);
+    info!(
+        "transcript latency session={} first_draft p50={:?}ms p95={:?}ms (n={}) speech_end_to_final p50={:?}ms p95={:?}ms (n={}, correlated={}) capture_to_send p50={:?}ms p95={:?}ms",
+        report.session_id,
+        report.first_draft.p50_ms,
+        report.first_draft.p95_ms,
+        report.first_draft.samples,
+        report.speech_end_to_final.p50_ms,
+        report.speech_end_to_final.p95_ms,
+        report.speech_end_to_final.samples,
+        report.correlated_finals,
+        report.capture_to_send.p50_ms,
+        report.capture_to_send.p95_ms,
+    );
+    *last_report().lock().unwrap() = Some(report);
+}
+
+/// The running session's numbers, or the last finished session's.
+pub fn current_latency_report() -> Option<LatencyReport> {
+    if let Some(tracker) = active_tracker().lock().unwrap().as_ref() {
+        return Some(tracker.report(true));
+    }
+    last_report().lock().unwrap().clone()
+}
+
+/// Timestamps a captured chunk, called from the capture callback.
+pub fn note_captured_f32(samples: &[f32], sample_rate: u32) {
+    let threshold = SPEECH_PEAK_THRESHOLD as f32 / i16::MAX as f32;
+    let has_speech = samples.iter().any(|sample| sample.abs() >= threshold);
+    let now = Instant::now();
+    with_tracker(|tracker| tracker.note_captured(samples.len(), sample_rate, has_speech, now));
+}
+
+#[cfg(any(target_os = "macos", feature = "asr-bench"))]
+pub fn note_captured_i16(samples: &[i16], sample_rate: u32) {
+    let has_speech = samples
+        .iter()
+        .any(|sample| sample.unsigned_abs() >= SPEECH_PEAK_THRESHOLD);
+    let now = Instant::now();
+    with_tracker(|tracker| tracker.note_captured(samples.len(), sample_rate, has_speech, now));
+}
+
+pub fn note_audio_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    let now = Instant::now();
+    with_tracker(|tracker| tracker.note_sent(vendor, samples, sample_rate, false, now));
+}
+
+pub fn note_keepalive_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    let now = Instant::now();
+    with_tracker(|tracker| tracker.note_sent(vendor, samples, sample_rate, true, now));
+}
+
+pub fn note_stream_started(vendor: TranscriptVendors) {
+    with_tracker(|tracker| tracker.note_stream_started(vendor));
+}
+
+pub fn note_result_offset(vendor: TranscriptVendors, end_secs: f64) {
+    with_tracker(|tracker| tracker.note_result_offset(vendor, end_secs));
+}
+
+pub fn note_transcript_emitted(event: &TranscriptEvent) {
+    let now = Instant::now();
+    with_tracker(|tracker| tracker.note_event(event, now));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LatencyTracker, percentile, vendor_from_display_name};
+    use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind, TranscriptVendors};
+    use std::time::{Duration, Instant};
+
+    fn event(vendor: &str, kind: TranscriptEventKind) -> TranscriptEvent {
+        TranscriptEvent {
+            vendor: vendor.to_string(),
+            kind,
+            text: "hello".to_string(),
+            language: None,
+            utterance_id: None,
+        }
+    }
+
+    #[test]
+    fn vendor_offsets_map_back_to_capture_time() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut tracker = LatencyTracker::new("session");
+        let vendor = TranscriptVendors::SpeechMatics;
+
+        // Ten 100 ms chunks, speech in the first five, each sent 20 ms later.
+        for index in 0..10u64 {
+            tracker.note_captured(1_600, 16_000, index < 5, at(index * 100 + 100));
+            tracker.note_sent(vendor, 1_600, 16_000, false, at(index * 100 + 120));
+        }
+        // A second of keepalive silence shifts the vendor clock, not ours.
+        tracker.note_sent(vendor, 16_000, 16_000, true, at(1_200));
+        tracker.note_captured(1_600, 16_000, true, at(1_300));
+        tracker.note_sent(vendor, 1_600, 16_000, false, at(1_320));
+
+        tracker.note_event(&event("SpeechMatics", TranscriptEventKind::Draft), at(400));
+        // Speech ended at 0.5 s of audio, captured at 500 ms.
+        tracker.note_result_offset(vendor, 0.5);
+        tracker.note_event(&event("SpeechMatics", TranscriptEventKind::Commit), at(900));
+        // Vendor offset 2.05 s is 1.05 s of captured audio once keepalive is skipped.
+        tracker.note_result_offset(vendor, 2.05);
+        tracker.note_event(
+            &event("SpeechMatics", TranscriptEventKind::Commit),
+            at(1_700),
+        );
+
+        let report = tracker.report(true);
+        assert_eq!(report.first_draft.p50_ms, Some(300));
+        assert_eq!(report.correlated_finals, 2);
+        assert_eq!(report.estimated_finals, 0);
+        assert_eq!(report.speech_end_to_final.p50_ms, Some(400));
+        assert_eq!(report.speech_end_to_final.p95_ms, Some(400));
+        assert_eq!(report.capture_to_send.p50_ms, Some(20));
+    }
+
+    #[test]
+    fn finals_without_offsets_fall_back_to_local_speech_end() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut tracker = LatencyTracker::new("session");
+
+        tracker.note_captured(1_600, 16_000, true, at(100));
+        tracker.note_captured(1_600, 16_000, true, at(200));
+        tracker.note_captured(1_600, 16_000, false, at(300));
+        tracker.note_event(&event("RevAI", TranscriptEventKind::Commit), at(950));
+        // No speech since the commit, so nothing to time.
+        tracker.note_event(&event("RevAI", TranscriptEventKind::Commit), at(1_500));
+
+        let report = tracker.report(false);
+        assert_eq!(report.correlated_finals, 0);
+        assert_eq!(report.estimated_finals, 1);
+        assert_eq!(report.speech_end_to_final.p50_ms, Some(750));
+        assert_eq!(report.first_draft.p50_ms, Some(850));
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let values = (1..=20).collect::<Vec<u64>>();
+        assert_eq!(percentile(&values, 50.0), Some(10));
+        assert_eq!(percentile(&values, 95.0), Some(19));
+        assert_eq!(percentile(&[], 95.0), None);
+        assert_eq!(
+            vendor_from_display_name("OpenAIRealtime"),
+            Some(TranscriptVendors::OpenAiRealtime)
+        );
+    }
+}
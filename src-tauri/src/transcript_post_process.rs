@@ -0,0 +1,992 @@
+//! Post-processing chain applied to transcript events before they are
+//! emitted. Stages run in a fixed order: Chinese script conversion, filler
+//! removal, number normalization, the user's find/replace dictionary and
+//! finally punctuation restoration (commits only).
+
+use crate::llm::{FlowArgs, complete_with_llm_provider};
+use crate::provider_config::{
+    LlmRuntimeConfig, TranscriptRuntimeConfig, resolve_optional_bool, resolve_optional_string,
+    resolve_string_or_default,
+};
+use crate::transcript_vendors::{PcmCallback, TranscriptEvent, TranscriptEventKind};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::thread;
+use tauri::AppHandle;
+use zhconv::{Variant, zhconv};
+
+const DEFAULT_PUNCTUATION_LLM_PROVIDER: &str = "deepseek_api";
+const PUNCTUATION_PROMPT: &str = "Restore punctuation and capitalization in the user's speech transcript. \
+Do not add, remove, reorder or translate any words. Reply with the punctuated text only.";
+const ENGLISH_FILLERS: [&str; 10] = [
+    "um", "umm", "uh", "uhh", "uhm", "erm", "er", "hmm", "hm", "mhm",
+];
+// Only fillers that are never part of a real word; 那个/就是 are left alone.
+const CHINESE_FILLERS: [char; 3] = ['嗯', '呃', '唔'];
+const QUESTION_ENDINGS: [char; 3] = ['吗', '呢', '么'];
+const QUESTION_STARTERS: [&str; 18] = [
+    "what", "why", "how", "when", "where", "who", "which", "whose", "is", "are", "do", "does",
+    "did", "can", "could", "would", "should", "will",
+];
+/// Measure words that mark a Chinese numeral as a quantity worth converting.
+const CHINESE_MEASURES: [&str; 20] = [
+    "年", "个月", "月", "日", "号", "岁", "元", "块", "次", "倍", "秒", "分钟", "小时", "天", "周",
+    "点", "人", "行", "页", "米",
+];
+
+pub type LlmPunctuator = Arc<dyn Fn(&str) -> Result<String, String> + Send + Sync + 'static>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunctuationMode {
+    Off,
+    Rule,
+    /// LLM restoration with the rule-based stage as fallback.
+    Llm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub from: String,
+    pub to: String,
+}
+
+pub enum PostProcessStage {
+    ChineseConversion(Variant),
+    RemoveFillers,
+    NormalizeNumbers,
+    Replace(Vec<Replacement>),
+    RulePunctuation,
+    LlmPunctuation(LlmPunctuator),
+}
+
+pub struct PostProcessChain {
+    stages: Vec<PostProcessStage>,
+}
+
+impl PostProcessChain {
+    pub fn from_config(
+        transcript_config: &TranscriptRuntimeConfig,
+        llm_punctuator: Option<LlmPunctuator>,
+    ) -> Self {
+        let mut stages = Vec::new();
+
+        if let Some(variant) = resolve_optional_string(
+            transcript_config.transcript_chinese_variant.as_deref(),
+            &["TRANSCRIPT_CHINESE_VARIANT"],
+        )
+        .and_then(|value| parse_chinese_variant(&value))
+        {
+            stages.push(PostProcessStage::ChineseConversion(variant));
+        }
+        if resolve_optional_bool(
+            transcript_config.transcript_remove_fillers,
+            &["TRANSCRIPT_REMOVE_FILLERS"],
+        )
+        .unwrap_or(false)
+        {
+            stages.push(PostProcessStage::RemoveFillers);
+        }
+        if resolve_optional_bool(
+            transcript_config.transcript_normalize_numbers,
+            &["TRANSCRIPT_NORMALIZE_NUMBERS"],
+        )
+        .unwrap_or(false)
+        {
+            stages.push(PostProcessStage::NormalizeNumbers);
+        }
+        let replacements = resolve_optional_string(
+            transcript_config.transcript_replacements.as_deref(),
+            &["TRANSCRIPT_REPLACEMENTS"],
+        )
+        .map(|raw| parse_replacements(&raw))
+        .unwrap_or_default();
+        if !replacements.is_empty() {
+            stages.push(PostProcessStage::Replace(replacements));
+        }
+        match (punctuation_mode(transcript_config), llm_punctuator) {
+            (PunctuationMode::Off, _) => {}
+            (PunctuationMode::Llm, Some(punctuator)) => {
+                stages.push(PostProcessStage::LlmPunctuation(punctuator));
+            }
+            (PunctuationMode::Rule, _) | (PunctuationMode::Llm, None) => {
+                stages.push(PostProcessStage::RulePunctuation);
+            }
+        }
+
+        Self { stages }
+    }
+
+    pub fn with_stages(stages: Vec<PostProcessStage>) -> Self {
+        Self { stages }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Drafts skip punctuation since they are rewritten until the commit.
+    /// Returns `None` when nothing is left of the text.
+    pub fn apply(&self, mut event: TranscriptEvent) -> Option<TranscriptEvent> {
+        if event.kind == TranscriptEventKind::Translation {
+            return Some(event);
+        }
+
+        let is_commit = event.kind == TranscriptEventKind::Commit;
+        let mut text = event.text;
+        for stage in &self.stages {
+            text = match stage {
+                PostProcessStage::ChineseConversion(variant) => zhconv(&text, *variant),
+                PostProcessStage::RemoveFillers => remove_fillers(&text),
+                PostProcessStage::NormalizeNumbers => normalize_numbers(&text),
+                PostProcessStage::Replace(replacements) => apply_replacements(&text, replacements),
+                PostProcessStage::RulePunctuation if is_commit => restore_punctuation(&text),
+                PostProcessStage::LlmPunctuation(punctuator) if is_commit => {
+                    llm_punctuation(&text, punctuator)
+                }
+                PostProcessStage::RulePunctuation | PostProcessStage::LlmPunctuation(_) => text,
+            };
+        }
+
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        event.text = text.to_string();
+        Some(event)
+    }
+
+    /// LLM punctuation blocks, so commits then go through a worker thread to
+    /// keep vendor sockets responsive. Drafts never reach the LLM and are
+    /// emitted right away unless a commit is still queued, in which case they
+    /// wait behind it so listeners see events in vendor order.
+    pub fn wrap_callback(self, callback: PcmCallback) -> PcmCallback {
+        let needs_worker = self
+            .stages
+            .iter()
+            .any(|stage| matches!(stage, PostProcessStage::LlmPunctuation(_)));
+        if !needs_worker {
+            return Arc::new(move |event: TranscriptEvent| {
+                if let Some(event) = self.apply(event) {
+                    callback(event);
+                }
+            });
+        }
+
+        let chain = Arc::new(self);
+        // Events handed to the worker and not yet emitted.
+        let queued = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel::<TranscriptEvent>();
+        {
+            let chain = chain.clone();
+            let callback = callback.clone();
+            let queued = queued.clone();
+            thread::spawn(move || {
+                for event in receiver {
+                    if let Some(event) = chain.apply(event) {
+                        callback(event);
+                    }
+                    queued.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+        Arc::new(move |event: TranscriptEvent| {
+            if event.kind == TranscriptEventKind::Draft && queued.load(Ordering::SeqCst) == 0 {
+                if let Some(event) = chain.apply(event) {
+                    callback(event);
+                }
+                return;
+            }
+            queued.fetch_add(1, Ordering::SeqCst);
+            if sender.send(event).is_err() {
+                queued.fetch_sub(1, Ordering::SeqCst);
+                eprintln!("Transcript post-processing worker stopped, dropping event");
+            }
+        })
+    }
+}
+
+fn punctuation_mode(transcript_config: &TranscriptRuntimeConfig) -> PunctuationMode {
+    match resolve_optional_string(
+        transcript_config.transcript_punctuation.as_deref(),
+        &["TRANSCRIPT_PUNCTUATION"],
+    )
+    .map(|value| value.to_ascii_lowercase())
+    .as_deref()
+    {
+        None | Some("off") | Some("none") => PunctuationMode::Off,
+        Some("rule") | Some("rules") => PunctuationMode::Rule,
+        Some("llm") => PunctuationMode::Llm,
+        Some(other) => {
+            eprintln!("Unknown TRANSCRIPT_PUNCTUATION {other:?}, using rule-based punctuation");
+            PunctuationMode::Rule
+        }
+    }
+}
+
+pub fn parse_chinese_variant(value: &str) -> Option<Variant> {
+    match value.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+        "" | "off" | "none" => None,
+        "zh-cn" | "cn" | "t2s" => Some(Variant::ZhCN),
+        "zh-hans" | "simplified" => Some(Variant::ZhHans),
+        "zh-tw" | "tw" | "s2t" => Some(Variant::ZhTW),
+        "zh-hk" | "hk" => Some(Variant::ZhHK),
+        "zh-hant" | "traditional" => Some(Variant::ZhHant),
+        other => {
+            eprintln!("Unknown TRANSCRIPT_CHINESE_VARIANT {other:?}, conversion disabled");
+            None
+        }
+    }
+}
+
+/// One `from => to` (or `from=to`) pair per line or `;`; an empty `to` deletes.
+pub fn parse_replacements(raw: &str) -> Vec<Replacement> {
+    raw.split(['\n', ';'])
+        .filter_map(|entry| {
+            let (from, to) = entry.split_once("=>").or_else(|| entry.split_once('='))?;
+            let from = from.trim();
+            (!from.is_empty()).then(|| Replacement {
+                from: from.to_string(),
+                to: to.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// ASCII patterns match case-insensitively on word boundaries so "k8s" does
+/// not rewrite "k8ss"; anything else is replaced literally. Deleting a word
+/// also drops one of the spaces around it.
+fn apply_replacements(text: &str, replacements: &[Replacement]) -> String {
+    let mut output = text.to_string();
+    for replacement in replacements {
+        if !replacement.from.is_ascii() {
+            output = output.replace(&replacement.from, &replacement.to);
+            continue;
+        }
+
+        let lower = output.to_ascii_lowercase();
+        let pattern = replacement.from.to_ascii_lowercase();
+        let mut result = String::with_capacity(output.len());
+        let mut cursor = 0;
+        while let Some(found) = lower[cursor..].find(&pattern) {
+            let start = cursor + found;
+            let end = start + pattern.len();
+            let bounded = !is_word_char_before(&output, start) && !is_word_char_at(&output, end);
+            result.push_str(&output[cursor..start]);
+            cursor = end;
+            if !bounded {
+                result.push_str(&output[start..end]);
+            } else if !replacement.to.is_empty() {
+                result.push_str(&replacement.to);
+            } else if output[end..].starts_with(' ') && (result.is_empty() || result.ends_with(' '))
+            {
+                cursor += 1;
+            } else if end == output.len() {
+                result.truncate(result.trim_end_matches(' ').len());
+            }
+        }
+        result.push_str(&output[cursor..]);
+        output = result;
+    }
+    output
+}
+
+fn is_word_char_before(text: &str, index: usize) -> bool {
+    text[..index]
+        .chars()
+        .next_back()
+        .is_some_and(|ch| ch.is_ascii_alphanumeric())
+}
+
+fn is_word_char_at(text: &str, index: usize) -> bool {
+    text[index..]
+        .chars()
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphanumeric())
+}
+
+fn remove_fillers(text: &str) -> String {
+    // Chinese fillers go together with the pause mark that follows them.
+    let mut cleaned = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if CHINESE_FILLERS.contains(&ch) {
+            while chars
+                .next_if(|next| matches!(next, '，' | ',' | '、'))
+                .is_some()
+            {}
+            continue;
+        }
+        cleaned.push(ch);
+    }
+
+    let tokens = cleaned.split(' ').collect::<Vec<_>>();
+    let mut kept: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut capitalize_next = false;
+    for (index, token) in tokens.iter().enumerate() {
+        let core = token.trim_end_matches(['.', ',', '?', '!']);
+        if ENGLISH_FILLERS.contains(&core.to_ascii_lowercase().as_str()) {
+            capitalize_next |= index == 0 && core.starts_with(|ch: char| ch.is_uppercase());
+            // Keep sentence-final punctuation on the previous word.
+            if let (Some(last), Some(mark)) = (kept.last_mut(), token.chars().last())
+                && matches!(mark, '.' | '?' | '!')
+            {
+                last.push(mark);
+            }
+            continue;
+        }
+        if capitalize_next && !token.is_empty() {
+            kept.push(capitalize_first(token));
+            capitalize_next = false;
+        } else {
+            kept.push(token.to_string());
+        }
+    }
+
+    kept.join(" ")
+        .trim_start_matches(['，', ',', '、', ' '])
+        .to_string()
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Appends sentence-final punctuation and capitalizes Latin text.
+pub fn restore_punctuation(text: &str) -> String {
+    let text = text.trim();
+    let Some(last) = text.chars().last() else {
+        return String::new();
+    };
+    let mut output = if text.starts_with(|ch: char| ch.is_ascii_lowercase()) {
+        capitalize_first(text)
+    } else {
+        text.to_string()
+    };
+    if last.is_alphanumeric() {
+        let cjk = is_cjk(last);
+        let question = if cjk {
+            QUESTION_ENDINGS.contains(&last)
+        } else {
+            text.split_whitespace()
+                .next()
+                .is_some_and(|word| QUESTION_STARTERS.contains(&word.to_ascii_lowercase().as_str()))
+        };
+        output.push(match (cjk, question) {
+            (true, true) => '？',
+            (true, false) => '。',
+            (false, true) => '?',
+            (false, false) => '.',
+        });
+    }
+    output
+}
+
+fn llm_punctuation(text: &str, punctuator: &LlmPunctuator) -> String {
+    match punctuator(text) {
+        Ok(punctuated) if same_words(text, &punctuated) => punctuated,
+        Ok(punctuated) => {
+            eprintln!("LLM punctuation changed the words, using rule-based fallback: {punctuated}");
+            restore_punctuation(text)
+        }
+        Err(err) => {
+            eprintln!("LLM punctuation failed, using rule-based fallback: {err}");
+            restore_punctuation(text)
+        }
+    }
+}
+
+/// The LLM may only touch punctuation, spacing and letter case.
+fn same_words(original: &str, punctuated: &str) -> bool {
+    let letters = |text: &str| {
+        text.chars()
+            .filter(|ch| ch.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    letters(original) == letters(punctuated)
+}
+
+/// Numbers from ten upwards become digits; smaller ones stay as words, as
+/// most style guides prefer in running text.
+pub fn normalize_numbers(text: &str) -> String {
+    normalize_english_numbers(&normalize_chinese_numbers(text))
+}
+
+fn chinese_digit(ch: char) -> Option<u64> {
+    Some(match ch {
+        '零' | '〇' => 0,
+        '一' => 1,
+        '二' | '两' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    })
+}
+
+fn chinese_unit(ch: char) -> Option<u64> {
+    Some(match ch {
+        '十' => 10,
+        '百' => 100,
+        '千' => 1_000,
+        '万' => 10_000,
+        '亿' => 100_000_000,
+        _ => return None,
+    })
+}
+
+fn is_chinese_numeral(ch: char) -> bool {
+    chinese_digit(ch).is_some() || chinese_unit(ch).is_some()
+}
+
+/// Parses numerals such as 十二万三千零五; bare digit runs like 二零二四 are
+/// read digit by digit. Values beyond `u64` are left alone.
+fn parse_chinese_number(run: &[char]) -> Option<u64> {
+    if run.iter().all(|ch| chinese_digit(*ch).is_some()) {
+        return run.iter().try_fold(0_u64, |value, ch| {
+            value.checked_mul(10)?.checked_add(chinese_digit(*ch)?)
+        });
+    }
+
+    let (mut total, mut section, mut number) = (0_u64, 0_u64, None::<u64>);
+    for (index, ch) in run.iter().enumerate() {
+        if let Some(digit) = chinese_digit(*ch) {
+            // Two digits in a row only make sense after 零 (一万零五).
+            if number.is_some_and(|previous| previous != 0) {
+                return None;
+            }
+            number = Some(digit);
+            continue;
+        }
+        match chinese_unit(*ch)? {
+            unit @ (10 | 100 | 1_000) => {
+                let multiplier = match number.take() {
+                    Some(value) => value,
+                    None if unit == 10 && index == 0 => 1,
+                    None if unit == 10 && section > 0 => 1,
+                    None => return None,
+                };
+                section = section.checked_add(multiplier.checked_mul(unit)?)?;
+            }
+            10_000 => {
+                let value = section.checked_add(number.take().unwrap_or(0))?;
+                total = total.checked_add(value.checked_mul(10_000)?)?;
+                section = 0;
+            }
+            _ => {
+                total = total
+                    .checked_add(section)?
+                    .checked_add(number.take().unwrap_or(0))?
+                    .checked_mul(100_000_000)?;
+                section = 0;
+            }
+        }
+    }
+    total.checked_add(section)?.checked_add(number.unwrap_or(0))
+}
+
+fn normalize_chinese_numbers(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        let percent = chars[index..].starts_with(&['百', '分', '之']);
+        let start = if percent { index + 3 } else { index };
+        let end = (start..chars.len())
+            .find(|position| !is_chinese_numeral(chars[*position]))
+            .unwrap_or(chars.len());
+        let run = &chars[start..end];
+
+        let converted = (!run.is_empty() && (chinese_digit(run[0]).is_some() || run[0] == '十'))
+            .then(|| parse_chinese_number(run))
+            .flatten()
+            .and_then(|value| {
+                let decimal = decimal_suffix(&chars, end, percent);
+                let rest = chars[end..].iter().collect::<String>();
+                let unit_less_digits = run.iter().all(|ch| chinese_digit(*ch).is_some());
+                let keep = if percent || decimal.is_some() {
+                    true
+                } else if unit_less_digits {
+                    // 二零二四 but not 三三两两.
+                    run.len() >= 2 && run.iter().any(|ch| matches!(ch, '零' | '〇'))
+                } else {
+                    value >= 10
+                        && (run.len() >= 2
+                            || CHINESE_MEASURES
+                                .iter()
+                                .any(|measure| rest.starts_with(measure)))
+                };
+                keep.then_some((value, decimal))
+            });
+
+        match converted {
+            Some((value, decimal)) => {
+                output.push_str(&value.to_string());
+                index = end;
+                if let Some(digits) = decimal {
+                    output.push('.');
+                    output.push_str(&digits);
+                    index += digits.chars().count() + 1;
+                }
+                if percent {
+                    output.push('%');
+                }
+            }
+            // A clock time (三点五十分) or 一点一点 stays whole instead of
+            // converting the part after 点 on its own.
+            None if !run.is_empty() && chars.get(end) == Some(&'点') => {
+                let after = (end + 1..chars.len())
+                    .find(|position| !is_chinese_numeral(chars[*position]))
+                    .unwrap_or(chars.len());
+                output.extend(&chars[index..after]);
+                index = after;
+            }
+            None => {
+                output.push(chars[index]);
+                index += 1;
+            }
+        }
+    }
+    output
+}
+
+/// 点 followed by bare digits: 三点五 → ".5". Times (三点五十分, 三点五分)
+/// and phrases like 一点一点 are not decimals, so the digits must end the
+/// text or clause, come before a measure word, or follow 百分之.
+fn decimal_suffix(chars: &[char], position: usize, percent: bool) -> Option<String> {
+    if chars.get(position) != Some(&'点') {
+        return None;
+    }
+    let digits = chars[position + 1..]
+        .iter()
+        .map_while(|ch| chinese_digit(*ch))
+        .map(|digit| char::from(b'0' + digit as u8))
+        .collect::<String>();
+    if digits.is_empty() {
+        return None;
+    }
+
+    let rest = chars[position + 1 + digits.len()..]
+        .iter()
+        .collect::<String>();
+    let decimal = match rest.chars().next() {
+        Some('分' | '钟' | '十' | '点') => false,
+        _ if percent => true,
+        None => true,
+        Some(next) if !is_cjk(next) => true,
+        Some(_) => CHINESE_MEASURES
+            .iter()
+            .any(|measure| *measure != "点" && rest.starts_with(measure)),
+    };
+    decimal.then_some(digits)
+}
+
+fn english_number_word(word: &str) -> Option<EnglishNumberWord> {
+    const SMALL: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 8] = [
+        "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    if let Some(value) = SMALL.iter().position(|small| *small == word) {
+        return Some(EnglishNumberWord::Small(value as u64));
+    }
+    if let Some(index) = TENS.iter().position(|tens| *tens == word) {
+        return Some(EnglishNumberWord::Tens(20 + index as u64 * 10));
+    }
+    match word {
+        "hundred" => Some(EnglishNumberWord::Hundred),
+        "thousand" => Some(EnglishNumberWord::Scale(1_000)),
+        "million" => Some(EnglishNumberWord::Scale(1_000_000)),
+        "billion" => Some(EnglishNumberWord::Scale(1_000_000_000)),
+        "and" => Some(EnglishNumberWord::And),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnglishNumberWord {
+    Small(u64),
+    Tens(u64),
+    Hundred,
+    Scale(u64),
+    And,
+}
+
+/// The run is well formed but does not fit in a `u64`.
+#[derive(Debug, PartialEq, Eq)]
+struct NumberOverflow;
+
+/// `Ok(None)` when the words do not form one number.
+fn parse_english_number(words: &[EnglishNumberWord]) -> Result<Option<u64>, NumberOverflow> {
+    use EnglishNumberWord::*;
+
+    let (mut total, mut current) = (0_u64, 0_u64);
+    let mut last = None::<EnglishNumberWord>;
+    for word in words {
+        match (*word, last) {
+            (Small(_), Some(Small(_))) | (Tens(_), Some(Small(_) | Tens(_))) => return Ok(None),
+            (Small(value), Some(Tens(_))) if value >= 10 => return Ok(None),
+            (Small(value) | Tens(value), _) => {
+                current = current.checked_add(value).ok_or(NumberOverflow)?;
+            }
+            (Hundred, Some(Small(_) | Tens(_))) => {
+                current = current.checked_mul(100).ok_or(NumberOverflow)?;
+            }
+            (Scale(scale), Some(Small(_) | Tens(_) | Hundred)) => {
+                total = current
+                    .checked_mul(scale)
+                    .and_then(|value| total.checked_add(value))
+                    .ok_or(NumberOverflow)?;
+                current = 0;
+            }
+            (And, Some(Hundred | Scale(_))) => {}
+            _ => return Ok(None),
+        }
+        last = Some(*word);
+    }
+    match last {
+        Some(And) | None => Ok(None),
+        _ => total.checked_add(current).ok_or(NumberOverflow).map(Some),
+    }
+}
+
+fn normalize_english_numbers(text: &str) -> String {
+    let tokens = text.split(' ').collect::<Vec<_>>();
+    let mut output: Vec<String> = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+
+    while index < tokens.len() {
+        // Longest run of number words; punctuation ends the run.
+        let mut words = Vec::new();
+        let mut end = index;
+        while end < tokens.len() {
+            let core = tokens[end].trim_end_matches(['.', ',', '?', '!']);
+            let parts = core
+                .to_ascii_lowercase()
+                .split('-')
+                .map(english_number_word)
+                .collect::<Option<Vec<_>>>();
+            let Some(parts) = parts.filter(|_| !core.is_empty()) else {
+                break;
+            };
+            words.extend(parts);
+            end += 1;
+            if core.len() != tokens[end - 1].len() {
+                break;
+            }
+        }
+
+        // Drop a trailing "and" that belongs to the following words.
+        while end > index && words.last() == Some(&EnglishNumberWord::And) {
+            words.pop();
+            end -= 1;
+        }
+
+        match (end > index).then(|| parse_english_number(&words)) {
+            Some(Ok(Some(value))) if value >= 10 => {
+                let last = tokens[end - 1];
+                let suffix = &last[last.trim_end_matches(['.', ',', '?', '!']).len()..];
+                output.push(format!("{value}{suffix}"));
+                index = end;
+            }
+            // Too large to mean anything; keep the whole run as spoken.
+            Some(Err(NumberOverflow)) => {
+                output.extend(tokens[index..end].iter().map(|token| token.to_string()));
+                index = end;
+            }
+            _ => {
+                output.push(tokens[index].to_string());
+                index += 1;
+            }
+        }
+    }
+    output.join(" ")
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0x3040..=0x30FF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+/// Wraps the session callback with the configured post-processing chain.
+pub fn attach_post_processing(
+    app: &AppHandle,
+    transcript_config: Option<&TranscriptRuntimeConfig>,
+    llm_config: Option<LlmRuntimeConfig>,
+    callback: PcmCallback,
+) -> PcmCallback {
+    let transcript_config = transcript_config.cloned().unwrap_or_default();
+    let llm_punctuator =
+        (punctuation_mode(&transcript_config) == PunctuationMode::Llm).then(|| {
+            let app = app.clone();
+            let llm_config = llm_config.unwrap_or_default();
+            let provider = resolve_string_or_default(
+                transcript_config
+                    .transcript_punctuation_llm_provider
+                    .as_deref(),
+                &["TRANSCRIPT_PUNCTUATION_LLM_PROVIDER"],
+                DEFAULT_PUNCTUATION_LLM_PROVIDER,
+            );
+            let request_counter = Arc::new(AtomicU64::new(0));
+            Arc::new(move |text: &str| {
+                let request_id = format!(
+                    "punctuation_{}",
+                    request_counter.fetch_add(1, Ordering::SeqCst)
+                );
+                tauri::async_runtime::block_on(complete_with_llm_provider(
+                    app.clone(),
+                    &provider,
+                    &llm_config,
+                    FlowArgs::new(text, PUNCTUATION_PROMPT),
+                    request_id,
+                ))
+            }) as LlmPunctuator
+        });
+
+    let chain = PostProcessChain::from_config(&transcript_config, llm_punctuator);
+    if chain.is_empty() {
+        return callback;
+    }
+    println!(
+        "Transcript post-processing enabled with {} stages",
+        chain.stages.len()
+    );
+    chain.wrap_callback(callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        NumberOverflow, PostProcessChain, PostProcessStage, apply_replacements,
+        english_number_word, normalize_numbers, parse_chinese_variant, parse_english_number,
+        parse_replacements, remove_fillers, restore_punctuation,
+    };
+    use crate::transcript_vendors::{PcmCallback, TranscriptEvent, TranscriptEventKind};
+    use std::sync::{Arc, Mutex, mpsc};
+    use std::time::Duration;
+
+    fn event(kind: TranscriptEventKind, text: &str) -> TranscriptEvent {
+        TranscriptEvent {
+            vendor: "Gladia".to_string(),
+            kind,
+            text: text.to_string(),
+            language: None,
+            utterance_id: None,
+        }
+    }
+
+    #[test]
+    fn overflowing_english_numbers_are_not_converted() {
+        let text = "nine hundred ".repeat(11);
+        let words = text
+            .split_whitespace()
+            .map(|word| english_number_word(word).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(parse_english_number(&words), Err(NumberOverflow));
+        assert_eq!(normalize_numbers(text.trim_end()), text.trim_end());
+        assert_eq!(normalize_numbers("nine hundred ninety nine"), "999");
+    }
+
+    #[test]
+    fn normalizes_chinese_and_english_numbers() {
+        assert_eq!(
+            normalize_numbers("我们有三百二十五个用户，增长了百分之十五"),
+            "我们有325个用户，增长了15%"
+        );
+        assert_eq!(
+            normalize_numbers("二零二四年用了三点五秒"),
+            "2024年用了3.5秒"
+        );
+        assert_eq!(
+            normalize_numbers("十年前，十分重要，一个人"),
+            "10年前，十分重要，一个人"
+        );
+        assert_eq!(
+            normalize_numbers("万一出错，大家三三两两地走"),
+            "万一出错，大家三三两两地走"
+        );
+        assert_eq!(normalize_numbers("一亿亿亿"), "一亿亿亿");
+        assert_eq!(normalize_numbers("一点一点地改"), "一点一点地改");
+        assert_eq!(
+            normalize_numbers("下午三点五十分开会"),
+            "下午三点五十分开会"
+        );
+        assert_eq!(normalize_numbers("十二点三十分"), "12点30分");
+        assert_eq!(
+            normalize_numbers("增长了百分之三点五，用了三点五"),
+            "增长了3.5%，用了3.5"
+        );
+        assert_eq!(
+            normalize_numbers("about twenty-five or one hundred and two users, not three"),
+            "about 25 or 102 users, not three"
+        );
+        assert_eq!(normalize_numbers("one of them"), "one of them");
+    }
+
+    #[test]
+    fn removes_fillers_and_applies_dictionary() {
+        assert_eq!(
+            remove_fillers("Um, so we shipped it uh."),
+            "So we shipped it."
+        );
+        assert_eq!(
+            remove_fillers("嗯，我觉得呃这个方案可以"),
+            "我觉得这个方案可以"
+        );
+
+        let replacements = parse_replacements("k8s => Kubernetes\n瑞士=>Rust; gonna=");
+        assert_eq!(replacements.len(), 3);
+        assert_eq!(
+            apply_replacements("K8S and k8ss in 瑞士, gonna ship", &replacements),
+            "Kubernetes and k8ss in Rust, ship"
+        );
+    }
+
+    #[test]
+    fn deleting_a_word_collapses_surrounding_spaces() {
+        let replacements = parse_replacements("like=");
+        assert_eq!(
+            apply_replacements("like I said it is like done like", &replacements),
+            "I said it is done"
+        );
+    }
+
+    #[test]
+    fn rule_punctuation_only_touches_commits() {
+        assert_eq!(restore_punctuation("what is tokio"), "What is tokio?");
+        assert_eq!(restore_punctuation("你知道吗"), "你知道吗？");
+        assert_eq!(restore_punctuation("我们开始吧"), "我们开始吧。");
+        assert_eq!(restore_punctuation("Done!"), "Done!");
+
+        let chain = PostProcessChain::with_stages(vec![PostProcessStage::RulePunctuation]);
+        assert_eq!(
+            chain
+                .apply(event(TranscriptEventKind::Draft, "hello there"))
+                .unwrap()
+                .text,
+            "hello there"
+        );
+        assert_eq!(
+            chain
+                .apply(event(TranscriptEventKind::Commit, "hello there"))
+                .unwrap()
+                .text,
+            "Hello there."
+        );
+    }
+
+    #[test]
+    fn llm_punctuation_falls_back_when_words_change() {
+        let chain = PostProcessChain::with_stages(vec![PostProcessStage::LlmPunctuation(
+            Arc::new(|text: &str| {
+                Ok(if text.starts_with("so") {
+                    "So, what now?".to_string()
+                } else {
+                    "I rewrote this entirely.".to_string()
+                })
+            }),
+        )]);
+
+        assert_eq!(
+            chain
+                .apply(event(TranscriptEventKind::Commit, "so what now"))
+                .unwrap()
+                .text,
+            "So, what now?"
+        );
+        assert_eq!(
+            chain
+                .apply(event(TranscriptEventKind::Commit, "we ship today"))
+                .unwrap()
+                .text,
+            "We ship today."
+        );
+    }
+
+    #[test]
+    fn drafts_wait_only_behind_a_commit_being_punctuated() {
+        let (release, released) = mpsc::channel::<()>();
+        let released = Mutex::new(released);
+        let chain = PostProcessChain::with_stages(vec![PostProcessStage::LlmPunctuation(
+            Arc::new(move |text: &str| {
+                released.lock().unwrap().recv().unwrap();
+                Ok(format!("{text}."))
+            }),
+        )]);
+        let (sender, received) = mpsc::channel::<TranscriptEvent>();
+        let sender = Mutex::new(sender);
+        let callback: PcmCallback = Arc::new(move |event| {
+            sender.lock().unwrap().send(event).unwrap();
+        });
+        let callback = chain.wrap_callback(callback);
+
+        callback(event(TranscriptEventKind::Draft, "fir"));
+        let draft = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(draft.text, "fir");
+
+        callback(event(TranscriptEventKind::Commit, "first"));
+        callback(event(TranscriptEventKind::Draft, "sec"));
+        assert!(received.recv_timeout(Duration::from_millis(100)).is_err());
+
+        release.send(()).unwrap();
+        let commit = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(commit.text, "first.");
+        let draft = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(draft.text, "sec");
+    }
+
+    #[test]
+    fn chinese_conversion_and_empty_results() {
+        let variant = parse_chinese_variant("zh_CN").unwrap();
+        let chain = PostProcessChain::with_stages(vec![
+            PostProcessStage::ChineseConversion(variant),
+            PostProcessStage::RemoveFillers,
+        ]);
+
+        assert_eq!(
+            chain
+                .apply(event(TranscriptEventKind::Commit, "我們說話"))
+                .unwrap()
+                .text,
+            "我们说话"
+        );
+        assert!(
+            chain
+                .apply(event(TranscriptEventKind::Commit, "嗯，"))
+                .is_none()
+        );
+        assert!(parse_chinese_variant("off").is_none());
+    }
+}
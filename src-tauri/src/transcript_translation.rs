@@ -0,0 +1,449 @@
+//! Parallel stream of translated utterances. Vendors with native translation
+//! (Speechmatics, Gladia) emit `Translation` events right after the commit they
+//! belong to; for everyone else each commit is sent through the LLM chain.
+
+use crate::llm::translate_with_llm_provider;
+use crate::provider_config::{
+    LlmRuntimeConfig, TranscriptRuntimeConfig, resolve_optional_string, resolve_string_or_default,
+};
+use crate::transcript_vendors::language::{parse_language_list, primary_subtag};
+use crate::transcript_vendors::{PcmCallback, TranscriptEvent, TranscriptEventKind};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+const DEFAULT_LLM_PROVIDER: &str = "deepseek_api";
+const NATIVE_TRANSLATION_VENDORS: [&str; 2] = ["speechmatics", "gladia"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationMode {
+    Off,
+    /// Only vendor-native translation; other vendors produce no translations.
+    Vendor,
+    Llm,
+    /// Vendor-native where available, the LLM otherwise.
+    Auto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationSettings {
+    pub mode: TranslationMode,
+    pub target_languages: Vec<String>,
+    pub llm_provider: String,
+}
+
+impl TranslationSettings {
+    pub fn resolve(transcript_config: &TranscriptRuntimeConfig) -> Self {
+        let target_languages = resolve_optional_string(
+            transcript_config.translation_target_languages.as_deref(),
+            &["TRANSLATION_TARGET_LANGUAGES"],
+        )
+        .map(|raw| parse_language_list(&raw))
+        .unwrap_or_default();
+        let mode = match resolve_optional_string(
+            transcript_config.translation_mode.as_deref(),
+            &["TRANSLATION_MODE"],
+        )
+        .map(|mode| mode.to_ascii_lowercase())
+        .as_deref()
+        {
+            None | Some("off") | Some("none") => TranslationMode::Off,
+            Some("vendor") | Some("native") => TranslationMode::Vendor,
+            Some("llm") => TranslationMode::Llm,
+            Some("auto") => TranslationMode::Auto,
+            Some(other) => {
+                eprintln!("Unknown TRANSLATION_MODE {other:?}, translation disabled");
+                TranslationMode::Off
+            }
+        };
+
+        Self {
+            // Without a target language there is nothing to translate into.
+            mode: if target_languages.is_empty() {
+                TranslationMode::Off
+            } else {
+                mode
+            },
+            target_languages,
+            llm_provider: resolve_string_or_default(
+                transcript_config.translation_llm_provider.as_deref(),
+                &["TRANSLATION_LLM_PROVIDER"],
+                DEFAULT_LLM_PROVIDER,
+            ),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != TranslationMode::Off
+    }
+
+    /// Targets a vendor should request from its own API; empty when the LLM
+    /// is responsible for translation.
+    pub fn vendor_target_languages(&self) -> &[String] {
+        match self.mode {
+            TranslationMode::Vendor | TranslationMode::Auto => &self.target_languages,
+            TranslationMode::Off | TranslationMode::Llm => &[],
+        }
+    }
+
+    fn uses_llm_for(&self, vendor: &str) -> bool {
+        match self.mode {
+            TranslationMode::Llm => true,
+            TranslationMode::Auto => !supports_native_translation(vendor),
+            TranslationMode::Off | TranslationMode::Vendor => false,
+        }
+    }
+}
+
+/// Matches both vendor ids (`speechmatics`) and event names (`SpeechMatics`).
+pub fn supports_native_translation(vendor: &str) -> bool {
+    NATIVE_TRANSLATION_VENDORS
+        .iter()
+        .any(|native| native.eq_ignore_ascii_case(vendor))
+}
+
+/// A translated utterance, linked to its source commit by `utterance_id`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationEvent {
+    pub utterance_id: u64,
+    pub vendor: String,
+    /// Who translated: the vendor name or `llm:<provider>`.
+    pub engine: String,
+    pub source_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_language: Option<String>,
+    pub target_language: String,
+    pub text: String,
+}
+
+pub type TranslationCallback = Arc<dyn Fn(TranslationEvent) + Send + Sync + 'static>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LlmTranslationJob {
+    pub provider: String,
+    pub utterance_id: u64,
+    pub vendor: String,
+    pub source_text: String,
+    pub source_language: Option<String>,
+    pub target_language: String,
+}
+
+impl LlmTranslationJob {
+    pub fn into_event(self, text: String) -> TranslationEvent {
+        TranslationEvent {
+            utterance_id: self.utterance_id,
+            vendor: self.vendor,
+            engine: format!("llm:{}", self.provider),
+            source_text: self.source_text,
+            source_language: self.source_language,
+            target_language: self.target_language,
+            text,
+        }
+    }
+}
+
+pub type LlmTranslationDispatcher = Arc<dyn Fn(LlmTranslationJob) + Send + Sync + 'static>;
+
+#[derive(Clone)]
+struct CommittedUtterance {
+    id: u64,
+    vendor: String,
+    text: String,
+    language: Option<String>,
+}
+
+#[derive(Default)]
+struct RouterState {
+    next_utterance_id: u64,
+    last_commit: Option<CommittedUtterance>,
+    /// Vendor translations that arrived before any commit of the session.
+    orphaned: Vec<TranscriptEvent>,
+}
+
+pub struct TranslationRouter {
+    settings: TranslationSettings,
+    state: Mutex<RouterState>,
+    on_translation: TranslationCallback,
+    dispatch_llm: Option<LlmTranslationDispatcher>,
+}
+
+impl TranslationRouter {
+    pub fn new(
+        settings: TranslationSettings,
+        on_translation: TranslationCallback,
+        dispatch_llm: Option<LlmTranslationDispatcher>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            state: Mutex::new(RouterState::default()),
+            on_translation,
+            dispatch_llm,
+        })
+    }
+
+    /// Numbers commits, turns vendor translations into linked events and
+    /// queues LLM translations. Translation events never reach `callback`.
+    pub fn wrap_callback(self: &Arc<Self>, callback: PcmCallback) -> PcmCallback {
+        let router = self.clone();
+        Arc::new(move |event: TranscriptEvent| {
+            if let Some(event) = router.route(event) {
+                callback(event);
+            }
+        })
+    }
+
+    fn route(&self, mut event: TranscriptEvent) -> Option<TranscriptEvent> {
+        match event.kind {
+            TranscriptEventKind::Draft => Some(event),
+            TranscriptEventKind::Translation => {
+                let source = {
+                    let mut state = self.state.lock().unwrap();
+                    let Some(source) = state.last_commit.clone() else {
+                        state.orphaned.push(event);
+                        return None;
+                    };
+                    source
+                };
+                (self.on_translation)(link_vendor_translation(&source, event));
+                None
+            }
+            TranscriptEventKind::Commit => {
+                let (source, orphaned) = {
+                    let mut state = self.state.lock().unwrap();
+                    let source = CommittedUtterance {
+                        id: state.next_utterance_id,
+                        vendor: event.vendor.clone(),
+                        text: event.text.clone(),
+                        language: event.language.clone(),
+                    };
+                    state.next_utterance_id += 1;
+                    state.last_commit = Some(source.clone());
+                    (source, std::mem::take(&mut state.orphaned))
+                };
+
+                event.utterance_id = Some(source.id);
+                for translation in orphaned {
+                    (self.on_translation)(link_vendor_translation(&source, translation));
+                }
+                if self.settings.uses_llm_for(&event.vendor) {
+                    self.dispatch_llm_jobs(&source);
+                }
+                Some(event)
+            }
+        }
+    }
+
+    fn dispatch_llm_jobs(&self, source: &CommittedUtterance) {
+        let Some(dispatch) = self.dispatch_llm.as_ref() else {
+            return;
+        };
+        let source_subtag = source.language.as_deref().map(primary_subtag);
+        for target in &self.settings.target_languages {
+            if source_subtag.as_deref() == Some(primary_subtag(target).as_str()) {
+                continue;
+            }
+            dispatch(LlmTranslationJob {
+                provider: self.settings.llm_provider.clone(),
+                utterance_id: source.id,
+                vendor: source.vendor.clone(),
+                source_text: source.text.clone(),
+                source_language: source.language.clone(),
+                target_language: target.clone(),
+            });
+        }
+    }
+}
+
+fn link_vendor_translation(
+    source: &CommittedUtterance,
+    translation: TranscriptEvent,
+) -> TranslationEvent {
+    TranslationEvent {
+        utterance_id: source.id,
+        vendor: source.vendor.clone(),
+        engine: translation.vendor,
+        source_text: source.text.clone(),
+        source_language: source.language.clone(),
+        target_language: translation.language.unwrap_or_default(),
+        text: translation.text,
+    }
+}
+
+/// Wraps the session callback with a translation router when translation is
+/// configured; translations are emitted as `translation_event`.
+pub fn attach_translation_stream(
+    app: &AppHandle,
+    transcript_config: Option<&TranscriptRuntimeConfig>,
+    llm_config: Option<LlmRuntimeConfig>,
+    callback: PcmCallback,
+) -> PcmCallback {
+    let settings = TranslationSettings::resolve(&transcript_config.cloned().unwrap_or_default());
+    if !settings.is_enabled() {
+        return callback;
+    }
+
+    let emit_app = app.clone();
+    let on_translation: TranslationCallback = Arc::new(move |event: TranslationEvent| {
+        if let Err(err) = emit_app.emit("translation_event", event) {
+            eprintln!("Failed to emit translation event: {err}");
+        }
+    });
+    let dispatch_llm = (settings.mode != TranslationMode::Vendor).then(|| {
+        let llm_app = app.clone();
+        let llm_config = llm_config.unwrap_or_default();
+        let on_translation = on_translation.clone();
+        Arc::new(move |job: LlmTranslationJob| {
+            let app = llm_app.clone();
+            let llm_config = llm_config.clone();
+            let on_translation = on_translation.clone();
+            tauri::async_runtime::spawn(async move {
+                let request_id =
+                    format!("translation_{}_{}", job.utterance_id, job.target_language);
+                match translate_with_llm_provider(
+                    app,
+                    &job.provider,
+                    &llm_config,
+                    &job.source_text,
+                    job.source_language.as_deref(),
+                    &job.target_language,
+                    request_id,
+                )
+                .await
+                {
+                    Ok(text) if !text.is_empty() => on_translation(job.into_event(text)),
+                    Ok(_) => {}
+                    Err(err) => eprintln!(
+                        "LLM translation of utterance {} into {} failed: {err}",
+                        job.utterance_id, job.target_language
+                    ),
+                }
+            });
+        }) as LlmTranslationDispatcher
+    });
+
+    println!(
+        "Transcript translation enabled: mode={:?} targets={:?}",
+        settings.mode, settings.target_languages
+    );
+    TranslationRouter::new(settings, on_translation, dispatch_llm).wrap_callback(callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        LlmTranslationJob, TranslationEvent, TranslationMode, TranslationRouter,
+        TranslationSettings,
+    };
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::language::DetectedLanguage;
+    use crate::transcript_vendors::{
+        PcmCallback, TranscriptEvent, emit_commit, emit_draft, emit_translation,
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn settings(mode: TranslationMode, targets: &[&str]) -> TranslationSettings {
+        TranslationSettings {
+            mode,
+            target_languages: targets.iter().map(ToString::to_string).collect(),
+            llm_provider: "deepseek_api".to_string(),
+        }
+    }
+
+    struct Harness {
+        transcripts: Arc<Mutex<Vec<TranscriptEvent>>>,
+        translations: Arc<Mutex<Vec<TranslationEvent>>>,
+        jobs: Arc<Mutex<Vec<LlmTranslationJob>>>,
+        callback: PcmCallback,
+    }
+
+    fn harness(settings: TranslationSettings) -> Harness {
+        let transcripts = Arc::new(Mutex::new(Vec::new()));
+        let translations = Arc::new(Mutex::new(Vec::new()));
+        let jobs = Arc::new(Mutex::new(Vec::new()));
+        let transcript_sink = transcripts.clone();
+        let translation_sink = translations.clone();
+        let job_sink = jobs.clone();
+        let router = TranslationRouter::new(
+            settings,
+            Arc::new(move |event| translation_sink.lock().unwrap().push(event)),
+            Some(Arc::new(move |job| job_sink.lock().unwrap().push(job))),
+        );
+        let callback = router.wrap_callback(Arc::new(move |event| {
+            transcript_sink.lock().unwrap().push(event)
+        }));
+        Harness {
+            transcripts,
+            translations,
+            jobs,
+            callback,
+        }
+    }
+
+    #[test]
+    fn resolve_disables_translation_without_targets() {
+        let config = TranscriptRuntimeConfig {
+            translation_mode: Some("llm".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            TranslationSettings::resolve(&config).mode,
+            TranslationMode::Off
+        );
+
+        let config = TranscriptRuntimeConfig {
+            translation_mode: Some("Auto".to_string()),
+            translation_target_languages: Some("en, ja".to_string()),
+            ..Default::default()
+        };
+        let resolved = TranslationSettings::resolve(&config);
+        assert_eq!(resolved.mode, TranslationMode::Auto);
+        assert_eq!(resolved.vendor_target_languages(), ["en", "ja"]);
+    }
+
+    #[test]
+    fn vendor_translations_link_to_the_preceding_commit() {
+        let harness = harness(settings(TranslationMode::Auto, &["en"]));
+
+        emit_translation(&harness.callback, "SpeechMatics", "en", "early");
+        emit_draft(&harness.callback, "SpeechMatics", "你好");
+        emit_commit(&harness.callback, "SpeechMatics", "你好世界");
+        emit_translation(&harness.callback, "SpeechMatics", "en", "Hello world");
+
+        let transcripts = harness.transcripts.lock().unwrap();
+        assert_eq!(transcripts.len(), 2);
+        assert_eq!(transcripts[1].utterance_id, Some(0));
+
+        let translations = harness.translations.lock().unwrap();
+        assert_eq!(translations.len(), 2);
+        assert!(translations.iter().all(|event| event.utterance_id == 0));
+        assert_eq!(translations[1].source_text, "你好世界");
+        assert_eq!(translations[1].target_language, "en");
+        assert_eq!(translations[1].engine, "SpeechMatics");
+        // Native translation covers Speechmatics in auto mode.
+        assert!(harness.jobs.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn llm_jobs_skip_targets_matching_the_source_language() {
+        let harness = harness(settings(TranslationMode::Auto, &["en", "zh-CN"]));
+        let detected_language = DetectedLanguage::default();
+        detected_language.update(Some("cmn"));
+        let detected = detected_language.wrap_callback(harness.callback.clone());
+
+        emit_commit(&detected, "Deepgram", "第一句");
+        emit_commit(&detected, "Deepgram", "第二句");
+
+        let jobs = harness.jobs.lock().unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert!(jobs.iter().all(|job| job.target_language == "en"));
+        assert_eq!(
+            jobs.iter().map(|job| job.utterance_id).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            jobs[1].clone().into_event("Second".to_string()).engine,
+            "llm:deepseek_api"
+        );
+    }
+}
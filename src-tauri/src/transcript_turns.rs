@@ -0,0 +1,717 @@
+//! Turn analyzer for the remote speaker. Commits are grouped into turns that
+//! end after a stretch of silence; a turn that asks something (punctuation,
+//! Chinese or English interrogative patterns) is reported as an
+//! `auto_question`, optionally confirmed by a cheap LLM classifier first. In
+//! `answer` mode the configured provider is asked once the frontend confirms
+//! it listens on `llm_stream_{requestId}` (see `auto_answer_stream_ready`);
+//! the reply streams there and is then emitted as `auto_answer`.
+
+use crate::llm::{FlowArgs, chat_with_llm_provider, complete_with_llm_provider};
+use crate::provider_config::{
+    LlmRuntimeConfig, TranscriptRuntimeConfig, resolve_optional_string, resolve_string_or_default,
+};
+use crate::transcript_vendors::{PcmCallback, TranscriptEvent, TranscriptEventKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+const DEFAULT_SILENCE_MS: u64 = 1200;
+const DEFAULT_LLM_PROVIDER: &str = "deepseek_api";
+/// Oldest commits are dropped once a turn grows past this many characters.
+const MAX_TURN_CHARS: usize = 800;
+/// How long an answer waits for the frontend to subscribe to its stream.
+const STREAM_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_ANSWER_PROMPT: &str =
+    "你是对话中的回答助手。对方刚刚提出了下面的问题，请直接、简洁地给出可以当场说出口的回答。";
+const CLASSIFIER_PROMPT: &str = "The user message is a transcript of what the other person in a conversation just said. \
+Does it ask a question or make a request that the listener is expected to answer? Reply with yes or no only.";
+const CHINESE_QUESTION_PARTICLES: [char; 3] = ['吗', '呢', '么'];
+const CHINESE_INTERROGATIVES: [&str; 30] = [
+    "什么",
+    "为什么",
+    "为啥",
+    "怎么",
+    "怎样",
+    "如何",
+    "哪里",
+    "哪儿",
+    "哪个",
+    "哪些",
+    "哪种",
+    "谁",
+    "几个",
+    "几种",
+    "多少",
+    "多久",
+    "是否",
+    "能不能",
+    "可不可以",
+    "会不会",
+    "有没有",
+    "是不是",
+    "要不要",
+    "对不对",
+    "请问",
+    "介绍一下",
+    "讲讲",
+    "说说",
+    "谈谈",
+    "聊聊",
+];
+const ENGLISH_QUESTION_STARTERS: [&str; 10] = [
+    "what", "what's", "why", "how", "how's", "when", "where", "who", "which", "whose",
+];
+/// Leading auxiliaries only ask something when a subject follows ("do you", not "do that").
+const ENGLISH_AUXILIARIES: [&str; 12] = [
+    "is", "are", "do", "does", "did", "can", "could", "would", "should", "will", "have", "has",
+];
+const ENGLISH_SUBJECTS: [&str; 10] = [
+    "i", "you", "we", "they", "he", "she", "it", "there", "anyone", "anybody",
+];
+const ENGLISH_REQUEST_PHRASES: [&str; 6] = [
+    "tell me",
+    "walk me through",
+    "can you",
+    "could you",
+    "would you",
+    "explain",
+];
+/// Filler allowed before a request phrase ("so tell me", "please explain").
+const ENGLISH_REQUEST_LEAD_INS: [&str; 6] = ["so", "and", "okay", "ok", "now", "please"];
+/// Endings that mean the speaker paused mid-sentence rather than finished.
+const TRAILING_CONNECTORS: [&str; 14] = [
+    "and", "or", "but", "so", "because", "then", "然后", "还有", "而且", "所以", "因为", "或者",
+    "就是", "那个",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoQuestionMode {
+    Off,
+    /// Only emit `auto_question`; the frontend decides what to do with it.
+    Event,
+    /// Emit `auto_question` and ask the configured provider for an answer.
+    Answer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoQuestionSettings {
+    pub mode: AutoQuestionMode,
+    pub silence: Duration,
+    pub llm_provider: String,
+    pub classifier_provider: Option<String>,
+    pub prompt: String,
+}
+
+impl AutoQuestionSettings {
+    pub fn resolve(transcript_config: &TranscriptRuntimeConfig) -> Self {
+        let mode = match resolve_optional_string(
+            transcript_config.auto_question_mode.as_deref(),
+            &["AUTO_QUESTION_MODE"],
+        )
+        .map(|mode| mode.to_ascii_lowercase())
+        .as_deref()
+        {
+            None | Some("off") | Some("none") => AutoQuestionMode::Off,
+            Some("event") | Some("detect") => AutoQuestionMode::Event,
+            Some("answer") | Some("llm") => AutoQuestionMode::Answer,
+            Some(other) => {
+                eprintln!("Unknown AUTO_QUESTION_MODE {other:?}, question detection disabled");
+                AutoQuestionMode::Off
+            }
+        };
+        let silence_ms = resolve_optional_string(
+            transcript_config.auto_question_silence_ms.as_deref(),
+            &["AUTO_QUESTION_SILENCE_MS"],
+        )
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SILENCE_MS);
+
+        Self {
+            mode,
+            silence: Duration::from_millis(silence_ms),
+            llm_provider: resolve_string_or_default(
+                transcript_config.auto_question_llm_provider.as_deref(),
+                &["AUTO_QUESTION_LLM_PROVIDER"],
+                DEFAULT_LLM_PROVIDER,
+            ),
+            classifier_provider: resolve_optional_string(
+                transcript_config
+                    .auto_question_classifier_provider
+                    .as_deref(),
+                &["AUTO_QUESTION_CLASSIFIER_PROVIDER"],
+            ),
+            prompt: resolve_string_or_default(
+                transcript_config.auto_question_prompt.as_deref(),
+                &["AUTO_QUESTION_PROMPT"],
+                DEFAULT_ANSWER_PROMPT,
+            ),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != AutoQuestionMode::Off
+    }
+}
+
+/// A finished turn of the remote speaker that asks something.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedQuestion {
+    pub id: u64,
+    pub vendor: String,
+    pub question: String,
+    /// Set in `answer` mode; the reply streams on `llm_stream_{request_id}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAnswerEvent {
+    pub question_id: u64,
+    pub request_id: String,
+    pub provider: String,
+    pub question: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub type QuestionCallback = Arc<dyn Fn(DetectedQuestion) + Send + Sync + 'static>;
+/// Second opinion on a heuristic match; `Ok(false)` drops the question.
+pub type QuestionClassifier = Arc<dyn Fn(&str) -> Result<bool, String> + Send + Sync + 'static>;
+
+/// Groups commits into turns and closes a turn after `silence` without
+/// speech. Drafts count as speech, so a pause mid-utterance never ends a turn.
+pub struct TurnAnalyzer {
+    silence: Duration,
+    segments: Vec<String>,
+    vendor: String,
+    last_activity: Option<Instant>,
+}
+
+impl TurnAnalyzer {
+    pub fn new(silence: Duration) -> Self {
+        Self {
+            silence,
+            segments: Vec::new(),
+            vendor: String::new(),
+            last_activity: None,
+        }
+    }
+
+    pub fn on_commit(&mut self, vendor: &str, text: &str, now: Instant) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.segments.push(text.to_string());
+        while self.segments.len() > 1
+            && self
+                .segments
+                .iter()
+                .map(|s| s.chars().count())
+                .sum::<usize>()
+                > MAX_TURN_CHARS
+        {
+            self.segments.remove(0);
+        }
+        self.vendor = vendor.to_string();
+        self.last_activity = Some(now);
+    }
+
+    pub fn on_draft(&mut self, now: Instant) {
+        if !self.segments.is_empty() {
+            self.last_activity = Some(now);
+        }
+    }
+
+    /// When the open turn ends if nobody speaks; a dangling connector such as
+    /// "and" or "然后" doubles the wait.
+    pub fn deadline(&self) -> Option<Instant> {
+        let last_activity = self.last_activity?;
+        let wait = if self.segments.last().is_some_and(|s| ends_mid_sentence(s)) {
+            self.silence * 2
+        } else {
+            self.silence
+        };
+        Some(last_activity + wait)
+    }
+
+    /// Closes the turn once its deadline passed and returns it when it asks a
+    /// question; statements are discarded with the turn.
+    pub fn poll(&mut self, now: Instant) -> Option<(String, String)> {
+        if self.deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        self.last_activity = None;
+        let text = join_segments(&std::mem::take(&mut self.segments));
+        is_question(&text).then(|| (std::mem::take(&mut self.vendor), text))
+    }
+}
+
+/// True when any sentence of `text` asks something.
+pub fn is_question(text: &str) -> bool {
+    split_sentences(text)
+        .into_iter()
+        .any(|sentence| is_question_sentence(&sentence))
+}
+
+fn is_question_sentence(sentence: &str) -> bool {
+    let trimmed = sentence.trim();
+    if trimmed.ends_with(['?', '？']) {
+        return true;
+    }
+    let core = trimmed.trim_end_matches(|ch: char| !ch.is_alphanumeric());
+    let Some(last) = core.chars().last() else {
+        return false;
+    };
+    if CHINESE_QUESTION_PARTICLES.contains(&last) {
+        return true;
+    }
+    if CHINESE_INTERROGATIVES
+        .iter()
+        .any(|word| contains_unnegated(core, word))
+    {
+        return true;
+    }
+
+    let lower = core.to_lowercase();
+    let words: Vec<&str> = lower
+        .split_whitespace()
+        .map(|word| word.trim_matches(|ch: char| !ch.is_alphanumeric() && ch != '\''))
+        .collect();
+    let starts_question = match words.as_slice() {
+        // A single word ("so", "right") is too little to call a question.
+        [first, _, ..] if ENGLISH_QUESTION_STARTERS.contains(first) => true,
+        [first, second, ..] => {
+            ENGLISH_AUXILIARIES.contains(first) && ENGLISH_SUBJECTS.contains(second)
+        }
+        _ => false,
+    };
+    if starts_question {
+        return true;
+    }
+
+    // Requests are imperatives, so the phrase must open the sentence;
+    // "let me explain why" is a statement.
+    let request = words
+        .iter()
+        .skip_while(|word| ENGLISH_REQUEST_LEAD_INS.contains(word))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    ENGLISH_REQUEST_PHRASES.iter().any(|phrase| {
+        request
+            .strip_prefix(phrase)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+/// `没什么` and `不怎么` use the interrogative as an indefinite, not a question.
+fn contains_unnegated(text: &str, word: &str) -> bool {
+    text.match_indices(word)
+        .any(|(index, _)| !text[..index].ends_with(['没', '不']))
+}
+
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for ch in text.chars() {
+        current.push(ch);
+        if matches!(ch, '。' | '？' | '！' | '?' | '!' | '.' | '；' | ';') {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current);
+    }
+    sentences
+}
+
+fn ends_mid_sentence(text: &str) -> bool {
+    let trimmed = text.trim_end();
+    if trimmed.ends_with([',', '，', '、']) {
+        return true;
+    }
+    let lower = trimmed.to_lowercase();
+    TRAILING_CONNECTORS.iter().any(|connector| {
+        lower.strip_suffix(connector).is_some_and(|rest| {
+            // English connectors must be whole words.
+            !connector.is_ascii() || rest.is_empty() || rest.ends_with(' ')
+        })
+    })
+}
+
+fn join_segments(segments: &[String]) -> String {
+    let mut text = String::new();
+    for segment in segments {
+        let needs_space = text
+            .chars()
+            .last()
+            .is_some_and(|last| last.is_ascii() && !last.is_whitespace())
+            && segment.starts_with(|ch: char| ch.is_ascii_alphanumeric());
+        if needs_space {
+            text.push(' ');
+        }
+        text.push_str(segment);
+    }
+    text
+}
+
+fn parse_classifier_reply(reply: &str) -> Result<bool, String> {
+    let reply = reply.trim().to_lowercase();
+    if reply.starts_with("yes") || reply.starts_with('是') {
+        Ok(true)
+    } else if reply.starts_with("no") || reply.starts_with('否') || reply.starts_with("不是") {
+        Ok(false)
+    } else {
+        Err(format!("unexpected classifier reply: {reply}"))
+    }
+}
+
+enum TurnMessage {
+    Draft,
+    Commit { vendor: String, text: String },
+}
+
+/// Passes events through unchanged while a worker thread watches the turns.
+pub fn wrap_callback(
+    settings: &AutoQuestionSettings,
+    classifier: Option<QuestionClassifier>,
+    on_question: QuestionCallback,
+    callback: PcmCallback,
+) -> PcmCallback {
+    let (sender, receiver) = mpsc::channel::<TurnMessage>();
+    let analyzer = TurnAnalyzer::new(settings.silence);
+    thread::spawn(move || run_worker(analyzer, receiver, classifier, on_question));
+
+    Arc::new(move |event: TranscriptEvent| {
+        let message = match event.kind {
+            TranscriptEventKind::Draft => Some(TurnMessage::Draft),
+            TranscriptEventKind::Commit => Some(TurnMessage::Commit {
+                vendor: event.vendor.clone(),
+                text: event.text.clone(),
+            }),
+            TranscriptEventKind::Translation => None,
+        };
+        if let Some(message) = message {
+            let _ = sender.send(message);
+        }
+        callback(event);
+    })
+}
+
+/// Ends with the session, when the last callback clone drops the sender.
+fn run_worker(
+    mut analyzer: TurnAnalyzer,
+    receiver: mpsc::Receiver<TurnMessage>,
+    classifier: Option<QuestionClassifier>,
+    on_question: QuestionCallback,
+) {
+    let mut next_id = 0_u64;
+    loop {
+        let message = match analyzer.deadline() {
+            Some(deadline) => {
+                match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(message) => Some(message),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            None => match receiver.recv() {
+                Ok(message) => Some(message),
+                Err(_) => break,
+            },
+        };
+
+        let now = Instant::now();
+        match message {
+            Some(TurnMessage::Draft) => analyzer.on_draft(now),
+            Some(TurnMessage::Commit { vendor, text }) => analyzer.on_commit(&vendor, &text, now),
+            None => {}
+        }
+        let Some((vendor, question)) = analyzer.poll(now) else {
+            continue;
+        };
+        let confirmed = match classifier.as_ref().map(|classify| classify(&question)) {
+            None | Some(Ok(true)) => true,
+            Some(Ok(false)) => false,
+            Some(Err(err)) => {
+                eprintln!("Question classifier failed, keeping heuristic match: {err}");
+                true
+            }
+        };
+        if confirmed {
+            on_question(DetectedQuestion {
+                id: next_id,
+                vendor,
+                question,
+                request_id: None,
+            });
+            next_id += 1;
+        }
+    }
+}
+
+static STREAM_LISTENERS: OnceLock<Mutex<HashMap<String, oneshot::Sender<()>>>> = OnceLock::new();
+
+fn stream_listeners() -> &'static Mutex<HashMap<String, oneshot::Sender<()>>> {
+    STREAM_LISTENERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Called once the frontend listens on `llm_stream_{request_id}`, so the
+/// answer does not start streaming before anyone is subscribed.
+#[tauri::command]
+pub fn auto_answer_stream_ready(request_id: String) {
+    if let Some(ready) = stream_listeners().lock().unwrap().remove(&request_id) {
+        let _ = ready.send(());
+    }
+}
+
+/// Wraps the session callback with the turn analyzer when auto questions are
+/// enabled. Only loopback capture carries the remote speaker.
+pub fn attach_turn_analyzer(
+    app: &AppHandle,
+    transcript_config: Option<&TranscriptRuntimeConfig>,
+    llm_config: Option<LlmRuntimeConfig>,
+    remote_audio: bool,
+    callback: PcmCallback,
+) -> PcmCallback {
+    let settings = AutoQuestionSettings::resolve(&transcript_config.cloned().unwrap_or_default());
+    if !settings.is_enabled() || !remote_audio {
+        return callback;
+    }
+
+    let llm_config = llm_config.unwrap_or_default();
+    let classifier = settings.classifier_provider.clone().map(|provider| {
+        let app = app.clone();
+        let llm_config = llm_config.clone();
+        let request_counter = Arc::new(AtomicU64::new(0));
+        Arc::new(move |text: &str| {
+            let request_id = format!(
+                "question_classifier_{}",
+                request_counter.fetch_add(1, Ordering::SeqCst)
+            );
+            tauri::async_runtime::block_on(complete_with_llm_provider(
+                app.clone(),
+                &provider,
+                &llm_config,
+                FlowArgs::new(text, CLASSIFIER_PROMPT),
+                request_id,
+            ))
+            .and_then(|reply| parse_classifier_reply(&reply))
+        }) as QuestionClassifier
+    });
+
+    let emit_app = app.clone();
+    let answer_settings = (settings.mode == AutoQuestionMode::Answer).then(|| settings.clone());
+    let session_tag = chrono::Utc::now().timestamp_millis();
+    let on_question: QuestionCallback = Arc::new(move |mut question: DetectedQuestion| {
+        let stream_ready = answer_settings.as_ref().map(|_| {
+            let request_id = format!("auto_question_{session_tag}_{}", question.id);
+            let (ready, ready_rx) = oneshot::channel();
+            stream_listeners()
+                .lock()
+                .unwrap()
+                .insert(request_id.clone(), ready);
+            question.request_id = Some(request_id);
+            ready_rx
+        });
+        if let Err(err) = emit_app.emit("auto_question", question.clone()) {
+            eprintln!("Failed to emit auto question: {err}");
+        }
+        let (Some(settings), Some(request_id), Some(stream_ready)) =
+            (answer_settings.as_ref(), question.request_id, stream_ready)
+        else {
+            return;
+        };
+
+        let app = emit_app.clone();
+        let llm_config = llm_config.clone();
+        let provider = settings.llm_provider.clone();
+        let prompt = settings.prompt.clone();
+        tauri::async_runtime::spawn(async move {
+            if tokio::time::timeout(STREAM_HANDSHAKE_TIMEOUT, stream_ready)
+                .await
+                .is_err()
+            {
+                stream_listeners().lock().unwrap().remove(&request_id);
+                eprintln!(
+                    "No listener for auto answer {request_id}; answering without live streaming"
+                );
+            }
+            let result = chat_with_llm_provider(
+                app.clone(),
+                provider.clone(),
+                FlowArgs::new(question.question.clone(), prompt)
+                    .set_request_id(Some(request_id.clone()))
+                    .set_thread_id(Some(format!("auto_question_{session_tag}"))),
+                Some(llm_config),
+            )
+            .await;
+            let (answer, error) = match result {
+                Ok(answer) => (Some(answer), None),
+                Err(err) => {
+                    eprintln!("Auto answer for question {} failed: {err}", question.id);
+                    (None, Some(err))
+                }
+            };
+            let event = AutoAnswerEvent {
+                question_id: question.id,
+                request_id,
+                provider,
+                question: question.question,
+                answer,
+                error,
+            };
+            if let Err(err) = app.emit("auto_answer", event) {
+                eprintln!("Failed to emit auto answer: {err}");
+            }
+        });
+    });
+
+    println!(
+        "Auto question detection enabled: mode={:?} silence={:?} classifier={:?}",
+        settings.mode, settings.silence, settings.classifier_provider
+    );
+    wrap_callback(&settings, classifier, on_question, callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AutoQuestionMode, AutoQuestionSettings, DetectedQuestion, TurnAnalyzer, is_question,
+        parse_classifier_reply, wrap_callback,
+    };
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::{emit_commit, emit_draft};
+    use std::sync::{Arc, Mutex, mpsc};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn detects_chinese_and_english_questions() {
+        for question in [
+            "你们项目里缓存是怎么设计的",
+            "这个方案可行吗",
+            "先介绍一下你自己。",
+            "What would you do differently?",
+            "how does the scheduler handle retries",
+            "Okay. Walk me through the last incident.",
+            "我们用了 Redis。你觉得有没有更好的办法",
+            "do you have a rollback plan",
+            "Is there a reason you picked Kafka",
+            "Please explain the retry logic",
+            "So tell me about your last project",
+        ] {
+            assert!(is_question(question), "{question}");
+        }
+        for statement in [
+            "我们上周上线了新版本。",
+            "So.",
+            "That sounds good, thanks.",
+            "几乎所有服务都迁移完了",
+            "没什么问题，我们继续。",
+            "Have a good one.",
+            "Do that later.",
+            "Will do, thanks.",
+            "Let me explain why we picked Kafka.",
+            "I tried to explain the outage to them",
+            "They said they would tell me later",
+        ] {
+            assert!(!is_question(statement), "{statement}");
+        }
+    }
+
+    #[test]
+    fn turn_closes_after_silence_and_drafts_keep_it_open() {
+        let start = Instant::now();
+        let silence = Duration::from_millis(1000);
+        let mut analyzer = TurnAnalyzer::new(silence);
+
+        analyzer.on_commit("Deepgram", "我们用了 Redis 做缓存。", start);
+        analyzer.on_commit("Deepgram", "你觉得怎么避免缓存穿透", start + silence / 2);
+        analyzer.on_draft(start + silence);
+        assert_eq!(analyzer.poll(start + silence + silence / 2), None);
+
+        assert_eq!(
+            analyzer.poll(start + silence * 2),
+            Some((
+                "Deepgram".to_string(),
+                "我们用了 Redis 做缓存。你觉得怎么避免缓存穿透".to_string()
+            ))
+        );
+        // The turn is consumed; nothing fires twice.
+        assert_eq!(analyzer.poll(start + silence * 5), None);
+
+        analyzer.on_commit("Deepgram", "好的，谢谢。", start + silence * 6);
+        assert_eq!(analyzer.poll(start + silence * 8), None);
+        assert_eq!(analyzer.deadline(), None);
+    }
+
+    #[test]
+    fn trailing_connector_waits_longer() {
+        let start = Instant::now();
+        let silence = Duration::from_millis(1000);
+        let mut analyzer = TurnAnalyzer::new(silence);
+
+        analyzer.on_commit("Gladia", "What did you change in the pipeline and", start);
+        assert_eq!(analyzer.deadline(), Some(start + silence * 2));
+        assert_eq!(analyzer.poll(start + silence), None);
+        analyzer.on_commit("Gladia", "why?", start + silence + silence / 2);
+        assert_eq!(
+            analyzer.poll(start + silence * 3).map(|(_, text)| text),
+            Some("What did you change in the pipeline and why?".to_string())
+        );
+    }
+
+    #[test]
+    fn worker_emits_questions_confirmed_by_the_classifier() {
+        let config = TranscriptRuntimeConfig {
+            auto_question_mode: Some("event".to_string()),
+            auto_question_silence_ms: Some("30".to_string()),
+            ..Default::default()
+        };
+        let settings = AutoQuestionSettings::resolve(&config);
+        assert_eq!(settings.mode, AutoQuestionMode::Event);
+        assert_eq!(settings.silence, Duration::from_millis(30));
+
+        let (question_tx, question_rx) = mpsc::channel::<DetectedQuestion>();
+        let passed = Arc::new(Mutex::new(Vec::new()));
+        let passed_sink = passed.clone();
+        let callback = wrap_callback(
+            &settings,
+            Some(Arc::new(|text: &str| Ok(!text.contains("rhetorical")))),
+            Arc::new(move |question| {
+                question_tx.send(question).unwrap();
+            }),
+            Arc::new(move |event| passed_sink.lock().unwrap().push(event)),
+        );
+
+        emit_draft(&callback, "Deepgram", "Isn't that");
+        emit_commit(&callback, "Deepgram", "Isn't that rhetorical?");
+        std::thread::sleep(Duration::from_millis(120));
+        emit_commit(&callback, "Deepgram", "How do you test it?");
+
+        let question = question_rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("question event");
+        assert_eq!(question.id, 0);
+        assert_eq!(question.question, "How do you test it?");
+        assert_eq!(question.request_id, None);
+        assert_eq!(passed.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn parses_classifier_replies() {
+        assert_eq!(parse_classifier_reply(" Yes."), Ok(true));
+        assert_eq!(parse_classifier_reply("否"), Ok(false));
+        assert!(parse_classifier_reply("maybe").is_err());
+    }
+}
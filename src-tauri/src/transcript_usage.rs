@@ -0,0 +1,676 @@
+//! ASR usage metering. Vendors report every audio chunk they actually send,
+//! idle-silence keepalives included, and the totals are kept per day, per
+//! session and per vendor in a JSON file under the app data dir.
+
+use crate::provider_config::{TranscriptRuntimeConfig, resolve_optional_string};
+use crate::transcript_vendors::TranscriptVendors;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+const USAGE_FILE_NAME: &str = "asr_usage.json";
+const MAX_STORED_SESSIONS: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+/// Fractions of the monthly budget that raise an alert, once per month each.
+const BUDGET_ALERT_THRESHOLDS: [f64; 2] = [0.8, 1.0];
+
+static ACTIVE_METER: OnceLock<Mutex<Option<UsageMeter>>> = OnceLock::new();
+
+pub type BudgetAlertCallback = Arc<dyn Fn(BudgetAlert) + Send + Sync + 'static>;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    /// Captured audio sent to the vendor.
+    pub audio_seconds: f64,
+    /// Synthetic silence sent only to keep an idle connection open.
+    pub keepalive_seconds: f64,
+}
+
+impl UsageTotals {
+    pub fn billed_seconds(&self) -> f64 {
+        self.audio_seconds + self.keepalive_seconds
+    }
+
+    fn add(&mut self, other: &UsageTotals) {
+        self.audio_seconds += other.audio_seconds;
+        self.keepalive_seconds += other.keepalive_seconds;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(default)]
+    pub ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub vendors: BTreeMap<String, UsageTotals>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStore {
+    /// Local date (YYYY-MM-DD) → vendor id → totals.
+    #[serde(default)]
+    pub days: BTreeMap<String, BTreeMap<String, UsageTotals>>,
+    #[serde(default)]
+    pub sessions: Vec<SessionUsage>,
+    /// Month (YYYY-MM) → highest budget threshold already alerted.
+    #[serde(default)]
+    pub budget_alerts: BTreeMap<String, f64>,
+}
+
+impl UsageStore {
+    fn record(&mut self, date: &str, vendor: &str, totals: &UsageTotals) {
+        self.days
+            .entry(date.to_string())
+            .or_default()
+            .entry(vendor.to_string())
+            .or_default()
+            .add(totals);
+        if let Some(session) = self
+            .sessions
+            .last_mut()
+            .filter(|session| session.ended_at.is_none())
+        {
+            session
+                .vendors
+                .entry(vendor.to_string())
+                .or_default()
+                .add(totals);
+        }
+    }
+
+    fn month_totals(&self, month: &str) -> BTreeMap<String, UsageTotals> {
+        let mut totals = BTreeMap::<String, UsageTotals>::new();
+        for (_, vendors) in self.days.iter().filter(|(date, _)| date.starts_with(month)) {
+            for (vendor, usage) in vendors {
+                totals.entry(vendor.clone()).or_default().add(usage);
+            }
+        }
+        totals
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsagePricing {
+    /// Vendor id → USD per audio minute.
+    pub per_minute: BTreeMap<String, f64>,
+    pub monthly_budget: Option<f64>,
+}
+
+impl UsagePricing {
+    pub fn resolve(transcript_config: &TranscriptRuntimeConfig) -> Self {
+        let per_minute =
+            resolve_optional_string(transcript_config.asr_prices.as_deref(), &["ASR_PRICES"])
+                .map(|raw| parse_prices(&raw))
+                .unwrap_or_default();
+        let monthly_budget = resolve_optional_string(
+            transcript_config.asr_monthly_budget.as_deref(),
+            &["ASR_MONTHLY_BUDGET"],
+        )
+        .and_then(|raw| raw.parse::<f64>().ok())
+        .filter(|budget| *budget > 0.0);
+        Self {
+            per_minute,
+            monthly_budget,
+        }
+    }
+
+    /// `None` when no price is configured for the vendor.
+    pub fn cost(&self, vendor: &str, totals: &UsageTotals) -> Option<f64> {
+        self.per_minute
+            .get(vendor)
+            .map(|price| price * totals.billed_seconds() / 60.0)
+    }
+
+    fn total_cost<'a>(
+        &self,
+        vendors: impl IntoIterator<Item = (&'a String, &'a UsageTotals)>,
+    ) -> f64 {
+        vendors
+            .into_iter()
+            .filter_map(|(vendor, totals)| self.cost(vendor, totals))
+            .sum()
+    }
+}
+
+/// `deepgram=0.0077, gladia=0.01`: vendor id and USD per minute, separated
+/// by commas, semicolons or newlines.
+pub fn parse_prices(raw: &str) -> BTreeMap<String, f64> {
+    raw.split([',', ';', '\n'])
+        .filter_map(|entry| {
+            let (vendor, price) = entry.split_once('=').or_else(|| entry.split_once(':'))?;
+            let vendor = vendor.trim().parse::<TranscriptVendors>().ok()?;
+            let price = price
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|price| *price >= 0.0)?;
+            Some((vendor.as_str().to_string(), price))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlert {
+    pub month: String,
+    pub threshold: f64,
+    pub estimated_cost: f64,
+    pub budget: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorUsageReport {
+    pub vendor: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+    pub estimated_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsageReport {
+    pub date: String,
+    pub vendors: Vec<VendorUsageReport>,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsageReport {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub vendors: Vec<VendorUsageReport>,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub currency: &'static str,
+    pub month: String,
+    pub month_vendors: Vec<VendorUsageReport>,
+    pub month_estimated_cost: f64,
+    pub monthly_budget: Option<f64>,
+    pub days: Vec<DailyUsageReport>,
+    pub sessions: Vec<SessionUsageReport>,
+    /// Vendors that have usage but no configured price.
+    pub unpriced_vendors: Vec<String>,
+}
+
+struct UsageMeter {
+    path: PathBuf,
+    store: UsageStore,
+    pricing: UsagePricing,
+    on_budget_alert: Option<BudgetAlertCallback>,
+    last_flush: Instant,
+    /// Estimated cost of `month` so far, kept up to date per chunk instead of
+    /// summing every stored day.
+    month: String,
+    month_cost: f64,
+}
+
+impl UsageMeter {
+    fn open(
+        path: PathBuf,
+        session_id: &str,
+        pricing: UsagePricing,
+        on_budget_alert: Option<BudgetAlertCallback>,
+    ) -> Result<Self, String> {
+        let mut store = load_store_or_back_up(&path)?;
+        store.sessions.push(SessionUsage {
+            session_id: session_id.to_string(),
+            started_at: Utc::now(),
+            ended_at: None,
+            vendors: BTreeMap::new(),
+        });
+        let overflow = store.sessions.len().saturating_sub(MAX_STORED_SESSIONS);
+        store.sessions.drain(..overflow);
+
+        let month = Local::now().format("%Y-%m").to_string();
+        let month_cost = pricing.total_cost(&store.month_totals(&month));
+        let mut meter = Self {
+            path,
+            store,
+            pricing,
+            on_budget_alert,
+            last_flush: Instant::now(),
+            month,
+            month_cost,
+        };
+        meter.flush();
+        Ok(meter)
+    }
+
+    fn record(&mut self, vendor: TranscriptVendors, totals: UsageTotals) {
+        let today = Local::now().date_naive();
+        self.store.record(
+            &today.format("%Y-%m-%d").to_string(),
+            vendor.as_str(),
+            &totals,
+        );
+        let month = today.format("%Y-%m").to_string();
+        if month == self.month {
+            self.month_cost += self.pricing.cost(vendor.as_str(), &totals).unwrap_or(0.0);
+        } else {
+            self.month_cost = self.pricing.total_cost(&self.store.month_totals(&month));
+            self.month = month;
+        }
+        self.check_budget();
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    fn finish(mut self) {
+        if let Some(session) = self
+            .store
+            .sessions
+            .last_mut()
+            .filter(|session| session.ended_at.is_none())
+        {
+            session.ended_at = Some(Utc::now());
+        }
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        if let Err(err) = save_store(&self.path, &self.store) {
+            eprintln!("{err}");
+        }
+        self.last_flush = Instant::now();
+    }
+
+    fn check_budget(&mut self) {
+        let Some(budget) = self.pricing.monthly_budget else {
+            return;
+        };
+        let estimated_cost = self.month_cost;
+        let alerted = self
+            .store
+            .budget_alerts
+            .get(&self.month)
+            .copied()
+            .unwrap_or(0.0);
+        let Some(threshold) = BUDGET_ALERT_THRESHOLDS
+            .into_iter()
+            .rev()
+            .find(|threshold| estimated_cost >= budget * threshold && *threshold > alerted)
+        else {
+            return;
+        };
+
+        self.store
+            .budget_alerts
+            .insert(self.month.clone(), threshold);
+        let alert = BudgetAlert {
+            month: self.month.clone(),
+            threshold,
+            estimated_cost,
+            budget,
+        };
+        eprintln!(
+            "ASR 用量已达到本月预算的 {:.0}%: ${:.2} / ${:.2}",
+            threshold * 100.0,
+            alert.estimated_cost,
+            alert.budget
+        );
+        if let Some(callback) = self.on_budget_alert.as_ref() {
+            callback(alert);
+        }
+        self.flush();
+    }
+}
+
+fn active_meter() -> &'static Mutex<Option<UsageMeter>> {
+    ACTIVE_METER.get_or_init(|| Mutex::new(None))
+}
+
+pub fn usage_file_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("获取应用数据目录失败: {}", err))?;
+    Ok(app_dir.join(USAGE_FILE_NAME))
+}
+
+pub fn load_store(path: &Path) -> Result<UsageStore, String> {
+    match fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(|err| format!("解析用量记录失败: {}", err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(UsageStore::default()),
+        Err(err) => Err(format!("读取用量记录失败: {}", err)),
+    }
+}
+
+/// Like `load_store`, but a file that no longer parses is moved aside so
+/// metering starts over instead of failing every session.
+fn load_store_or_back_up(path: &Path) -> Result<UsageStore, String> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(UsageStore::default());
+        }
+        Err(err) => return Err(format!("读取用量记录失败: {}", err)),
+    };
+    match serde_json::from_str(&raw) {
+        Ok(store) => Ok(store),
+        Err(err) => {
+            let backup = path.with_extension(format!(
+                "json.corrupt-{}",
+                Local::now().format("%Y%m%dT%H%M%S")
+            ));
+            fs::rename(path, &backup).map_err(|err| format!("备份损坏的用量记录失败: {}", err))?;
+            eprintln!(
+                "Usage file {} is corrupt ({err}); moved to {} and starting fresh",
+                path.display(),
+                backup.display()
+            );
+            Ok(UsageStore::default())
+        }
+    }
+}
+
+fn save_store(path: &Path, store: &UsageStore) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| format!("创建用量目录失败: {}", err))?;
+    }
+    let raw = serde_json::to_string_pretty(store)
+        .map_err(|err| format!("序列化用量记录失败: {}", err))?;
+    // Write then rename so a crash never leaves a truncated file behind.
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, raw).map_err(|err| format!("写入用量记录失败: {}", err))?;
+    fs::rename(&temp_path, path).map_err(|err| format!("写入用量记录失败: {}", err))
+}
+
+/// Starts metering a recording session, closing any session left open.
+pub fn begin_usage_session(
+    app: &AppHandle,
+    session_id: &str,
+    transcript_config: Option<&TranscriptRuntimeConfig>,
+) -> Result<(), String> {
+    let path = usage_file_path(app)?;
+    let alert_app = app.clone();
+    let on_budget_alert: BudgetAlertCallback = Arc::new(move |alert: BudgetAlert| {
+        if let Err(err) = alert_app.emit("usage_budget_alert", alert) {
+            eprintln!("Failed to emit usage budget alert: {err}");
+        }
+    });
+    let pricing = UsagePricing::resolve(&transcript_config.cloned().unwrap_or_default());
+
+    end_usage_session();
+    let meter = UsageMeter::open(path, session_id, pricing, Some(on_budget_alert))?;
+    *active_meter().lock().unwrap() = Some(meter);
+    Ok(())
+}
+
+pub fn end_usage_session() {
+    if let Some(meter) = active_meter().lock().unwrap().take() {
+        meter.finish();
+    }
+}
+
+/// Records captured audio sent to `vendor`. A no-op outside a metered session.
+pub fn record_audio_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    record_usage(
+        vendor,
+        UsageTotals {
+            audio_seconds: samples_to_seconds(samples, sample_rate),
+            keepalive_seconds: 0.0,
+        },
+    );
+}
+
+/// Records idle-silence keepalive audio, which vendors bill like speech.
+pub fn record_keepalive_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    record_usage(
+        vendor,
+        UsageTotals {
+            audio_seconds: 0.0,
+            keepalive_seconds: samples_to_seconds(samples, sample_rate),
+        },
+    );
+}
+
+fn samples_to_seconds(samples: usize, sample_rate: u32) -> f64 {
+    if sample_rate == 0 {
+        return 0.0;
+    }
+    samples as f64 / sample_rate as f64
+}
+
+fn record_usage(vendor: TranscriptVendors, totals: UsageTotals) {
+    if let Some(meter) = active_meter().lock().unwrap().as_mut() {
+        meter.record(vendor, totals);
+    }
+}
+
+/// The live session's numbers when one is running, otherwise the stored file.
+pub fn current_usage_store(path: &Path) -> Result<UsageStore, String> {
+    if let Some(meter) = active_meter().lock().unwrap().as_ref() {
+        return Ok(meter.store.clone());
+    }
+    load_store(path)
+}
+
+fn vendor_reports(
+    vendors: &BTreeMap<String, UsageTotals>,
+    pricing: &UsagePricing,
+) -> Vec<VendorUsageReport> {
+    vendors
+        .iter()
+        .map(|(vendor, totals)| VendorUsageReport {
+            vendor: vendor.clone(),
+            totals: *totals,
+            estimated_cost: pricing.cost(vendor, totals),
+        })
+        .collect()
+}
+
+/// Builds the report for `month` (YYYY-MM, default current) plus daily
+/// totals between `from` and `to` inclusive, newest first.
+pub fn build_usage_report(
+    store: &UsageStore,
+    pricing: &UsagePricing,
+    month: Option<&str>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> UsageReport {
+    let month = month
+        .map(ToString::to_string)
+        .unwrap_or_else(|| Local::now().format("%Y-%m").to_string());
+    let in_range = |date: &str| {
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return false;
+        };
+        from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+    };
+
+    let days = store
+        .days
+        .iter()
+        .rev()
+        .filter(|(date, _)| in_range(date))
+        .map(|(date, vendors)| DailyUsageReport {
+            date: date.clone(),
+            vendors: vendor_reports(vendors, pricing),
+            estimated_cost: pricing.total_cost(vendors),
+        })
+        .collect();
+    let sessions = store
+        .sessions
+        .iter()
+        .rev()
+        .filter(|session| {
+            in_range(
+                &session
+                    .started_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d")
+                    .to_string(),
+            )
+        })
+        .map(|session| SessionUsageReport {
+            session_id: session.session_id.clone(),
+            started_at: session.started_at,
+            ended_at: session.ended_at,
+            vendors: vendor_reports(&session.vendors, pricing),
+            estimated_cost: pricing.total_cost(&session.vendors),
+        })
+        .collect();
+    let month_totals = store.month_totals(&month);
+    let mut unpriced_vendors = store
+        .days
+        .values()
+        .flat_map(|vendors| vendors.keys())
+        .filter(|vendor| !pricing.per_minute.contains_key(*vendor))
+        .cloned()
+        .collect::<Vec<_>>();
+    unpriced_vendors.sort();
+    unpriced_vendors.dedup();
+
+    UsageReport {
+        currency: "USD",
+        month_estimated_cost: pricing.total_cost(&month_totals),
+        month_vendors: vendor_reports(&month_totals, pricing),
+        month,
+        monthly_budget: pricing.monthly_budget,
+        days,
+        sessions,
+        unpriced_vendors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BudgetAlert, UsageMeter, UsagePricing, UsageStore, UsageTotals, build_usage_report,
+        load_store, parse_prices,
+    };
+    use crate::transcript_vendors::TranscriptVendors;
+    use chrono::NaiveDate;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    fn temp_usage_file(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("asr_usage_test_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("asr_usage.json")
+    }
+
+    fn seconds(audio_seconds: f64, keepalive_seconds: f64) -> UsageTotals {
+        UsageTotals {
+            audio_seconds,
+            keepalive_seconds,
+        }
+    }
+
+    #[test]
+    fn parse_prices_accepts_known_vendors_only() {
+        let prices = parse_prices("Deepgram=0.0077; gladia: 0.01\nnope=1, revai=-2");
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices["deepgram"], 0.0077);
+        assert_eq!(prices["gladia"], 0.01);
+    }
+
+    #[test]
+    fn meter_counts_keepalives_persists_and_alerts_once() {
+        let path = temp_usage_file("meter");
+        let alerts = Arc::new(Mutex::new(Vec::<BudgetAlert>::new()));
+        let sink = alerts.clone();
+        let pricing = UsagePricing {
+            per_minute: BTreeMap::from([("gladia".to_string(), 1.0)]),
+            monthly_budget: Some(1.0),
+        };
+        let mut meter = UsageMeter::open(
+            path.clone(),
+            "session-1",
+            pricing.clone(),
+            Some(Arc::new(move |alert| sink.lock().unwrap().push(alert))),
+        )
+        .unwrap();
+
+        // 48 s of speech plus 1.5 s of keepalive crosses 80% of a $1 budget.
+        meter.record(TranscriptVendors::GlaDia, seconds(48.0, 0.0));
+        meter.record(TranscriptVendors::GlaDia, seconds(0.0, 1.5));
+        meter.record(TranscriptVendors::RevAI, seconds(0.5, 0.0));
+        meter.record(TranscriptVendors::GlaDia, seconds(0.01, 0.0));
+        meter.finish();
+
+        let alerts = alerts.lock().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold, 0.8);
+
+        let store = load_store(&path).unwrap();
+        let session = &store.sessions[0];
+        assert!(session.ended_at.is_some());
+        let gladia = session.vendors["gladia"];
+        assert!((gladia.audio_seconds - 48.01).abs() < 1e-9);
+        assert!((gladia.keepalive_seconds - 1.5).abs() < 1e-9);
+
+        let report = build_usage_report(&store, &pricing, None, None, None);
+        assert_eq!(report.days.len(), 1);
+        assert!((report.month_estimated_cost - 49.51 / 60.0).abs() < 1e-9);
+        assert_eq!(report.unpriced_vendors, vec!["revai".to_string()]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn corrupt_usage_file_is_backed_up_and_replaced() {
+        let path = temp_usage_file("corrupt");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let meter =
+            UsageMeter::open(path.clone(), "session-1", UsagePricing::default(), None).unwrap();
+        meter.finish();
+
+        assert_eq!(load_store(&path).unwrap().sessions.len(), 1);
+        let backups = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn report_filters_days_by_range() {
+        let mut store = UsageStore::default();
+        store.record("2026-03-01", "deepgram", &seconds(60.0, 0.0));
+        store.record("2026-03-15", "deepgram", &seconds(30.0, 30.0));
+        store.record("2026-04-01", "deepgram", &seconds(60.0, 0.0));
+        let pricing = UsagePricing {
+            per_minute: BTreeMap::from([("deepgram".to_string(), 0.5)]),
+            monthly_budget: None,
+        };
+
+        let report = build_usage_report(
+            &store,
+            &pricing,
+            Some("2026-03"),
+            NaiveDate::from_ymd_opt(2026, 3, 10),
+            None,
+        );
+        let dates = report
+            .days
+            .iter()
+            .map(|day| day.date.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(dates, vec!["2026-04-01", "2026-03-15"]);
+        assert_eq!(report.month_estimated_cost, 1.0);
+    }
+}
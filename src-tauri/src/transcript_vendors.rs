@@ -1,32 +1,36 @@
-use serde::Serialize;
+use crate::provider_config::{TranscriptRuntimeConfig, resolve_optional_string};
+use crate::{transcript_latency, transcript_usage};
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use std::sync::Arc;
 
-#[cfg(all(feature = "api", feature = "sdk"))]
-compile_error!("Deepgram features 'api' and 'sdk' are mutually exclusive. Enable only one.");
-
-#[cfg(not(any(feature = "api", feature = "sdk")))]
-compile_error!("One Deepgram feature must be enabled. Use 'api' or 'sdk'.");
-
 pub mod assemblyai;
-#[cfg(feature = "api")]
+pub mod capabilities;
+pub mod comparison;
+pub mod dashscope;
 pub mod deepgram_api;
-#[cfg(feature = "sdk")]
 pub mod deepgram_sdk;
+pub mod failover;
 pub mod gladia;
+pub mod language;
+#[cfg(any(test, feature = "mock-asr"))]
+pub mod mock_server;
+pub mod openai_compatible;
+pub mod openai_realtime;
+pub mod resample;
 pub mod revai;
 pub mod speechmatics;
+pub mod token_broker;
+pub mod vocabulary;
+pub mod volcengine;
 
-#[cfg(all(feature = "api", not(feature = "sdk")))]
-pub use deepgram_api::DeepgramApiTranscriber as SelectedDeepgramTranscriber;
-#[cfg(all(feature = "sdk", not(feature = "api")))]
-pub use deepgram_sdk::DeepgramTranscriber as SelectedDeepgramTranscriber;
-
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum TranscriptEventKind {
     Draft,
     Commit,
+    /// Vendor-native translation of the latest commit; `language` is the target.
+    Translation,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -35,6 +39,12 @@ pub struct TranscriptEvent {
     pub vendor: String,
     pub kind: TranscriptEventKind,
     pub text: String,
+    /// Language the vendor detected for this text, when it reports one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Sequence number of a commit within the session, set when translation is on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utterance_id: Option<u64>,
 }
 
 pub type PcmCallback = Arc<dyn Fn(TranscriptEvent) + Send + Sync + 'static>;
@@ -48,6 +58,27 @@ pub fn emit_commit(callback: &PcmCallback, vendor: &str, text: impl Into<String>
     emit_transcript_event(callback, vendor, TranscriptEventKind::Commit, text);
 }
 
+pub fn emit_translation(
+    callback: &PcmCallback,
+    vendor: &str,
+    target_language: &str,
+    text: impl Into<String>,
+) {
+    let text = text.into();
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return;
+    }
+
+    callback(TranscriptEvent {
+        vendor: vendor.to_string(),
+        kind: TranscriptEventKind::Translation,
+        text: trimmed.to_string(),
+        language: Some(target_language.to_string()),
+        utterance_id: None,
+    });
+}
+
 fn emit_transcript_event(
     callback: &PcmCallback,
     vendor: &str,
@@ -64,9 +95,38 @@ fn emit_transcript_event(
         vendor: vendor.to_string(),
         kind,
         text: trimmed.to_string(),
+        language: None,
+        utterance_id: None,
     });
 }
 
+/// Called by the sink loops right after captured audio reaches the socket;
+/// feeds usage metering and latency timing.
+pub(crate) fn report_audio_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    transcript_usage::record_audio_sent(vendor, samples, sample_rate);
+    transcript_latency::note_audio_sent(vendor, samples, sample_rate);
+}
+
+/// Same as `report_audio_sent` for idle-silence keepalives.
+pub(crate) fn report_keepalive_sent(vendor: TranscriptVendors, samples: usize, sample_rate: u32) {
+    transcript_usage::record_keepalive_sent(vendor, samples, sample_rate);
+    transcript_latency::note_keepalive_sent(vendor, samples, sample_rate);
+}
+
+/// Percent-encodes a query-string value (RFC 3986 unreserved characters pass through).
+pub(crate) fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
 pub trait StreamingTranscriber: Send + Sync {
     fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String>;
     fn get_vendor_name(&self) -> String;
@@ -77,13 +137,18 @@ pub trait StreamingTranscriber: Send + Sync {
     fn shutdown(&self);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Per-vendor features and required settings live in `capabilities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TranscriptVendors {
     DeepGram,
-    RevAI,      //Normal
-    AssemblyAI, //Normal
-    GlaDia,     // No punctuation
+    RevAI,
+    AssemblyAI,
+    GlaDia,
     SpeechMatics,
+    OpenAiCompatible,
+    OpenAiRealtime,
+    DashScope,
+    Volcengine,
 }
 
 impl FromStr for TranscriptVendors {
@@ -96,7 +161,225 @@ impl FromStr for TranscriptVendors {
             "assemblyai" => Ok(TranscriptVendors::AssemblyAI),
             "gladia" => Ok(TranscriptVendors::GlaDia),
             "speechmatics" => Ok(TranscriptVendors::SpeechMatics),
+            "openai_compatible" => Ok(TranscriptVendors::OpenAiCompatible),
+            "openai_realtime" => Ok(TranscriptVendors::OpenAiRealtime),
+            "dashscope" => Ok(TranscriptVendors::DashScope),
+            "volcengine" => Ok(TranscriptVendors::Volcengine),
             _ => Err(format!("Unknown vendor: {}", s)),
         }
     }
 }
+
+impl TranscriptVendors {
+    pub const ALL: [TranscriptVendors; 9] = [
+        TranscriptVendors::DeepGram,
+        TranscriptVendors::RevAI,
+        TranscriptVendors::AssemblyAI,
+        TranscriptVendors::GlaDia,
+        TranscriptVendors::SpeechMatics,
+        TranscriptVendors::OpenAiCompatible,
+        TranscriptVendors::OpenAiRealtime,
+        TranscriptVendors::DashScope,
+        TranscriptVendors::Volcengine,
+    ];
+
+    /// The identifier accepted by `FromStr` and used by the frontend.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptVendors::DeepGram => "deepgram",
+            TranscriptVendors::RevAI => "revai",
+            TranscriptVendors::AssemblyAI => "assemblyai",
+            TranscriptVendors::GlaDia => "gladia",
+            TranscriptVendors::SpeechMatics => "speechmatics",
+            TranscriptVendors::OpenAiCompatible => "openai_compatible",
+            TranscriptVendors::OpenAiRealtime => "openai_realtime",
+            TranscriptVendors::DashScope => "dashscope",
+            TranscriptVendors::Volcengine => "volcengine",
+        }
+    }
+}
+
+/// Which Deepgram integration a session uses. Both are always compiled in;
+/// the `api`/`sdk` cargo features only choose the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeepgramBackend {
+    Api,
+    Sdk,
+}
+
+impl DeepgramBackend {
+    pub fn build_default() -> Self {
+        if cfg!(all(feature = "sdk", not(feature = "api"))) {
+            DeepgramBackend::Sdk
+        } else {
+            DeepgramBackend::Api
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeepgramBackend::Api => "api",
+            DeepgramBackend::Sdk => "sdk",
+        }
+    }
+
+    /// `deepgram_backend` or `DEEPGRAM_BACKEND`; unknown values fall back to the default.
+    pub fn resolve(transcript_config: &TranscriptRuntimeConfig) -> Self {
+        let Some(value) = resolve_optional_string(
+            transcript_config.deepgram_backend.as_deref(),
+            &["DEEPGRAM_BACKEND"],
+        ) else {
+            return Self::build_default();
+        };
+        match value.to_lowercase().as_str() {
+            "api" => DeepgramBackend::Api,
+            "sdk" => DeepgramBackend::Sdk,
+            _ => {
+                eprintln!("Unknown Deepgram backend {value}, using the default");
+                Self::build_default()
+            }
+        }
+    }
+}
+
+pub fn start_transcriber(
+    vendor: TranscriptVendors,
+    sample_rate: u32,
+    callback: PcmCallback,
+    status_callback: Option<StatusCallback>,
+    transcript_config: TranscriptRuntimeConfig,
+) -> Result<Arc<dyn StreamingTranscriber>, String> {
+    let transcriber: Arc<dyn StreamingTranscriber> = match vendor {
+        TranscriptVendors::AssemblyAI => Arc::new(
+            assemblyai::AssemblyAiTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start AssemblyAI stream: {e}"))?,
+        ),
+        TranscriptVendors::RevAI => Arc::new(
+            revai::RevAiTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start RevAI stream: {e}"))?,
+        ),
+        TranscriptVendors::DeepGram => match DeepgramBackend::resolve(&transcript_config) {
+            DeepgramBackend::Api => Arc::new(
+                deepgram_api::DeepgramApiTranscriber::start(
+                    sample_rate,
+                    callback,
+                    status_callback,
+                    transcript_config,
+                )
+                .map_err(|e| format!("Failed to start Deepgram stream: {e}"))?,
+            ),
+            DeepgramBackend::Sdk => Arc::new(
+                deepgram_sdk::DeepgramTranscriber::start(
+                    sample_rate,
+                    callback,
+                    status_callback,
+                    transcript_config,
+                )
+                .map_err(|e| format!("Failed to start Deepgram SDK stream: {e}"))?,
+            ),
+        },
+        TranscriptVendors::SpeechMatics => Arc::new(
+            speechmatics::SpeechmaticsTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start Speechmatics stream: {e}"))?,
+        ),
+        TranscriptVendors::GlaDia => Arc::new(
+            gladia::GladiaTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start Gladia stream: {e}"))?,
+        ),
+        TranscriptVendors::OpenAiCompatible => Arc::new(
+            openai_compatible::OpenAiCompatibleTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start OpenAI-compatible transcription: {e}"))?,
+        ),
+        TranscriptVendors::OpenAiRealtime => Arc::new(
+            openai_realtime::OpenAiRealtimeTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start OpenAI realtime stream: {e}"))?,
+        ),
+        TranscriptVendors::DashScope => Arc::new(
+            dashscope::DashScopeTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start DashScope stream: {e}"))?,
+        ),
+        TranscriptVendors::Volcengine => Arc::new(
+            volcengine::VolcengineTranscriber::start(
+                sample_rate,
+                callback,
+                status_callback,
+                transcript_config,
+            )
+            .map_err(|e| format!("Failed to start Volcengine stream: {e}"))?,
+        ),
+    };
+
+    Ok(transcriber)
+}
+
+/// Starts the transcriber for a recording session: comparison mode when extra
+/// comparison vendors are configured, otherwise the selected vendor with failover.
+pub fn start_session_transcriber(
+    selected: TranscriptVendors,
+    sample_rate: u32,
+    callback: PcmCallback,
+    status_callback: Option<StatusCallback>,
+    switch_callback: Option<failover::VendorSwitchCallback>,
+    comparison_callback: Option<comparison::ComparisonCallback>,
+    transcript_config: TranscriptRuntimeConfig,
+) -> Result<Arc<dyn StreamingTranscriber>, String> {
+    let comparison_vendors = failover::build_vendor_attempt_plan(
+        selected,
+        transcript_config.asr_comparison_vendors.as_deref(),
+    );
+    if comparison_vendors.len() > 1 {
+        let transcriber = comparison::ComparisonTranscriber::start(
+            comparison_vendors,
+            sample_rate,
+            callback,
+            status_callback,
+            comparison_callback,
+            transcript_config,
+        )?;
+        return Ok(Arc::new(transcriber));
+    }
+
+    failover::start_transcriber_with_failover(
+        selected,
+        sample_rate,
+        callback,
+        status_callback,
+        switch_callback,
+        transcript_config,
+    )
+}
@@ -1,9 +1,17 @@
 #![allow(clippy::collapsible_if)]
 
 ///https://www.assemblyai.com/docs/api-reference/streaming-api/universal-streaming/universal-streaming
-use crate::provider_config::{TranscriptRuntimeConfig, resolve_required_string};
+use crate::provider_config::{
+    ASSEMBLY_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string, resolve_string_or_default,
+};
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_latency::{note_result_offset, note_stream_started};
+use crate::transcript_vendors::language::{DetectedLanguage, LanguageSetting};
+use crate::transcript_vendors::token_broker::{CredentialSource, VendorCredential};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    encode_query_component, report_audio_sent,
 };
 use futures_util::{SinkExt, StreamExt, future::try_join};
 use serde_json::{Value, json};
@@ -15,7 +23,7 @@ use tauri::http::Uri;
 use tokio::runtime::Runtime;
 use tokio::sync::{Mutex as AsyncMutex, mpsc, oneshot, watch};
 use tokio::time::{self, MissedTickBehavior};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::tungstenite::protocol::Message;
 use tungstenite::client::{ClientRequestBuilder, IntoClientRequest};
 
 const BASE_URL: &str = "wss://streaming.assemblyai.com/v3/ws";
@@ -44,11 +52,28 @@ impl AssemblyAiTranscriber {
         status_callback: Option<StatusCallback>,
         transcript_config: TranscriptRuntimeConfig,
     ) -> Result<Self, String> {
-        let api_key = resolve_required_string(
-            transcript_config.assembly_api_key.as_deref(),
-            &["ASSEMBLY_API_KEY"],
+        let credentials = CredentialSource::resolve(
+            TranscriptVendors::AssemblyAI,
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.assembly_api_key.as_deref(),
+                ASSEMBLY_ENV_KEYS,
+            ),
             "ASSEMBLY_API_KEY",
         )?;
+        let ws_url = resolve_string_or_default(
+            transcript_config.assembly_ws_url.as_deref(),
+            &["ASSEMBLY_WS_URL"],
+            BASE_URL,
+        );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::AssemblyAI.as_str(), &ws_url);
+        let vocabulary = resolve_vocabulary(&transcript_config);
+        // AssemblyAI has no per-vendor language field; only the unified mode applies.
+        let language = LanguageSetting::resolve(&transcript_config, None);
+        if let Some(fixed) = language.fixed_language() {
+            eprintln!("AssemblyAI streaming cannot pin a language; ignoring {fixed}");
+        }
 
         let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
         let (shutdown, shutdown_rx) = oneshot::channel::<()>();
@@ -58,7 +83,11 @@ impl AssemblyAiTranscriber {
         let handle = thread::spawn(move || {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_stream(
-                api_key,
+                credentials,
+                ws_url,
+                proxy,
+                language,
+                vocabulary,
                 sample_rate,
                 callback,
                 receiver,
@@ -111,31 +140,43 @@ impl Drop for AssemblyAiTranscriber {
 }
 
 async fn run_stream(
-    api_key: String,
+    credentials: CredentialSource,
+    ws_url: String,
+    proxy: Option<String>,
+    language: LanguageSetting,
+    vocabulary: Vec<String>,
     sample_rate: u32,
     callback: PcmCallback,
     mut audio_rx: mpsc::Receiver<StreamCommand>,
     mut shutdown_rx: oneshot::Receiver<()>,
     stop_requested: Arc<AtomicBool>,
 ) -> Result<(), String> {
-    let query = format!(
-        "sample_rate={sample_rate}&speech_model={SPEECH_MODEL}&encoding={AUDIO_ENCODING}&format_turns=true&min_turn_silence={MIN_TURN_SILENCE_MS}&inactivity_timeout={INACTIVITY_TIMEOUT_SECS}"
-    );
-    let url = format!("{BASE_URL}?{query}");
+    let mut url = build_streaming_url(&ws_url, &language, &vocabulary, sample_rate);
+    let credential = credentials.fetch(None).await?;
+    // Temporary tokens ride on the query string; only master keys use the header.
+    if let VendorCredential::Token(token) = &credential {
+        url.push_str(&format!("&token={}", encode_query_component(token)));
+    }
+    let detected_language = DetectedLanguage::default();
+    let callback = detected_language.wrap_callback(callback);
 
     let uri: Uri = url
         .parse()
         .map_err(|e| format!("Failed to parse streaming URI: {e}"))?;
-    let builder = ClientRequestBuilder::new(uri)
-        .with_header("Authorization", api_key)
-        .with_header("Content-Type", "application/json");
+    let mut builder =
+        ClientRequestBuilder::new(uri).with_header("Content-Type", "application/json");
+    if let VendorCredential::ApiKey(api_key) = credential {
+        builder = builder.with_header("Authorization", api_key);
+    }
     let client_request = builder
         .into_client_request()
         .map_err(|e| format!("Failed to build websocket request: {e}"))?;
 
-    let (ws_stream, _) = connect_async(client_request)
+    let (ws_stream, _) = connect_websocket(client_request, proxy.as_deref())
         .await
         .map_err(|e| format!("Failed to connect to AssemblyAI: {e}"))?;
+    // Result offsets restart with every connection.
+    note_stream_started(TranscriptVendors::AssemblyAI);
 
     let (mut sink, mut stream) = ws_stream.split();
     let (termination_tx, mut termination_rx) = watch::channel(false);
@@ -162,6 +203,7 @@ async fn run_stream(
                         sink.send(Message::Binary(audio_bytes.into()))
                             .await
                             .map_err(|e| format!("Failed to send audio chunk: {e}"))?;
+                        report_audio_sent(TranscriptVendors::AssemblyAI, samples.len(), sample_rate);
                     }
                     Some(StreamCommand::ForceEndpoint) => {
                         let payload = json!({ "type": "ForceEndpoint" });
@@ -197,6 +239,7 @@ async fn run_stream(
     let receive_events = {
         let callback = callback.clone();
         let termination_tx = termination_tx.clone();
+        let detected_language = detected_language.clone();
 
         async move {
             let mut last_emitted: Option<(bool, String)> = None;
@@ -221,6 +264,8 @@ async fn run_stream(
                                     );
                                 }
                                 _ => {
+                                    detected_language
+                                        .update(value.get("language_code").and_then(Value::as_str));
                                     let transcripts = extract_transcripts(&value);
                                     for (transcript, is_final) in transcripts {
                                         let normalized = normalize_transcript_text(&transcript);
@@ -236,6 +281,12 @@ async fn run_stream(
 
                                         last_emitted = Some(next_event);
                                         if is_final {
+                                            if let Some(end) = turn_audio_end(&value) {
+                                                note_result_offset(
+                                                    TranscriptVendors::AssemblyAI,
+                                                    end,
+                                                );
+                                            }
                                             let commit = {
                                                 let mut buffer = utterance_buffer.lock().await;
                                                 append_utterance_segment(&mut buffer, &normalized);
@@ -311,6 +362,30 @@ fn extract_transcripts(value: &Value) -> Vec<(String, bool)> {
     transcripts
 }
 
+fn build_streaming_url(
+    ws_url: &str,
+    language: &LanguageSetting,
+    vocabulary: &[String],
+    sample_rate: u32,
+) -> String {
+    let mut query = format!(
+        "sample_rate={sample_rate}&speech_model={SPEECH_MODEL}&encoding={AUDIO_ENCODING}&format_turns=true&min_turn_silence={MIN_TURN_SILENCE_MS}&inactivity_timeout={INACTIVITY_TIMEOUT_SECS}"
+    );
+    if language.is_multilingual() {
+        // Turns then carry a `language_code`.
+        query.push_str("&language_detection=true");
+    }
+    if !vocabulary.is_empty() {
+        // `keyterms_prompt` is a JSON array passed as a single query value.
+        let keyterms = Value::from(vocabulary.to_vec()).to_string();
+        query.push_str(&format!(
+            "&keyterms_prompt={}",
+            encode_query_component(&keyterms)
+        ));
+    }
+    format!("{ws_url}?{query}")
+}
+
 fn resolve_event_type(value: &Value) -> Option<&str> {
     value
         .get("type")
@@ -318,6 +393,18 @@ fn resolve_event_type(value: &Value) -> Option<&str> {
         .and_then(|entry| entry.as_str())
 }
 
+/// End of the turn's last word, in seconds from the stream start. Words carry
+/// millisecond offsets.
+fn turn_audio_end(value: &Value) -> Option<f64> {
+    value
+        .get("words")
+        .and_then(Value::as_array)?
+        .iter()
+        .filter_map(|word| word.get("end").and_then(Value::as_f64))
+        .reduce(f64::max)
+        .map(|end_ms| end_ms / 1000.0)
+}
+
 fn extract_turn_transcripts(value: &Value) -> Vec<(String, bool)> {
     let text = first_non_empty_text(value, &["utterance", "transcript"]);
     let Some(text) = text else {
@@ -560,11 +647,41 @@ impl StreamingTranscriber for AssemblyAiTranscriber {
 #[cfg(test)]
 mod tests {
     use super::{
-        append_utterance_segment, extract_plain_transcripts, extract_turn_transcripts,
-        merge_segments, normalize_transcript_dedup_key, normalize_transcript_text,
+        BASE_URL, append_utterance_segment, build_streaming_url, extract_plain_transcripts,
+        extract_turn_transcripts, merge_segments, normalize_transcript_dedup_key,
+        normalize_transcript_text, turn_audio_end,
     };
+    use crate::transcript_vendors::language::LanguageSetting;
     use serde_json::json;
 
+    #[test]
+    fn turn_audio_end_uses_last_word_in_seconds() {
+        let turn = json!({
+            "type": "Turn",
+            "end_of_turn": true,
+            "words": [{ "text": "hi", "start": 120, "end": 480 }, { "text": "there", "start": 500, "end": 910 }]
+        });
+        assert_eq!(turn_audio_end(&turn), Some(0.91));
+        assert_eq!(turn_audio_end(&json!({ "type": "Turn" })), None);
+    }
+
+    #[test]
+    fn streaming_url_encodes_keyterms_prompt_as_json_array() {
+        let url = build_streaming_url(BASE_URL, &LanguageSetting::VendorDefault, &[], 16_000);
+        assert!(!url.contains("keyterms_prompt"));
+        assert!(!url.contains("language_detection"));
+
+        let url = build_streaming_url(
+            BASE_URL,
+            &LanguageSetting::CodeSwitching(vec!["zh".to_string(), "en".to_string()]),
+            &["gRPC".to_string(), "Tokio".to_string()],
+            16_000,
+        );
+        assert!(url.contains("&language_detection=true"));
+        assert!(url.starts_with("wss://streaming.assemblyai.com/v3/ws?sample_rate=16000&"));
+        assert!(url.ends_with("&keyterms_prompt=%5B%22gRPC%22%2C%22Tokio%22%5D"));
+    }
+
     #[test]
     fn turn_transcript_marks_non_final_turns_as_draft() {
         let value = json!({
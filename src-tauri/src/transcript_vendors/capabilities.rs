@@ -0,0 +1,320 @@
+//! Static capability descriptors for each transcription vendor, so the
+//! frontend and config validation don't have to hard-code vendor quirks.
+
+use crate::provider_config::{
+    ALI_QWEN_ENV_KEYS, ASSEMBLY_ENV_KEYS, DEEPGRAM_ENV_KEYS, GLADIA_ENV_KEYS,
+    OPENAI_COMPATIBLE_ASR_ENV_KEYS, OPENAI_ENV_KEYS, REVAI_ENV_KEYS, SPEECHMATICS_ENV_KEYS,
+    TranscriptRuntimeConfig, VOLCENGINE_ASR_ACCESS_ENV_KEYS, VOLCENGINE_ASR_APP_ENV_KEYS,
+    resolve_optional_string,
+};
+use crate::transcript_vendors::token_broker::uses_token_service;
+use crate::transcript_vendors::{DeepgramBackend, TranscriptVendors};
+use serde::Serialize;
+
+/// A setting the vendor cannot start without, named as in the frontend settings.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredConfigKey {
+    pub field: &'static str,
+    pub env_keys: &'static [&'static str],
+    #[serde(skip)]
+    read: fn(&TranscriptRuntimeConfig) -> Option<&str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorCapabilities {
+    pub vendor: &'static str,
+    /// False when the build left the vendor out.
+    pub compiled_in: bool,
+    /// Which Deepgram integration is selected (`api`/`sdk`); `None` for other vendors.
+    pub backend: Option<&'static str>,
+    /// Primary language subtags accepted by the realtime API.
+    pub languages: &'static [&'static str],
+    pub auto_language_detection: bool,
+    pub code_switching: bool,
+    /// Rate the audio is resampled to before upload; `None` streams the
+    /// capture rate as-is.
+    pub resampled_rate: Option<u32>,
+    pub drafts: bool,
+    pub punctuation: bool,
+    pub force_endpoint: bool,
+    pub diarization: bool,
+    pub translation: bool,
+    pub vocabulary: bool,
+    pub required_config: &'static [RequiredConfigKey],
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorCapabilityReport {
+    #[serde(flatten)]
+    pub capabilities: VendorCapabilities,
+    /// Required settings that are neither configured nor found in the env.
+    pub missing_config: Vec<&'static str>,
+}
+
+const MAJOR_LANGUAGES: &[&str] = &[
+    "zh", "en", "ja", "ko", "es", "fr", "de", "it", "pt", "ru", "nl", "hi", "ar", "tr", "pl", "vi",
+    "id", "th", "uk", "sv",
+];
+const DEEPGRAM_LANGUAGES: &[&str] = &[
+    "zh", "en", "ja", "ko", "es", "fr", "de", "it", "pt", "ru", "nl", "hi", "tr", "pl", "uk", "sv",
+    "id", "vi",
+];
+const REVAI_LANGUAGES: &[&str] = &["en", "es", "fr", "de", "it", "pt", "ja", "ko", "zh"];
+const DASHSCOPE_LANGUAGES: &[&str] = &["zh", "en", "ja", "ko", "de", "fr", "ru"];
+const VOLCENGINE_LANGUAGES: &[&str] = &["zh", "en"];
+
+macro_rules! required {
+    ($field:literal, $env_keys:expr, $member:ident) => {
+        RequiredConfigKey {
+            field: $field,
+            env_keys: $env_keys,
+            read: |config| config.$member.as_deref(),
+        }
+    };
+}
+
+const DEEPGRAM_REQUIRED: &[RequiredConfigKey] = &[required!(
+    "deepgramApiKey",
+    DEEPGRAM_ENV_KEYS,
+    deepgram_api_key
+)];
+const REVAI_REQUIRED: &[RequiredConfigKey] =
+    &[required!("revaiApiKey", REVAI_ENV_KEYS, revai_api_key)];
+const ASSEMBLY_REQUIRED: &[RequiredConfigKey] = &[required!(
+    "assemblyApiKey",
+    ASSEMBLY_ENV_KEYS,
+    assembly_api_key
+)];
+const GLADIA_REQUIRED: &[RequiredConfigKey] =
+    &[required!("gladiaApiKey", GLADIA_ENV_KEYS, gladia_api_key)];
+const SPEECHMATICS_REQUIRED: &[RequiredConfigKey] = &[required!(
+    "speechmaticsApiKey",
+    SPEECHMATICS_ENV_KEYS,
+    speechmatics_api_key
+)];
+const OPENAI_COMPATIBLE_REQUIRED: &[RequiredConfigKey] = &[
+    required!(
+        "openaiCompatibleApiKey",
+        OPENAI_COMPATIBLE_ASR_ENV_KEYS,
+        openai_compatible_api_key
+    ),
+    required!(
+        "openaiCompatibleBaseUrl",
+        &["OPENAI_COMPATIBLE_ASR_BASE_URL"],
+        openai_compatible_base_url
+    ),
+];
+const OPENAI_REALTIME_REQUIRED: &[RequiredConfigKey] = &[required!(
+    "openaiRealtimeApiKey",
+    OPENAI_ENV_KEYS,
+    openai_realtime_api_key
+)];
+const DASHSCOPE_REQUIRED: &[RequiredConfigKey] = &[required!(
+    "dashscopeApiKey",
+    ALI_QWEN_ENV_KEYS,
+    dashscope_api_key
+)];
+const VOLCENGINE_REQUIRED: &[RequiredConfigKey] = &[
+    required!(
+        "volcengineAppKey",
+        VOLCENGINE_ASR_APP_ENV_KEYS,
+        volcengine_app_key
+    ),
+    required!(
+        "volcengineAccessKey",
+        VOLCENGINE_ASR_ACCESS_ENV_KEYS,
+        volcengine_access_key
+    ),
+];
+
+impl TranscriptVendors {
+    pub fn capabilities(&self) -> VendorCapabilities {
+        let base = VendorCapabilities {
+            vendor: self.as_str(),
+            compiled_in: true,
+            backend: None,
+            languages: MAJOR_LANGUAGES,
+            auto_language_detection: false,
+            code_switching: false,
+            resampled_rate: None,
+            drafts: true,
+            punctuation: true,
+            force_endpoint: false,
+            diarization: false,
+            translation: false,
+            vocabulary: false,
+            required_config: &[],
+        };
+
+        match self {
+            TranscriptVendors::DeepGram => VendorCapabilities {
+                backend: Some(DeepgramBackend::build_default().as_str()),
+                languages: DEEPGRAM_LANGUAGES,
+                // `language=multi` only; streaming has no detection of its own.
+                code_switching: true,
+                // `punctuate=false` keeps drafts stable for the dedupe in audio_stream.
+                punctuation: false,
+                force_endpoint: true,
+                vocabulary: true,
+                required_config: DEEPGRAM_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::RevAI => VendorCapabilities {
+                languages: REVAI_LANGUAGES,
+                vocabulary: true,
+                required_config: REVAI_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::AssemblyAI => VendorCapabilities {
+                auto_language_detection: true,
+                code_switching: true,
+                force_endpoint: true,
+                vocabulary: true,
+                required_config: ASSEMBLY_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::GlaDia => VendorCapabilities {
+                auto_language_detection: true,
+                code_switching: true,
+                punctuation: false,
+                translation: true,
+                vocabulary: true,
+                required_config: GLADIA_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::SpeechMatics => VendorCapabilities {
+                // Bilingual packs cover cmn_en, en_ms and en_ta; other pairs fall back.
+                auto_language_detection: true,
+                code_switching: true,
+                force_endpoint: true,
+                translation: true,
+                vocabulary: true,
+                required_config: SPEECHMATICS_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::OpenAiCompatible => VendorCapabilities {
+                // Whole utterances are uploaded after local VAD, so no drafts.
+                drafts: false,
+                force_endpoint: true,
+                required_config: OPENAI_COMPATIBLE_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::OpenAiRealtime => VendorCapabilities {
+                resampled_rate: Some(24_000),
+                force_endpoint: true,
+                required_config: OPENAI_REALTIME_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::DashScope => VendorCapabilities {
+                languages: DASHSCOPE_LANGUAGES,
+                required_config: DASHSCOPE_REQUIRED,
+                ..base
+            },
+            TranscriptVendors::Volcengine => VendorCapabilities {
+                languages: VOLCENGINE_LANGUAGES,
+                resampled_rate: Some(16_000),
+                required_config: VOLCENGINE_REQUIRED,
+                ..base
+            },
+        }
+    }
+
+    /// Required settings still missing from `config` and the environment.
+    /// Keys a token service supplies don't count.
+    pub fn missing_config(&self, transcript_config: &TranscriptRuntimeConfig) -> Vec<&'static str> {
+        if uses_token_service(*self, transcript_config) {
+            return Vec::new();
+        }
+        self.capabilities()
+            .required_config
+            .iter()
+            .filter(|key| {
+                resolve_optional_string((key.read)(transcript_config), key.env_keys).is_none()
+            })
+            .map(|key| key.field)
+            .collect()
+    }
+}
+
+pub fn vendor_capability_reports(
+    transcript_config: &TranscriptRuntimeConfig,
+) -> Vec<VendorCapabilityReport> {
+    TranscriptVendors::ALL
+        .iter()
+        .map(|vendor| {
+            let mut capabilities = vendor.capabilities();
+            if *vendor == TranscriptVendors::DeepGram {
+                capabilities.backend = Some(DeepgramBackend::resolve(transcript_config).as_str());
+            }
+            VendorCapabilityReport {
+                capabilities,
+                missing_config: vendor.missing_config(transcript_config),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vendor_capability_reports;
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::TranscriptVendors;
+
+    #[test]
+    fn descriptors_match_vendor_implementations() {
+        let with_force_endpoint = TranscriptVendors::ALL
+            .iter()
+            .filter(|vendor| vendor.capabilities().force_endpoint)
+            .map(TranscriptVendors::as_str)
+            .collect::<Vec<_>>();
+        assert!(with_force_endpoint.contains(&"assemblyai"));
+        assert!(with_force_endpoint.contains(&"deepgram"));
+        assert!(with_force_endpoint.contains(&"speechmatics"));
+        assert!(!with_force_endpoint.contains(&"gladia"));
+
+        let translation = TranscriptVendors::ALL
+            .iter()
+            .filter(|vendor| vendor.capabilities().translation)
+            .map(TranscriptVendors::as_str)
+            .collect::<Vec<_>>();
+        assert_eq!(translation, vec!["gladia", "speechmatics"]);
+
+        assert!(!TranscriptVendors::OpenAiCompatible.capabilities().drafts);
+        assert!(TranscriptVendors::DeepGram.capabilities().compiled_in);
+    }
+
+    #[test]
+    fn missing_config_prefers_settings_over_env() {
+        let config = TranscriptRuntimeConfig {
+            volcengine_app_key: Some("app".to_string()),
+            volcengine_access_key: Some("  ".to_string()),
+            ..TranscriptRuntimeConfig::default()
+        };
+        let missing = TranscriptVendors::Volcengine.missing_config(&config);
+        if std::env::var("VOLCENGINE_ASR_ACCESS_TOKEN").is_err()
+            && std::env::var("VOLC_ASR_ACCESS_TOKEN").is_err()
+        {
+            assert_eq!(missing, vec!["volcengineAccessKey"]);
+        }
+
+        let reports = vendor_capability_reports(&config);
+        assert_eq!(reports.len(), TranscriptVendors::ALL.len());
+        let json = serde_json::to_value(&reports[0]).unwrap();
+        assert_eq!(json["vendor"], "deepgram");
+        assert_eq!(json["requiredConfig"][0]["field"], "deepgramApiKey");
+        if std::env::var("DEEPGRAM_BACKEND").is_err() {
+            let sdk = TranscriptRuntimeConfig {
+                deepgram_backend: Some("SDK".to_string()),
+                ..TranscriptRuntimeConfig::default()
+            };
+            assert_eq!(
+                vendor_capability_reports(&sdk)[0].capabilities.backend,
+                Some("sdk")
+            );
+        }
+        assert!(json["missingConfig"].is_array());
+    }
+}
@@ -0,0 +1,521 @@
+#![allow(clippy::collapsible_if)]
+
+use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptEvent, TranscriptEventKind,
+    TranscriptVendors, start_transcriber,
+};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Only the tail of each transcript is compared so a long session stays cheap.
+const DISAGREEMENT_WINDOW_TOKENS: usize = 1_000;
+const SPEECH_PEAK_THRESHOLD: u16 = 900;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorComparisonMetrics {
+    pub vendor: String,
+    pub utterances: u32,
+    pub last_first_draft_ms: Option<u64>,
+    pub avg_first_draft_ms: Option<u64>,
+    pub last_final_ms: Option<u64>,
+    pub avg_final_ms: Option<u64>,
+    /// The commit that produced this report, so listeners can rebuild the text.
+    pub last_commit: Option<String>,
+    /// Whole session transcript; only filled in the final report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub committed_text: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorDisagreement {
+    pub left: String,
+    pub right: String,
+    /// Token edit distance divided by the longer transcript, 0.0 means identical.
+    pub rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonReport {
+    pub vendors: Vec<VendorComparisonMetrics>,
+    pub disagreements: Vec<VendorDisagreement>,
+}
+
+/// A single vendor event in comparison mode. `report` is only filled on
+/// commits. The final update sent at shutdown has no `event` and carries the
+/// full committed text of every vendor.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonUpdate {
+    pub vendor: String,
+    pub event: Option<TranscriptEvent>,
+    pub report: Option<ComparisonReport>,
+}
+
+pub type ComparisonCallback = Arc<dyn Fn(ComparisonUpdate) + Send + Sync + 'static>;
+
+pub struct ComparisonTranscriber {
+    vendors: Vec<(TranscriptVendors, Arc<dyn StreamingTranscriber>)>,
+    tracker: Arc<Mutex<ComparisonTracker>>,
+    exhausted: Arc<AtomicBool>,
+    comparison_callback: Option<ComparisonCallback>,
+}
+
+impl ComparisonTranscriber {
+    /// Starts every vendor with the same audio. Events of the first vendor are
+    /// forwarded to `callback` so the session keeps working as usual; all
+    /// vendors, including the first, are reported through `comparison_callback`.
+    pub fn start(
+        vendors: Vec<TranscriptVendors>,
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        comparison_callback: Option<ComparisonCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        if vendors.len() < 2 {
+            return Err("Comparison mode needs at least two ASR vendors".into());
+        }
+
+        let tracker = Arc::new(Mutex::new(ComparisonTracker::new(
+            vendors.iter().map(|vendor| vendor.as_str().to_string()),
+        )));
+        let exhausted = Arc::new(AtomicBool::new(false));
+        let mut started = Vec::with_capacity(vendors.len());
+
+        for (index, vendor) in vendors.iter().copied().enumerate() {
+            let vendor_callback = {
+                let tracker = tracker.clone();
+                let primary = callback.clone();
+                let comparison_callback = comparison_callback.clone();
+                Arc::new(move |event: TranscriptEvent| {
+                    let report =
+                        tracker
+                            .lock()
+                            .unwrap()
+                            .record_event(index, &event, Instant::now());
+                    if let Some(callback) = comparison_callback.as_ref() {
+                        callback(ComparisonUpdate {
+                            vendor: vendor.as_str().to_string(),
+                            event: Some(event.clone()),
+                            report,
+                        });
+                    }
+                    if index == 0 {
+                        primary(event);
+                    }
+                }) as PcmCallback
+            };
+            let vendor_status_callback = {
+                let tracker = tracker.clone();
+                let exhausted = exhausted.clone();
+                let status_callback = status_callback.clone();
+                Arc::new(move |message: String| {
+                    eprintln!("Comparison vendor {} failed: {message}", vendor.as_str());
+                    let all_failed = tracker.lock().unwrap().record_failure(index, message);
+                    if all_failed && !exhausted.swap(true, Ordering::SeqCst) {
+                        if let Some(callback) = status_callback.as_ref() {
+                            callback("All ASR vendors in comparison mode failed".to_string());
+                        }
+                    }
+                }) as StatusCallback
+            };
+
+            match start_transcriber(
+                vendor,
+                sample_rate,
+                vendor_callback,
+                Some(vendor_status_callback),
+                transcript_config.clone(),
+            ) {
+                Ok(transcriber) => started.push((vendor, transcriber)),
+                Err(err) => {
+                    for (_, transcriber) in started {
+                        transcriber.shutdown();
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(Self {
+            vendors: started,
+            tracker,
+            exhausted,
+            comparison_callback,
+        })
+    }
+
+    /// Full report including every vendor's committed text.
+    pub fn report(&self) -> ComparisonReport {
+        self.tracker.lock().unwrap().report(true)
+    }
+}
+
+impl StreamingTranscriber for ComparisonTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        if self.exhausted.load(Ordering::SeqCst) {
+            return Err("All ASR vendors in comparison mode failed".into());
+        }
+
+        let has_speech = chunk
+            .iter()
+            .any(|sample| sample.unsigned_abs() >= SPEECH_PEAK_THRESHOLD);
+        let failed = {
+            let mut tracker = self.tracker.lock().unwrap();
+            if has_speech {
+                tracker.note_speech(Instant::now());
+            }
+            tracker.failed_vendors()
+        };
+
+        for (index, (vendor, transcriber)) in self.vendors.iter().enumerate() {
+            if failed[index] {
+                continue;
+            }
+            if let Err(err) = transcriber.queue_chunk(chunk.clone()) {
+                eprintln!("Comparison vendor {} dropped audio: {err}", vendor.as_str());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The primary vendor, whose events drive the session, so vendor-specific
+    /// handling in the capture loop keeps working.
+    fn get_vendor_name(&self) -> String {
+        self.vendors
+            .first()
+            .map(|(_, transcriber)| transcriber.get_vendor_name())
+            .unwrap_or_else(|| "Comparison".to_string())
+    }
+
+    fn force_endpoint(&self) -> Result<(), String> {
+        for (vendor, transcriber) in &self.vendors {
+            if let Err(err) = transcriber.force_endpoint() {
+                eprintln!(
+                    "Comparison vendor {} force endpoint failed: {err}",
+                    vendor.as_str()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn shutdown(&self) {
+        for (_, transcriber) in &self.vendors {
+            transcriber.shutdown();
+        }
+        if let Some(callback) = self.comparison_callback.as_ref() {
+            callback(ComparisonUpdate {
+                vendor: String::new(),
+                event: None,
+                report: Some(self.report()),
+            });
+        }
+    }
+}
+
+#[derive(Default)]
+struct VendorTrack {
+    vendor: String,
+    utterance_started_at: Option<Instant>,
+    draft_seen: bool,
+    utterances: u32,
+    first_draft_ms: Vec<u64>,
+    final_ms: Vec<u64>,
+    /// Only the comparison window is kept.
+    committed_tokens: Vec<String>,
+    committed_text: String,
+    last_commit: Option<String>,
+    error: Option<String>,
+}
+
+struct ComparisonTracker {
+    tracks: Vec<VendorTrack>,
+    disagreements: Vec<VendorDisagreement>,
+}
+
+impl ComparisonTracker {
+    fn new(vendors: impl Iterator<Item = String>) -> Self {
+        Self {
+            tracks: vendors
+                .map(|vendor| VendorTrack {
+                    vendor,
+                    ..VendorTrack::default()
+                })
+                .collect(),
+            disagreements: Vec::new(),
+        }
+    }
+
+    /// Speech after a commit starts a new utterance for every vendor that is idle.
+    fn note_speech(&mut self, now: Instant) {
+        for track in &mut self.tracks {
+            if track.utterance_started_at.is_none() {
+                track.utterance_started_at = Some(now);
+                track.draft_seen = false;
+            }
+        }
+    }
+
+    fn record_event(
+        &mut self,
+        index: usize,
+        event: &TranscriptEvent,
+        now: Instant,
+    ) -> Option<ComparisonReport> {
+        if event.kind == TranscriptEventKind::Translation {
+            return None;
+        }
+        let track = self.tracks.get_mut(index)?;
+        let elapsed_ms = track
+            .utterance_started_at
+            .map(|started| now.saturating_duration_since(started).as_millis() as u64);
+
+        if !track.draft_seen {
+            track.draft_seen = true;
+            if let Some(elapsed_ms) = elapsed_ms {
+                track.first_draft_ms.push(elapsed_ms);
+            }
+        }
+
+        if event.kind != TranscriptEventKind::Commit {
+            return None;
+        }
+
+        if let Some(elapsed_ms) = elapsed_ms {
+            track.final_ms.push(elapsed_ms);
+        }
+        track.utterances += 1;
+        track.utterance_started_at = None;
+        track.draft_seen = false;
+        if !track.committed_text.is_empty() {
+            track.committed_text.push(' ');
+        }
+        track.committed_text.push_str(&event.text);
+        track.last_commit = Some(event.text.clone());
+        track
+            .committed_tokens
+            .extend(tokenize_transcript(&event.text));
+        let excess = track
+            .committed_tokens
+            .len()
+            .saturating_sub(DISAGREEMENT_WINDOW_TOKENS);
+        track.committed_tokens.drain(..excess);
+        self.refresh_disagreements();
+
+        Some(self.report(false))
+    }
+
+    /// Returns true once every vendor has failed.
+    fn record_failure(&mut self, index: usize, message: String) -> bool {
+        if let Some(track) = self.tracks.get_mut(index) {
+            track.error.get_or_insert(message);
+        }
+        self.tracks.iter().all(|track| track.error.is_some())
+    }
+
+    fn failed_vendors(&self) -> Vec<bool> {
+        self.tracks
+            .iter()
+            .map(|track| track.error.is_some())
+            .collect()
+    }
+
+    fn refresh_disagreements(&mut self) {
+        let mut disagreements = Vec::new();
+        for (left_index, left) in self.tracks.iter().enumerate() {
+            for right in self.tracks.iter().skip(left_index + 1) {
+                disagreements.push(VendorDisagreement {
+                    left: left.vendor.clone(),
+                    right: right.vendor.clone(),
+                    rate: disagreement_rate(&left.committed_tokens, &right.committed_tokens),
+                });
+            }
+        }
+        self.disagreements = disagreements;
+    }
+
+    /// Per-commit reports leave out `committed_text`, which grows for the
+    /// whole session.
+    fn report(&self, with_text: bool) -> ComparisonReport {
+        ComparisonReport {
+            vendors: self
+                .tracks
+                .iter()
+                .map(|track| VendorComparisonMetrics {
+                    vendor: track.vendor.clone(),
+                    utterances: track.utterances,
+                    last_first_draft_ms: track.first_draft_ms.last().copied(),
+                    avg_first_draft_ms: average(&track.first_draft_ms),
+                    last_final_ms: track.final_ms.last().copied(),
+                    avg_final_ms: average(&track.final_ms),
+                    last_commit: track.last_commit.clone(),
+                    committed_text: with_text.then(|| track.committed_text.clone()),
+                    error: track.error.clone(),
+                })
+                .collect(),
+            disagreements: self.disagreements.clone(),
+        }
+    }
+}
+
+fn average(values: &[u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<u64>() / values.len() as u64)
+}
+
+/// Splits text into comparable tokens: one per CJK character, one per word
+/// otherwise. Case and punctuation are ignored.
+pub(crate) fn tokenize_transcript(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for ch in text.chars() {
+        if is_cjk(ch) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            tokens.push(ch.to_string());
+        } else if ch.is_alphanumeric() || ch == '\'' {
+            word.extend(ch.to_lowercase());
+        } else if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+pub(crate) fn edit_distance<T: PartialEq>(left: &[T], right: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (i, left_item) in left.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, right_item) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_item != right_item);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[right.len()]
+}
+
+fn disagreement_rate(left: &[String], right: &[String]) -> f64 {
+    let longest = left.len().max(right.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    edit_distance(left, right) as f64 / longest as f64
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xF900..=0xFAFF
+            | 0x3040..=0x30FF
+            | 0xAC00..=0xD7AF
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComparisonTracker, disagreement_rate, edit_distance, tokenize_transcript};
+    use crate::transcript_vendors::{TranscriptEvent, TranscriptEventKind};
+    use std::time::{Duration, Instant};
+
+    fn event(kind: TranscriptEventKind, text: &str) -> TranscriptEvent {
+        TranscriptEvent {
+            vendor: "test".to_string(),
+            kind,
+            text: text.to_string(),
+            language: None,
+            utterance_id: None,
+        }
+    }
+
+    #[test]
+    fn tokenizes_cjk_by_character_and_latin_by_word() {
+        assert_eq!(
+            tokenize_transcript("你好, Hello World!"),
+            vec!["你", "好", "hello", "world"]
+        );
+    }
+
+    #[test]
+    fn edit_distance_counts_insertions_and_substitutions() {
+        assert_eq!(edit_distance(&["a", "b", "c"], &["a", "x", "c", "d"]), 2);
+        assert_eq!(edit_distance::<&str>(&[], &["a"]), 1);
+        assert_eq!(
+            disagreement_rate(
+                &tokenize_transcript("今天天气"),
+                &tokenize_transcript("今天天气")
+            ),
+            0.0
+        );
+    }
+
+    #[test]
+    fn tracker_records_latency_per_vendor_and_disagreement() {
+        let start = Instant::now();
+        let mut tracker =
+            ComparisonTracker::new(["deepgram".to_string(), "gladia".to_string()].into_iter());
+        tracker.note_speech(start);
+
+        assert!(
+            tracker
+                .record_event(
+                    0,
+                    &event(TranscriptEventKind::Draft, "hello"),
+                    start + Duration::from_millis(300)
+                )
+                .is_none()
+        );
+        tracker.record_event(
+            0,
+            &event(TranscriptEventKind::Commit, "hello world"),
+            start + Duration::from_millis(900),
+        );
+        let report = tracker
+            .record_event(
+                1,
+                &event(TranscriptEventKind::Commit, "hello word"),
+                start + Duration::from_millis(1_200),
+            )
+            .unwrap();
+
+        assert_eq!(report.vendors[0].avg_first_draft_ms, Some(300));
+        assert_eq!(report.vendors[0].avg_final_ms, Some(900));
+        assert_eq!(report.vendors[1].last_first_draft_ms, Some(1_200));
+        assert_eq!(report.vendors[1].last_final_ms, Some(1_200));
+        assert_eq!(report.disagreements.len(), 1);
+        assert_eq!(report.disagreements[0].rate, 0.5);
+        assert_eq!(report.vendors[1].last_commit.as_deref(), Some("hello word"));
+        assert!(report.vendors[0].committed_text.is_none());
+        assert_eq!(
+            tracker.report(true).vendors[0].committed_text.as_deref(),
+            Some("hello world")
+        );
+    }
+
+    #[test]
+    fn all_vendors_failing_is_reported_once_every_vendor_failed() {
+        let mut tracker =
+            ComparisonTracker::new(["deepgram".to_string(), "gladia".to_string()].into_iter());
+        assert!(!tracker.record_failure(0, "closed".to_string()));
+        assert!(tracker.record_failure(1, "closed".to_string()));
+        assert_eq!(tracker.failed_vendors(), vec![true, true]);
+    }
+}
@@ -0,0 +1,642 @@
+#![allow(clippy::collapsible_if)]
+
+///https://help.aliyun.com/zh/model-studio/websocket-for-paraformer-real-time-service
+use crate::provider_config::{
+    ALI_QWEN_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string, resolve_required_string,
+    resolve_string_or_default,
+};
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_vendors::language::LanguageSetting;
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    report_audio_sent, report_keepalive_sent,
+};
+use futures_util::{SinkExt, StreamExt, future::try_join};
+use rand::{RngExt, rng as thread_rng};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use tauri::http::Uri;
+use tokio::runtime::Runtime;
+use tokio::sync::{Mutex as AsyncMutex, mpsc, mpsc::error::TrySendError, oneshot, watch};
+use tokio::time::{self, Duration, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
+};
+
+const VENDOR_NAME: &str = "DashScope";
+const DEFAULT_WS_URL: &str = "wss://dashscope.aliyuncs.com/api-ws/v1/inference";
+const DEFAULT_MODEL: &str = "paraformer-realtime-v2";
+const HEARTBEAT_INTERVAL_SECS: u64 = 20;
+const IDLE_SILENCE_INTERVAL_SECS: u64 = 15;
+const IDLE_SILENCE_CHUNK_MS: u32 = 100;
+const FINISH_TASK_TIMEOUT_SECS: u64 = 3;
+
+struct SessionSettings {
+    url: String,
+    api_key: String,
+    model: String,
+    language_hints: Vec<String>,
+    proxy: Option<String>,
+}
+
+pub struct DashScopeTranscriber {
+    sender: Mutex<Option<mpsc::Sender<Vec<u8>>>>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl DashScopeTranscriber {
+    pub fn start(
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        let api_key = resolve_required_string(
+            transcript_config.dashscope_api_key.as_deref(),
+            ALI_QWEN_ENV_KEYS,
+            "ALI_QWEN_QWQ_API_KEY",
+        )?;
+        let url = resolve_string_or_default(
+            transcript_config.dashscope_ws_url.as_deref(),
+            &["DASHSCOPE_WS_URL"],
+            DEFAULT_WS_URL,
+        );
+        let model = resolve_string_or_default(
+            transcript_config.dashscope_model.as_deref(),
+            &["DASHSCOPE_ASR_MODEL"],
+            DEFAULT_MODEL,
+        );
+        let language_hints = dashscope_language_hints(&LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.dashscope_language.as_deref(),
+                &["DASHSCOPE_ASR_LANGUAGE"],
+            ),
+        ));
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::DashScope.as_str(), &url);
+        let settings = SessionSettings {
+            url,
+            api_key,
+            model,
+            language_hints,
+            proxy,
+        };
+
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>(64);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let stop_requested_for_thread = stop_requested.clone();
+
+        let handle = thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to build Tokio runtime");
+            if let Err(err) = runtime.block_on(run_session(
+                settings,
+                sample_rate,
+                callback,
+                receiver,
+                shutdown_rx,
+                stop_requested_for_thread,
+            )) {
+                if let Some(cb) = status_callback.as_ref() {
+                    cb(format!("dashscope: {err}"));
+                }
+                eprintln!("DashScope streaming error: {err}");
+            }
+        });
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            shutdown: Mutex::new(Some(shutdown_tx)),
+            handle: Mutex::new(Some(handle)),
+            stop_requested,
+        })
+    }
+
+    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        let sender = self
+            .sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| "DashScope transcriber is not running".to_string())?;
+
+        let mut bytes = Vec::with_capacity(chunk.len() * 2);
+        for sample in chunk {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        match sender.try_send(bytes) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_chunk)) => Err(
+                "Failed to queue PCM chunk for DashScope: channel is full (consumer stalled)"
+                    .into(),
+            ),
+            Err(TrySendError::Closed(_chunk)) => {
+                Err("Failed to queue PCM chunk for DashScope: channel closed".into())
+            }
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        self.sender.lock().unwrap().take();
+        if let Some(shutdown) = self.shutdown.lock().unwrap().take() {
+            let _ = shutdown.send(());
+        }
+
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for DashScopeTranscriber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl StreamingTranscriber for DashScopeTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        self.enqueue_chunk(chunk)
+    }
+
+    fn get_vendor_name(&self) -> String {
+        VENDOR_NAME.to_string()
+    }
+
+    fn shutdown(&self) {
+        self.stop();
+        println!("DashScope websocket shutdown invoked");
+    }
+}
+
+async fn run_session(
+    settings: SessionSettings,
+    sample_rate: u32,
+    callback: PcmCallback,
+    mut audio_rx: mpsc::Receiver<Vec<u8>>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    stop_requested: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let uri: Uri = settings
+        .url
+        .parse()
+        .map_err(|e| format!("Failed to parse DashScope streaming URI: {e}"))?;
+    let builder = ClientRequestBuilder::new(uri)
+        .with_header("Authorization", format!("bearer {}", settings.api_key))
+        .with_header("X-DashScope-DataInspection", "enable");
+    let client_request = builder
+        .into_client_request()
+        .map_err(|e| format!("Failed to build DashScope websocket request: {e}"))?;
+
+    let (ws_stream, _) = connect_websocket(client_request, settings.proxy.as_deref())
+        .await
+        .map_err(|e| format!("Failed to connect to DashScope: {e}"))?;
+
+    let (mut sink, mut stream) = ws_stream.split();
+    let task_id = generate_task_id();
+    let run_task_payload = build_run_task_payload(
+        &task_id,
+        &settings.model,
+        sample_rate,
+        &settings.language_hints,
+    );
+
+    sink.send(Message::Text(run_task_payload.to_string().into()))
+        .await
+        .map_err(|e| format!("Failed to send DashScope run-task: {e}"))?;
+
+    let (termination_tx, mut termination_rx) = watch::channel(false);
+    let (started_tx, mut started_rx) = watch::channel(false);
+    let last_partial = Arc::new(AsyncMutex::new(None::<String>));
+    let finish_sent = Arc::new(AtomicBool::new(false));
+
+    let send_audio = {
+        let finish_sent = finish_sent.clone();
+        async move {
+            let mut heartbeat = time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+            heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            heartbeat.tick().await;
+
+            let idle_keepalive_samples =
+                ((sample_rate as u64 * IDLE_SILENCE_CHUNK_MS as u64) / 1000).max(1) as usize;
+            let idle_keepalive_chunk = vec![0_u8; idle_keepalive_samples * 2];
+            let idle_keepalive = time::sleep(Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+            tokio::pin!(idle_keepalive);
+
+            loop {
+                if *started_rx.borrow() {
+                    break;
+                }
+
+                tokio::select! {
+                    _ = &mut shutdown_rx => return Ok::<(), String>(()),
+                    result = termination_rx.changed() => {
+                        if result.is_err() || *termination_rx.borrow() {
+                            return Ok::<(), String>(());
+                        }
+                    }
+                    result = started_rx.changed() => {
+                        if result.is_err() {
+                            return Err("DashScope readiness watcher closed unexpectedly".into());
+                        }
+                    }
+                }
+            }
+
+            let mut should_finish_task = true;
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    result = termination_rx.changed() => {
+                        if result.is_err() || *termination_rx.borrow() {
+                            should_finish_task = false;
+                            break;
+                        }
+                    }
+                    chunk = audio_rx.recv() => match chunk {
+                        Some(bytes) => {
+                            let samples = bytes.len() / 2;
+                            sink.send(Message::Binary(bytes.into()))
+                                .await
+                                .map_err(|e| format!("Failed to send audio chunk to DashScope: {e}"))?;
+                            report_audio_sent(TranscriptVendors::DashScope, samples, sample_rate);
+                            idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                        }
+                        None => break,
+                    },
+                    _ = heartbeat.tick() => {
+                        sink.send(Message::Ping(Vec::new().into()))
+                            .await
+                            .map_err(|e| format!("Failed to send DashScope heartbeat ping: {e}"))?;
+                    }
+                    _ = &mut idle_keepalive => {
+                        sink.send(Message::Binary(idle_keepalive_chunk.clone().into()))
+                            .await
+                            .map_err(|e| format!("Failed to send DashScope idle silence chunk: {e}"))?;
+                        report_keepalive_sent(TranscriptVendors::DashScope, idle_keepalive_samples, sample_rate);
+                        idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                    }
+                }
+            }
+
+            if should_finish_task {
+                sink.send(Message::Text(
+                    build_finish_task_payload(&task_id).to_string().into(),
+                ))
+                .await
+                .map_err(|e| format!("Failed to send DashScope finish-task: {e}"))?;
+                finish_sent.store(true, Ordering::SeqCst);
+
+                // Give the server a moment to flush the trailing sentence.
+                let _ = time::timeout(Duration::from_secs(FINISH_TASK_TIMEOUT_SECS), async {
+                    while !*termination_rx.borrow() {
+                        if termination_rx.changed().await.is_err() {
+                            break;
+                        }
+                    }
+                })
+                .await;
+            }
+
+            sink.close()
+                .await
+                .map_err(|e| format!("Failed to close DashScope socket: {e}"))?;
+            Ok::<(), String>(())
+        }
+    };
+
+    let receive_events = {
+        let callback = callback.clone();
+        let termination_tx = termination_tx.clone();
+        let started_tx = started_tx.clone();
+        let last_partial = last_partial.clone();
+        let finish_sent = finish_sent.clone();
+        let stop_requested = stop_requested.clone();
+
+        async move {
+            while let Some(message) = stream.next().await {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(err) => {
+                        let _ = termination_tx.send(true);
+                        return Err(format!("DashScope receive error: {err}"));
+                    }
+                };
+
+                match message {
+                    Message::Text(payload) => {
+                        let value: Value = serde_json::from_str(&payload)
+                            .map_err(|e| format!("Failed to parse DashScope payload: {e}"))?;
+                        let event = value
+                            .get("header")
+                            .and_then(|header| header.get("event"))
+                            .and_then(|entry| entry.as_str())
+                            .unwrap_or_default();
+
+                        match event {
+                            "task-started" => {
+                                let _ = started_tx.send(true);
+                            }
+                            "result-generated" => match parse_sentence(&value) {
+                                Some(SentenceUpdate::Partial(text)) => {
+                                    emit_draft(&callback, VENDOR_NAME, text.as_str());
+                                    *last_partial.lock().await = Some(text);
+                                }
+                                Some(SentenceUpdate::Final(text)) => {
+                                    *last_partial.lock().await = None;
+                                    emit_commit(&callback, VENDOR_NAME, text);
+                                }
+                                None => {}
+                            },
+                            "task-finished" => {
+                                flush_last_partial(&callback, &last_partial).await;
+                                let _ = termination_tx.send(true);
+                                if finish_sent.load(Ordering::SeqCst)
+                                    || stop_requested.load(Ordering::SeqCst)
+                                {
+                                    return Ok::<(), String>(());
+                                }
+                                return Err("DashScope task finished unexpectedly".into());
+                            }
+                            "task-failed" => {
+                                let _ = termination_tx.send(true);
+                                return Err(format!(
+                                    "DashScope returned error: {}",
+                                    describe_task_failure(&value)
+                                ));
+                            }
+                            _ => {}
+                        }
+                    }
+                    Message::Close(frame) => {
+                        let _ = termination_tx.send(true);
+                        flush_last_partial(&callback, &last_partial).await;
+                        if finish_sent.load(Ordering::SeqCst)
+                            || stop_requested.load(Ordering::SeqCst)
+                        {
+                            return Ok::<(), String>(());
+                        }
+                        return Err(describe_close_frame("DashScope", frame.as_ref()));
+                    }
+                    _ => {}
+                }
+            }
+
+            let _ = termination_tx.send(true);
+            flush_last_partial(&callback, &last_partial).await;
+            if finish_sent.load(Ordering::SeqCst) || stop_requested.load(Ordering::SeqCst) {
+                Ok::<(), String>(())
+            } else {
+                Err("DashScope websocket closed unexpectedly without a close frame".into())
+            }
+        }
+    };
+
+    try_join(send_audio, receive_events).await?;
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SentenceUpdate {
+    Partial(String),
+    Final(String),
+}
+
+fn parse_sentence(value: &Value) -> Option<SentenceUpdate> {
+    let sentence = value.get("payload")?.get("output")?.get("sentence")?;
+    // fun-asr emits empty heartbeat sentences while the speaker is silent.
+    if sentence
+        .get("heartbeat")
+        .and_then(|entry| entry.as_bool())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+
+    let text = sentence
+        .get("text")
+        .and_then(|entry| entry.as_str())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())?
+        .to_string();
+    let sentence_end = sentence
+        .get("sentence_end")
+        .and_then(|entry| entry.as_bool())
+        .unwrap_or(false);
+
+    if sentence_end {
+        Some(SentenceUpdate::Final(text))
+    } else {
+        Some(SentenceUpdate::Partial(text))
+    }
+}
+
+async fn flush_last_partial(
+    callback: &PcmCallback,
+    last_partial: &Arc<AsyncMutex<Option<String>>>,
+) {
+    if let Some(text) = last_partial.lock().await.take() {
+        emit_commit(callback, VENDOR_NAME, text);
+    }
+}
+
+fn describe_task_failure(value: &Value) -> String {
+    let header = value.get("header");
+    let code = header
+        .and_then(|entry| entry.get("error_code"))
+        .and_then(|entry| entry.as_str())
+        .unwrap_or("unknown");
+    let message = header
+        .and_then(|entry| entry.get("error_message"))
+        .and_then(|entry| entry.as_str())
+        .unwrap_or("unknown DashScope error");
+    format!("{message} (code={code})")
+}
+
+fn describe_close_frame(vendor: &str, frame: Option<&tungstenite::protocol::CloseFrame>) -> String {
+    match frame {
+        Some(frame) => format!(
+            "{vendor} websocket closed unexpectedly (code={:?}, reason={})",
+            frame.code, frame.reason
+        ),
+        None => format!("{vendor} websocket closed unexpectedly without a close frame"),
+    }
+}
+
+fn generate_task_id() -> String {
+    let mut rng = thread_rng();
+    format!("{:032x}", rng.random::<u128>())
+}
+
+/// `language_hints` narrows detection to the listed languages and lets the
+/// model switch between them, so every mode maps onto it directly.
+fn dashscope_language_hints(language: &LanguageSetting) -> Vec<String> {
+    match language {
+        // The vendor field has always accepted a list of hints.
+        LanguageSetting::Fixed(value) => parse_language_hints(value),
+        other => other.candidate_languages().to_vec(),
+    }
+}
+
+fn parse_language_hints(value: &str) -> Vec<String> {
+    value
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn build_run_task_payload(
+    task_id: &str,
+    model: &str,
+    sample_rate: u32,
+    language_hints: &[String],
+) -> Value {
+    let mut parameters = json!({
+        "format": "pcm",
+        "sample_rate": sample_rate
+    });
+    if !language_hints.is_empty() {
+        parameters["language_hints"] = json!(language_hints);
+    }
+
+    json!({
+        "header": {
+            "action": "run-task",
+            "task_id": task_id,
+            "streaming": "duplex"
+        },
+        "payload": {
+            "task_group": "audio",
+            "task": "asr",
+            "function": "recognition",
+            "model": model,
+            "parameters": parameters,
+            "input": {}
+        }
+    })
+}
+
+fn build_finish_task_payload(task_id: &str) -> Value {
+    json!({
+        "header": {
+            "action": "finish-task",
+            "task_id": task_id,
+            "streaming": "duplex"
+        },
+        "payload": {
+            "input": {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SentenceUpdate, build_finish_task_payload, build_run_task_payload,
+        dashscope_language_hints, generate_task_id, parse_language_hints, parse_sentence,
+    };
+    use crate::transcript_vendors::language::LanguageSetting;
+    use serde_json::json;
+
+    #[test]
+    fn run_task_payload_matches_duplex_protocol() {
+        let payload = build_run_task_payload(
+            "abc",
+            "paraformer-realtime-v2",
+            16_000,
+            &["zh".to_string(), "en".to_string()],
+        );
+
+        assert_eq!(payload["header"]["action"], "run-task");
+        assert_eq!(payload["header"]["streaming"], "duplex");
+        assert_eq!(payload["payload"]["task_group"], "audio");
+        assert_eq!(payload["payload"]["model"], "paraformer-realtime-v2");
+        assert_eq!(payload["payload"]["parameters"]["format"], "pcm");
+        assert_eq!(payload["payload"]["parameters"]["sample_rate"], 16_000);
+        assert_eq!(
+            payload["payload"]["parameters"]["language_hints"],
+            json!(["zh", "en"])
+        );
+
+        let payload = build_run_task_payload("abc", "fun-asr-realtime", 16_000, &[]);
+        assert!(
+            payload["payload"]["parameters"]
+                .get("language_hints")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn finish_task_payload_reuses_task_id() {
+        let payload = build_finish_task_payload("abc");
+        assert_eq!(payload["header"]["action"], "finish-task");
+        assert_eq!(payload["header"]["task_id"], "abc");
+    }
+
+    #[test]
+    fn sentence_end_flag_selects_draft_or_commit() {
+        let partial = json!({
+            "header": { "event": "result-generated" },
+            "payload": { "output": { "sentence": { "text": "你好", "sentence_end": false } } }
+        });
+        let final_sentence = json!({
+            "header": { "event": "result-generated" },
+            "payload": { "output": { "sentence": { "text": "你好世界。", "sentence_end": true } } }
+        });
+
+        assert_eq!(
+            parse_sentence(&partial),
+            Some(SentenceUpdate::Partial("你好".to_string()))
+        );
+        assert_eq!(
+            parse_sentence(&final_sentence),
+            Some(SentenceUpdate::Final("你好世界。".to_string()))
+        );
+    }
+
+    #[test]
+    fn heartbeat_sentences_are_ignored() {
+        let heartbeat = json!({
+            "payload": { "output": { "sentence": { "text": "", "heartbeat": true, "sentence_end": false } } }
+        });
+        assert_eq!(parse_sentence(&heartbeat), None);
+    }
+
+    #[test]
+    fn language_hints_accept_comma_or_space_separated_values() {
+        assert_eq!(parse_language_hints("zh, en"), vec!["zh", "en"]);
+        assert_eq!(parse_language_hints("ja"), vec!["ja"]);
+        assert_eq!(
+            dashscope_language_hints(&LanguageSetting::Fixed("zh en".to_string())),
+            vec!["zh", "en"]
+        );
+        assert_eq!(
+            dashscope_language_hints(&LanguageSetting::CodeSwitching(vec![
+                "zh".to_string(),
+                "ja".to_string()
+            ])),
+            vec!["zh", "ja"]
+        );
+        assert!(dashscope_language_hints(&LanguageSetting::AutoDetect(Vec::new())).is_empty());
+    }
+
+    #[test]
+    fn task_id_is_32_hex_chars() {
+        let task_id = generate_task_id();
+        assert_eq!(task_id.len(), 32);
+        assert!(task_id.chars().all(|ch| ch.is_ascii_hexdigit()));
+    }
+}
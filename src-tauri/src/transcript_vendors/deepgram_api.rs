@@ -3,9 +3,16 @@
 /// https://developers.deepgram.com/reference/speech-to-text-api/listen-streaming
 use crate::provider_config::{
     TranscriptRuntimeConfig, resolve_deepgram_api_key, resolve_optional_string,
+    resolve_string_or_default,
 };
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_latency::{note_result_offset, note_stream_started};
+use crate::transcript_vendors::language::{DetectedLanguage, LanguageSetting, primary_subtag};
+use crate::transcript_vendors::token_broker::{CredentialSource, VendorCredential};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    encode_query_component, report_audio_sent,
 };
 use futures_util::{SinkExt, StreamExt, future::try_join};
 use serde_json::{Value, json};
@@ -17,19 +24,19 @@ use tauri::http::Uri;
 use tokio::runtime::Runtime;
 use tokio::sync::{Mutex as AsyncMutex, mpsc, mpsc::error::TrySendError, oneshot, watch};
 use tokio::time::{self, Duration};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{
-        client::{ClientRequestBuilder, IntoClientRequest},
-        protocol::Message,
-    },
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
 };
 
 const BASE_URL: &str = "wss://api.deepgram.com/v1/listen";
 const KEEPALIVE_INTERVAL_SECONDS: u64 = 3;
-const DEFAULT_ENDPOINTING_MS: u32 = 300;
-const DEFAULT_UTTERANCE_END_MS: u32 = 1_000;
+pub(super) const DEFAULT_ENDPOINTING_MS: u32 = 300;
+pub(super) const DEFAULT_UTTERANCE_END_MS: u32 = 1_000;
 const VENDOR_NAME: &str = "Deepgram";
+pub(super) const MULTILINGUAL_LANGUAGE: &str = "multi";
+const MULTILINGUAL_SUPPORTED_LANGUAGES: [&str; 10] =
+    ["en", "es", "fr", "de", "hi", "ru", "pt", "ja", "it", "nl"];
 
 enum StreamCommand {
     Audio(Vec<u8>),
@@ -50,14 +57,27 @@ impl DeepgramApiTranscriber {
         status_callback: Option<StatusCallback>,
         transcript_config: TranscriptRuntimeConfig,
     ) -> Result<Self, String> {
-        let api_key = resolve_deepgram_api_key(transcript_config.deepgram_api_key.as_deref())
-            .ok_or_else(|| {
-                "缺少 DEEPGRAM_API_KEY 配置，请在前端填写或在环境变量中提供".to_string()
-            })?;
-        let language = resolve_optional_string(
-            transcript_config.deepgram_language.as_deref(),
-            &["DEEPGRAM_LANGUAGE"],
+        let credentials = CredentialSource::resolve(
+            TranscriptVendors::DeepGram,
+            &transcript_config,
+            resolve_deepgram_api_key(transcript_config.deepgram_api_key.as_deref()),
+            "DEEPGRAM_API_KEY",
+        )?;
+        let language = LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.deepgram_language.as_deref(),
+                &["DEEPGRAM_LANGUAGE"],
+            ),
         );
+        let ws_url = resolve_string_or_default(
+            transcript_config.deepgram_ws_url.as_deref(),
+            &["DEEPGRAM_WS_URL"],
+            BASE_URL,
+        );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::DeepGram.as_str(), &ws_url);
+        let vocabulary = resolve_vocabulary(&transcript_config);
 
         let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
         let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
@@ -67,8 +87,11 @@ impl DeepgramApiTranscriber {
         let handle = thread::spawn(move || {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_session(
-                api_key,
+                credentials,
+                ws_url,
+                proxy,
                 language,
+                vocabulary,
                 sample_rate,
                 callback,
                 receiver,
@@ -168,27 +191,41 @@ impl StreamingTranscriber for DeepgramApiTranscriber {
 }
 
 async fn run_session(
-    api_key: String,
-    language: Option<String>,
+    credentials: CredentialSource,
+    ws_url: String,
+    proxy: Option<String>,
+    language: LanguageSetting,
+    vocabulary: Vec<String>,
     sample_rate: u32,
     callback: PcmCallback,
     mut audio_rx: mpsc::Receiver<StreamCommand>,
     mut shutdown_rx: oneshot::Receiver<()>,
     stop_requested: Arc<AtomicBool>,
 ) -> Result<(), String> {
-    let url = build_streaming_url(language.as_deref(), sample_rate);
+    let url = build_streaming_url(&ws_url, &language, &vocabulary, sample_rate);
+    let detected_language = DetectedLanguage::default();
+    let callback = detected_language.wrap_callback(callback);
     let uri: Uri = url
         .parse()
         .map_err(|e| format!("Failed to parse Deepgram streaming URI: {e}"))?;
-    let builder =
-        ClientRequestBuilder::new(uri).with_header("Authorization", format!("Token {api_key}"));
+    // Keys use the `Token` scheme; `/v1/auth/grant` tokens are bearer tokens.
+    let authorization = match credentials.fetch(None).await? {
+        VendorCredential::ApiKey(api_key) => format!("Token {api_key}"),
+        VendorCredential::Token(token) => format!("Bearer {token}"),
+        VendorCredential::SessionUrl(_) => {
+            return Err("Token service returned a session URL for Deepgram".to_string());
+        }
+    };
+    let builder = ClientRequestBuilder::new(uri).with_header("Authorization", authorization);
     let client_request = builder
         .into_client_request()
         .map_err(|e| format!("Failed to build Deepgram websocket request: {e}"))?;
 
-    let (ws_stream, _) = connect_async(client_request)
+    let (ws_stream, _) = connect_websocket(client_request, proxy.as_deref())
         .await
         .map_err(|e| format!("Failed to connect to Deepgram API: {e}"))?;
+    // Result offsets restart with every connection.
+    note_stream_started(TranscriptVendors::DeepGram);
 
     let (mut sink, mut stream) = ws_stream.split();
     let (termination_tx, mut termination_rx) = watch::channel(false);
@@ -217,9 +254,11 @@ async fn run_session(
                     }
                     command = audio_rx.recv() => match command {
                         Some(StreamCommand::Audio(bytes)) => {
+                            let samples = bytes.len() / 2;
                             sink.send(Message::Binary(bytes.into()))
                                 .await
                                 .map_err(|e| format!("Failed to send audio chunk to Deepgram: {e}"))?;
+                            report_audio_sent(TranscriptVendors::DeepGram, samples, sample_rate);
                         }
                         Some(StreamCommand::Finalize) => {
                             sink.send(Message::Text(json!({"type": "Finalize"}).to_string().into()))
@@ -249,6 +288,7 @@ async fn run_session(
         let termination_tx = termination_tx.clone();
         let close_sent = close_sent.clone();
         let utterance_buffer = utterance_buffer.clone();
+        let detected_language = detected_language.clone();
 
         async move {
             while let Some(message) = stream.next().await {
@@ -267,6 +307,8 @@ async fn run_session(
 
                         match value.get("type").and_then(|entry| entry.as_str()) {
                             Some("Results") => {
+                                detected_language
+                                    .update(extract_detected_language(&value).as_deref());
                                 if let Some(transcript) = extract_transcript(&value) {
                                     let is_final = value
                                         .get("is_final")
@@ -278,6 +320,9 @@ async fn run_session(
                                         .unwrap_or(false);
 
                                     if is_final {
+                                        if let Some(end) = result_audio_end(&value) {
+                                            note_result_offset(TranscriptVendors::DeepGram, end);
+                                        }
                                         append_utterance_segment(
                                             &utterance_buffer,
                                             transcript.as_str(),
@@ -359,8 +404,21 @@ fn describe_close_frame(vendor: &str, frame: Option<&tungstenite::protocol::Clos
     }
 }
 
-fn build_streaming_url(language: Option<&str>, sample_rate: u32) -> String {
-    let model = select_model(language);
+fn build_streaming_url(
+    base_url: &str,
+    language: &LanguageSetting,
+    vocabulary: &[String],
+    sample_rate: u32,
+) -> String {
+    // Streaming has no language detection; `multi` is nova-3's code-switching
+    // model and also the closest thing to auto-detect.
+    let (model, language) = if language.is_multilingual() {
+        warn_unsupported_multilingual(language);
+        ("nova-3", Some(MULTILINGUAL_LANGUAGE.to_string()))
+    } else {
+        let fixed = language.fixed_language();
+        (select_model(fixed), normalize_language(fixed))
+    };
     let mut query = vec![
         ("model", model.to_string()),
         ("encoding", "linear16".to_string()),
@@ -373,20 +431,32 @@ fn build_streaming_url(language: Option<&str>, sample_rate: u32) -> String {
         ("punctuate", "false".to_string()),
     ];
 
-    if let Some(language) = normalize_language(language) {
+    if let Some(language) = language {
         query.push(("language", language));
     }
 
+    // Nova-3 boosts with `keyterm` prompting; older models only know `keywords`.
+    let vocabulary_key = if model == "nova-3" {
+        "keyterm"
+    } else {
+        "keywords"
+    };
+    query.extend(
+        vocabulary
+            .iter()
+            .map(|term| (vocabulary_key, encode_query_component(term))),
+    );
+
     let query = query
         .into_iter()
         .map(|(key, value)| format!("{key}={value}"))
         .collect::<Vec<_>>()
         .join("&");
 
-    format!("{BASE_URL}?{query}")
+    format!("{base_url}?{query}")
 }
 
-fn normalize_language(value: Option<&str>) -> Option<String> {
+pub(super) fn normalize_language(value: Option<&str>) -> Option<String> {
     let trimmed = value?.trim();
     if trimmed.is_empty() {
         return None;
@@ -402,13 +472,42 @@ fn normalize_language(value: Option<&str>) -> Option<String> {
     Some(normalized)
 }
 
-fn select_model(language: Option<&str>) -> &'static str {
+pub(super) fn select_model(language: Option<&str>) -> &'static str {
     match normalize_language(language).as_deref() {
-        Some("zh") | Some("zh-CN") | None => "nova-3",
+        Some("zh") | Some("zh-CN") | None => "nova-2",
         _ => "nova-3",
     }
 }
 
+pub(super) fn warn_unsupported_multilingual(language: &LanguageSetting) {
+    let unsupported = language
+        .candidate_languages()
+        .iter()
+        .filter(|candidate| {
+            !MULTILINGUAL_SUPPORTED_LANGUAGES.contains(&primary_subtag(candidate).as_str())
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unsupported.is_empty() {
+        eprintln!(
+            "Deepgram multilingual streaming does not cover {}; results may be poor",
+            unsupported.join(", ")
+        );
+    }
+}
+
+/// Deepgram reports the languages spoken in a multilingual result.
+fn extract_detected_language(value: &Value) -> Option<String> {
+    value
+        .get("channel")
+        .and_then(|channel| channel.get("alternatives"))
+        .and_then(|alternatives| alternatives.get(0))
+        .and_then(|entry| entry.get("languages"))
+        .and_then(|languages| languages.get(0))
+        .and_then(|language| language.as_str())
+        .map(ToString::to_string)
+}
+
 fn extract_transcript(value: &Value) -> Option<String> {
     value
         .get("channel")
@@ -422,6 +521,13 @@ fn extract_transcript(value: &Value) -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// End of the audio a result covers, in seconds from the stream start.
+fn result_audio_end(value: &Value) -> Option<f64> {
+    let start = value.get("start").and_then(Value::as_f64)?;
+    let duration = value.get("duration").and_then(Value::as_f64)?;
+    Some(start + duration)
+}
+
 async fn append_utterance_segment(buffer: &AsyncMutex<String>, segment: &str) {
     let mut guard = buffer.lock().await;
     if !guard.is_empty() {
@@ -466,7 +572,7 @@ async fn flush_utterance(buffer: &AsyncMutex<String>, callback: &PcmCallback) {
     guard.clear();
 }
 
-fn merge_segments(prefix: &str, suffix: &str) -> String {
+pub(super) fn merge_segments(prefix: &str, suffix: &str) -> String {
     let prefix = prefix.trim();
     let suffix = suffix.trim();
 
@@ -537,24 +643,80 @@ fn is_spacing_punctuation(ch: char) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{build_streaming_url, extract_transcript, normalize_language, select_model};
+    use super::{
+        BASE_URL, DEFAULT_ENDPOINTING_MS, build_streaming_url, extract_detected_language,
+        extract_transcript, normalize_language, result_audio_end, select_model,
+    };
+    use crate::transcript_vendors::language::LanguageSetting;
     use serde_json::json;
 
+    fn fixed(language: &str) -> LanguageSetting {
+        LanguageSetting::Fixed(language.to_string())
+    }
+
+    #[test]
+    fn result_audio_end_adds_duration_to_start() {
+        let result = json!({ "type": "Results", "start": 3.5, "duration": 1.25 });
+        assert_eq!(result_audio_end(&result), Some(4.75));
+        assert_eq!(result_audio_end(&json!({ "start": 1.0 })), None);
+    }
+
     #[test]
     fn build_streaming_url_uses_expected_v1_endpoint() {
-        let url = build_streaming_url(Some("zh_CN"), 16_000);
+        let url = build_streaming_url(BASE_URL, &fixed("zh_CN"), &[], 16_000);
 
         assert!(url.starts_with("wss://api.deepgram.com/v1/listen?"));
         assert!(url.contains("model=nova-2"));
         assert!(url.contains("encoding=linear16"));
         assert!(url.contains("sample_rate=16000"));
         assert!(url.contains("channels=1"));
-        assert!(url.contains("endpointing=500"));
+        assert!(url.contains(&format!("endpointing={DEFAULT_ENDPOINTING_MS}")));
         assert!(url.contains("interim_results=true"));
         assert!(url.contains("utterance_end_ms=1000"));
         assert!(url.contains("language=zh-CN"));
     }
 
+    #[test]
+    fn build_streaming_url_maps_vocabulary_to_model_boost_parameter() {
+        let vocabulary = vec!["Kubernetes".to_string(), "Ruby on Rails".to_string()];
+
+        let url = build_streaming_url(BASE_URL, &fixed("en"), &vocabulary, 16_000);
+        assert!(url.contains("model=nova-3"));
+        assert!(url.contains("keyterm=Kubernetes&keyterm=Ruby%20on%20Rails"));
+        assert!(!url.contains("keywords="));
+
+        let url = build_streaming_url(BASE_URL, &fixed("zh"), &vocabulary, 16_000);
+        assert!(url.contains("model=nova-2"));
+        assert!(url.contains("keywords=Kubernetes&keywords=Ruby%20on%20Rails"));
+    }
+
+    #[test]
+    fn build_streaming_url_uses_multi_for_code_switching_and_auto_detect() {
+        for language in [
+            LanguageSetting::CodeSwitching(vec!["en".to_string(), "es".to_string()]),
+            LanguageSetting::AutoDetect(Vec::new()),
+        ] {
+            let url = build_streaming_url(BASE_URL, &language, &[], 16_000);
+            assert!(url.contains("model=nova-3"));
+            assert!(url.contains("language=multi"));
+        }
+
+        let url = build_streaming_url(BASE_URL, &LanguageSetting::VendorDefault, &[], 16_000);
+        assert!(!url.contains("language="));
+    }
+
+    #[test]
+    fn extract_detected_language_reads_multilingual_result() {
+        let value = json!({
+            "type": "Results",
+            "channel": {
+                "alternatives": [{ "transcript": "hola", "languages": ["es", "en"] }]
+            }
+        });
+        assert_eq!(extract_detected_language(&value).as_deref(), Some("es"));
+        assert_eq!(extract_detected_language(&json!({"channel": {}})), None);
+    }
+
     #[test]
     fn model_selection_matches_existing_language_behavior() {
         assert_eq!(select_model(None), "nova-2");
@@ -1,10 +1,19 @@
 use crate::provider_config::{
     TranscriptRuntimeConfig, resolve_deepgram_api_key, resolve_optional_string,
 };
+use crate::proxy::ProxySettings;
+use crate::transcript_latency::{note_result_offset, note_stream_started};
+use crate::transcript_vendors::deepgram_api::{
+    DEFAULT_ENDPOINTING_MS, DEFAULT_UTTERANCE_END_MS, MULTILINGUAL_LANGUAGE, merge_segments,
+    normalize_language, select_model, warn_unsupported_multilingual,
+};
+use crate::transcript_vendors::language::LanguageSetting;
+use crate::transcript_vendors::token_broker::{CredentialSource, VendorCredential};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    report_audio_sent,
 };
-use bytes::{BufMut, Bytes, BytesMut};
 use deepgram::{
     Deepgram,
     common::{
@@ -12,18 +21,23 @@ use deepgram::{
         stream_response::StreamResponse,
     },
 };
-use futures::channel::mpsc as futures_mpsc;
-use futures_util::{SinkExt, StreamExt};
-use std::fmt;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::{self, JoinHandle};
 use tokio::runtime::Runtime;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, mpsc::error::TrySendError, oneshot};
+
+const VENDOR_NAME: &str = "Deepgram";
+const SDK_ENDPOINT: &str = "wss://api.deepgram.com/v1/listen";
+
+enum StreamCommand {
+    Audio(Vec<u8>),
+    Finalize,
+}
 
 pub struct DeepgramTranscriber {
-    sender: mpsc::Sender<Vec<i16>>,
+    sender: Mutex<Option<mpsc::Sender<StreamCommand>>>,
     shutdown: Mutex<Option<oneshot::Sender<()>>>,
     handle: Mutex<Option<JoinHandle<()>>>,
     stop_requested: Arc<AtomicBool>,
@@ -36,16 +50,28 @@ impl DeepgramTranscriber {
         status_callback: Option<StatusCallback>,
         transcript_config: TranscriptRuntimeConfig,
     ) -> Result<Self, String> {
-        let api_key = resolve_deepgram_api_key(transcript_config.deepgram_api_key.as_deref())
-            .ok_or_else(|| {
-                "缺少 DEEPGRAM_API_KEY 配置，请在前端填写或在环境变量中提供".to_string()
-            })?;
-        let language = resolve_optional_string(
-            transcript_config.deepgram_language.as_deref(),
-            &["DEEPGRAM_LANGUAGE"],
+        // The SDK opens its own socket, so only the api backend can tunnel.
+        if ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::DeepGram.as_str(), SDK_ENDPOINT)
+            .is_some()
+        {
+            return Err("Deepgram SDK 后端不支持代理，请改用 api 后端".to_string());
+        }
+        let credentials = CredentialSource::resolve(
+            TranscriptVendors::DeepGram,
+            &transcript_config,
+            resolve_deepgram_api_key(transcript_config.deepgram_api_key.as_deref()),
+            "DEEPGRAM_API_KEY",
+        )?;
+        let language = LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.deepgram_language.as_deref(),
+                &["DEEPGRAM_LANGUAGE"],
+            ),
         );
-
-        let (sender, receiver) = mpsc::channel::<Vec<i16>>(64);
+        let vocabulary = resolve_vocabulary(&transcript_config);
+        let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
         let (shutdown, shutdown_rx) = oneshot::channel::<()>();
         let stop_requested = Arc::new(AtomicBool::new(false));
         let stop_requested_for_thread = stop_requested.clone();
@@ -53,8 +79,9 @@ impl DeepgramTranscriber {
         let handle = thread::spawn(move || {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_stream(
-                api_key,
+                credentials,
                 language,
+                vocabulary,
                 sample_rate,
                 callback,
                 receiver,
@@ -62,28 +89,57 @@ impl DeepgramTranscriber {
                 stop_requested_for_thread,
             )) {
                 if let Some(cb) = status_callback.as_ref() {
-                    cb(format!("deepgram: {err}"));
+                    cb(format!("deepgram_sdk: {err}"));
                 }
-                eprintln!("Deepgram streaming error: {err}");
+                eprintln!("Deepgram SDK streaming error: {err}");
             }
         });
 
         Ok(Self {
-            sender,
+            sender: Mutex::new(Some(sender)),
             shutdown: Mutex::new(Some(shutdown)),
             handle: Mutex::new(Some(handle)),
             stop_requested,
         })
     }
 
-    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+    fn sender(&self) -> Result<mpsc::Sender<StreamCommand>, String> {
         self.sender
-            .blocking_send(chunk)
-            .map_err(|e| format!("Failed to queue PCM chunk for Deepgram: {e}"))
+            .lock()
+            .unwrap()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| "Deepgram SDK transcriber is not running".to_string())
+    }
+
+    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        let sender = self.sender()?;
+
+        let mut bytes = Vec::with_capacity(chunk.len() * 2);
+        for sample in chunk {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        match sender.try_send(StreamCommand::Audio(bytes)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_chunk)) => {
+                Err("Failed to queue PCM chunk for Deepgram: channel is full".into())
+            }
+            Err(TrySendError::Closed(_chunk)) => {
+                Err("Failed to queue PCM chunk for Deepgram: channel closed".into())
+            }
+        }
+    }
+
+    pub fn request_finalize(&self) -> Result<(), String> {
+        self.sender()?
+            .blocking_send(StreamCommand::Finalize)
+            .map_err(|e| format!("Failed to queue Deepgram Finalize: {e}"))
     }
 
     pub fn stop(&self) {
         self.stop_requested.store(true, Ordering::SeqCst);
+        self.sender.lock().unwrap().take();
         if let Some(shutdown) = self.shutdown.lock().unwrap().take() {
             let _ = shutdown.send(());
         }
@@ -106,7 +162,11 @@ impl StreamingTranscriber for DeepgramTranscriber {
     }
 
     fn get_vendor_name(&self) -> String {
-        "Deepgram".to_string()
+        VENDOR_NAME.to_string()
+    }
+
+    fn force_endpoint(&self) -> Result<(), String> {
+        self.request_finalize()
     }
 
     fn shutdown(&self) {
@@ -115,75 +175,100 @@ impl StreamingTranscriber for DeepgramTranscriber {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_stream(
-    api_key: String,
-    language: Option<String>,
+    credentials: CredentialSource,
+    language: LanguageSetting,
+    vocabulary: Vec<String>,
     sample_rate: u32,
     callback: PcmCallback,
-    mut audio_rx: mpsc::Receiver<Vec<i16>>,
+    mut audio_rx: mpsc::Receiver<StreamCommand>,
     mut shutdown_rx: oneshot::Receiver<()>,
     stop_requested: Arc<AtomicBool>,
 ) -> Result<(), String> {
-    let deepgram =
-        Deepgram::new(&api_key).map_err(|e| format!("Failed to construct Deepgram client: {e}"))?;
+    let deepgram = match credentials.fetch(None).await? {
+        VendorCredential::ApiKey(api_key) => Deepgram::new(&api_key),
+        VendorCredential::Token(token) => Deepgram::with_temp_token(&token),
+        VendorCredential::SessionUrl(_) => {
+            return Err("Deepgram 不支持会话 URL 凭据".to_string());
+        }
+    }
+    .map_err(|e| format!("Failed to construct Deepgram client: {e}"))?;
 
     let transcription = deepgram.transcription();
-
-    let builder = transcription
-        .stream_request_with_options(build_stream_options(language.as_deref()))
+    let mut handle = transcription
+        .stream_request_with_options(build_stream_options(&language, &vocabulary))
         .keep_alive()
         .encoding(Encoding::Linear16)
         .sample_rate(sample_rate)
-        .endpointing(Endpointing::CustomDurationMs(500))
-        .channels(1);
-
-    let (mut stream_tx, stream_rx) = futures_mpsc::channel::<Result<Bytes, StreamBridgeError>>(32);
-
-    let bridge = tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                _ = &mut shutdown_rx => {
-                    break;
-                }
-                chunk = audio_rx.recv() => {
-                    match chunk {
-                        Some(samples) => {
-                            if stream_tx.send(Ok(pcm_chunk_to_bytes(&samples))).await.is_err() {
-                                break;
-                            }
-                        }
-                        None => break,
-                    }
-                }
-            }
-        }
-    });
-
-    let mut responses = builder
-        .stream(stream_rx)
+        .endpointing(Endpointing::CustomDurationMs(DEFAULT_ENDPOINTING_MS))
+        .interim_results(true)
+        .utterance_end_ms(DEFAULT_UTTERANCE_END_MS as u16)
+        .channels(1)
+        .handle()
         .await
         .map_err(|e| format!("Deepgram websocket failed: {e}"))?;
+    // Result offsets restart with every connection.
+    note_stream_started(TranscriptVendors::DeepGram);
 
     let mut utterance_buffer = String::new();
-
-    while let Some(message) = responses.next().await {
-        match message {
-            Ok(StreamResponse::TranscriptResponse {
-                channel,
-                is_final,
-                speech_final,
-                ..
-            }) => {
-                if let Some(entry) = channel.alternatives.first() {
-                    let transcript = entry.transcript.trim();
+    let mut close_sent = false;
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx, if !close_sent => {
+                close_sent = true;
+                handle
+                    .close_stream()
+                    .await
+                    .map_err(|e| format!("Failed to send Deepgram CloseStream: {e}"))?;
+            }
+            command = audio_rx.recv(), if !close_sent => match command {
+                Some(StreamCommand::Audio(bytes)) => {
+                    let samples = bytes.len() / 2;
+                    handle
+                        .send_data(bytes)
+                        .await
+                        .map_err(|e| format!("Failed to send audio chunk to Deepgram: {e}"))?;
+                    report_audio_sent(TranscriptVendors::DeepGram, samples, sample_rate);
+                }
+                Some(StreamCommand::Finalize) => {
+                    handle
+                        .finalize()
+                        .await
+                        .map_err(|e| format!("Failed to send Deepgram Finalize: {e}"))?;
+                }
+                None => {
+                    close_sent = true;
+                    handle
+                        .close_stream()
+                        .await
+                        .map_err(|e| format!("Failed to send Deepgram CloseStream: {e}"))?;
+                }
+            },
+            response = handle.receive() => match response {
+                Some(Ok(StreamResponse::TranscriptResponse {
+                    start,
+                    duration,
+                    channel,
+                    is_final,
+                    speech_final,
+                    ..
+                })) => {
+                    let transcript = channel
+                        .alternatives
+                        .first()
+                        .map(|entry| entry.transcript.trim())
+                        .unwrap_or_default();
 
                     if is_final && !transcript.is_empty() {
+                        note_result_offset(TranscriptVendors::DeepGram, start + duration);
                         append_utterance_segment(&mut utterance_buffer, transcript);
-                        emit_draft(&callback, "Deepgram", utterance_buffer.trim());
+                        emit_draft(&callback, VENDOR_NAME, utterance_buffer.trim());
                     } else if !transcript.is_empty() {
                         emit_draft(
                             &callback,
-                            "Deepgram",
+                            VENDOR_NAME,
                             merge_segments(&utterance_buffer, transcript),
                         );
                     }
@@ -192,15 +277,21 @@ async fn run_stream(
                         flush_utterance(&mut utterance_buffer, &callback);
                     }
                 }
-            }
-            Ok(_) => {}
-            Err(err) => return Err(format!("Deepgram stream response error: {err}")),
+                Some(Ok(StreamResponse::UtteranceEndResponse { .. })) => {
+                    flush_utterance(&mut utterance_buffer, &callback);
+                }
+                Some(Ok(_)) => {}
+                Some(Err(err)) => {
+                    flush_utterance(&mut utterance_buffer, &callback);
+                    return Err(format!("Deepgram stream response error: {err}"));
+                }
+                None => break,
+            },
         }
     }
 
-    let _ = bridge.await;
     flush_utterance(&mut utterance_buffer, &callback);
-    if stop_requested.load(Ordering::SeqCst) {
+    if close_sent || stop_requested.load(Ordering::SeqCst) {
         println!("Deepgram websocket closed");
         Ok(())
     } else {
@@ -208,54 +299,35 @@ async fn run_stream(
     }
 }
 
-fn pcm_chunk_to_bytes(chunk: &[i16]) -> Bytes {
-    let mut buffer = BytesMut::with_capacity(chunk.len() * 2);
-    for sample in chunk {
-        buffer.put_i16_le(*sample);
-    }
-    buffer.freeze()
-}
-
-fn build_stream_options(language: Option<&str>) -> Options {
-    let mut builder = Options::builder();
-    let mut select_language = Language::zh_CN;
-    if let Some(language) = language_from_value(language) {
-        select_language = language;
-        builder = builder.language(select_language.clone());
-    }
-
-    builder = builder.smart_format(false);
-    builder = if select_language == Language::zh || select_language == Language::zh_CN {
-        builder.model(Model::Nova3)
+fn build_stream_options(language: &LanguageSetting, vocabulary: &[String]) -> Options {
+    // Same model and language rules as the raw API client, so switching
+    // backends does not change recognition quality.
+    let (model, language) = if language.is_multilingual() {
+        warn_unsupported_multilingual(language);
+        ("nova-3", Some(MULTILINGUAL_LANGUAGE.to_string()))
     } else {
-        builder.model(Model::Nova3)
+        let fixed = language.fixed_language();
+        (select_model(fixed), normalize_language(fixed))
     };
 
-    builder.build()
-}
-
-fn language_from_value(value: Option<&str>) -> Option<Language> {
-    let trimmed = value?.trim();
-    if trimmed.is_empty() {
-        return None;
+    let mut builder = Options::builder()
+        .model(Model::CustomId(model.to_string()))
+        .smart_format(false)
+        .punctuate(false);
+    if let Some(language) = language {
+        builder = builder.language(Language::Other(language));
     }
-
-    Some(match_language(trimmed))
-}
-
-fn match_language(value: &str) -> Language {
-    let cleaned = value.trim().replace(['-', ' '], "_").to_lowercase();
-
-    match cleaned.as_str() {
-        "en" => Language::en,
-        "zh" => Language::zh,
-        "zh_cn" | "zh_CN" => Language::zh_CN,
-        _ => Language::Other(value.trim().to_string()),
+    if !vocabulary.is_empty() {
+        // Nova-3 boosts with `keyterm` prompting; older models only know `keywords`.
+        builder = if model == "nova-3" {
+            builder.keyterms(vocabulary.iter().map(String::as_str))
+        } else {
+            builder.keywords(vocabulary.iter().map(String::as_str))
+        };
     }
-}
 
-#[derive(Debug)]
-struct StreamBridgeError;
+    builder.build()
+}
 
 fn append_utterance_segment(buffer: &mut String, segment: &str) {
     if !buffer.is_empty() {
@@ -271,83 +343,6 @@ fn flush_utterance(buffer: &mut String, callback: &PcmCallback) {
         return;
     }
 
-    emit_commit(callback, "Deepgram", trimmed);
+    emit_commit(callback, VENDOR_NAME, trimmed);
     buffer.clear();
 }
-
-fn merge_segments(prefix: &str, suffix: &str) -> String {
-    let prefix = prefix.trim();
-    let suffix = suffix.trim();
-
-    match (prefix.is_empty(), suffix.is_empty()) {
-        (true, true) => String::new(),
-        (false, true) => prefix.to_string(),
-        (true, false) => suffix.to_string(),
-        (false, false) => {
-            if should_join_without_space(prefix, suffix) {
-                format!("{prefix}{suffix}")
-            } else {
-                format!("{prefix} {suffix}")
-            }
-        }
-    }
-}
-
-fn should_join_without_space(prefix: &str, suffix: &str) -> bool {
-    let Some(last) = prefix.chars().next_back() else {
-        return true;
-    };
-    let Some(first) = suffix.chars().next() else {
-        return true;
-    };
-
-    last.is_whitespace()
-        || first.is_whitespace()
-        || is_cjk(last)
-        || is_cjk(first)
-        || is_spacing_punctuation(last)
-        || is_spacing_punctuation(first)
-}
-
-fn is_cjk(ch: char) -> bool {
-    matches!(
-        ch as u32,
-        0x4E00..=0x9FFF
-            | 0x3400..=0x4DBF
-            | 0x3040..=0x30FF
-            | 0xAC00..=0xD7AF
-            | 0xF900..=0xFAFF
-    )
-}
-
-fn is_spacing_punctuation(ch: char) -> bool {
-    matches!(
-        ch,
-        ',' | '.'
-            | '!'
-            | '?'
-            | ':'
-            | ';'
-            | ')'
-            | ']'
-            | '}'
-            | '，'
-            | '。'
-            | '！'
-            | '？'
-            | '：'
-            | '；'
-            | '）'
-            | '】'
-            | '」'
-            | '、'
-    )
-}
-
-impl fmt::Display for StreamBridgeError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("stream bridge error")
-    }
-}
-
-impl std::error::Error for StreamBridgeError {}
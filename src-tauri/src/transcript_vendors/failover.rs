@@ -0,0 +1,793 @@
+#![allow(clippy::collapsible_if)]
+
+use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptEvent, TranscriptEventKind,
+    TranscriptVendors, start_transcriber,
+};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Keep enough audio to cover a reconnect plus the utterance in flight.
+const REPLAY_BUFFER_MS: u64 = 15_000;
+// Audio this close to a commit may not be part of it yet, so keep it around.
+const REPLAY_KEEP_AFTER_COMMIT_MS: u64 = 2_000;
+// Replayed audio is re-chunked so it fits in the vendors' 64 slot queues.
+const REPLAY_CHUNK_MS: u64 = 500;
+const MAX_CONSECUTIVE_QUEUE_FAILURES: u32 = 20;
+const SPEECH_WITHOUT_TRANSCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+// A pause this long forgets earlier loud audio that never produced a transcript.
+const SPEECH_RESET_SILENCE: Duration = Duration::from_secs(5);
+const SPEECH_PEAK_THRESHOLD: i16 = 900;
+const SPEECH_MEAN_ABS_THRESHOLD: f32 = 120.0;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorSwitchedEvent {
+    pub from: String,
+    pub to: String,
+    pub reason: String,
+    pub replayed_ms: u64,
+}
+
+pub type VendorSwitchCallback = Arc<dyn Fn(VendorSwitchedEvent) + Send + Sync + 'static>;
+
+/// Opens one vendor session; tests swap in fake vendors.
+type VendorStarter = Arc<
+    dyn Fn(
+            TranscriptVendors,
+            PcmCallback,
+            StatusCallback,
+        ) -> Result<Arc<dyn StreamingTranscriber>, String>
+        + Send
+        + Sync,
+>;
+
+/// Selected vendor first, then the configured fallbacks in order. Unknown
+/// names are skipped the same way unresolvable LLM fallbacks are.
+pub fn build_vendor_attempt_plan(
+    selected: TranscriptVendors,
+    fallback_vendors: Option<&str>,
+) -> Vec<TranscriptVendors> {
+    let mut plan = vec![selected];
+    for name in fallback_vendors
+        .unwrap_or_default()
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name.parse::<TranscriptVendors>() {
+            Ok(vendor) if !plan.contains(&vendor) => plan.push(vendor),
+            Ok(_) => {}
+            Err(err) => eprintln!("Skipping fallback ASR vendor {name}: {err}"),
+        }
+    }
+
+    plan
+}
+
+pub fn start_transcriber_with_failover(
+    selected: TranscriptVendors,
+    sample_rate: u32,
+    callback: PcmCallback,
+    status_callback: Option<StatusCallback>,
+    switch_callback: Option<VendorSwitchCallback>,
+    transcript_config: TranscriptRuntimeConfig,
+) -> Result<Arc<dyn StreamingTranscriber>, String> {
+    let plan =
+        build_vendor_attempt_plan(selected, transcript_config.asr_fallback_vendors.as_deref());
+    if plan.len() == 1 {
+        return start_transcriber(
+            selected,
+            sample_rate,
+            callback,
+            status_callback,
+            transcript_config,
+        );
+    }
+
+    let transcriber = FailoverTranscriber::start(
+        plan,
+        sample_rate,
+        callback,
+        status_callback,
+        switch_callback,
+        transcript_config,
+    )?;
+    Ok(Arc::new(transcriber))
+}
+
+struct ActiveVendor {
+    vendor: TranscriptVendors,
+    generation: u64,
+    transcriber: Arc<dyn StreamingTranscriber>,
+}
+
+struct FailoverState {
+    active: Option<ActiveVendor>,
+    next_index: usize,
+    consecutive_queue_failures: u32,
+    /// A worker is bringing up the next vendor; audio only goes to the replay buffer.
+    switching: bool,
+    stopped: bool,
+}
+
+/// State written from the vendor threads through the wrapped callbacks.
+struct SharedHealth {
+    generation: AtomicU64,
+    failure: Mutex<Option<(u64, String)>>,
+    speech: Mutex<SpeechWatch>,
+    replay: Mutex<ReplayBuffer>,
+}
+
+pub struct FailoverTranscriber {
+    inner: Arc<FailoverInner>,
+}
+
+/// Shared with the switch worker, which connects the next vendor off the
+/// capture thread.
+struct FailoverInner {
+    plan: Vec<TranscriptVendors>,
+    sample_rate: u32,
+    callback: PcmCallback,
+    status_callback: Option<StatusCallback>,
+    switch_callback: Option<VendorSwitchCallback>,
+    starter: VendorStarter,
+    state: Mutex<FailoverState>,
+    shared: Arc<SharedHealth>,
+    exhausted: AtomicBool,
+}
+
+impl FailoverTranscriber {
+    pub fn start(
+        plan: Vec<TranscriptVendors>,
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        switch_callback: Option<VendorSwitchCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        let starter: VendorStarter = Arc::new(move |vendor, callback, status_callback| {
+            start_transcriber(
+                vendor,
+                sample_rate,
+                callback,
+                Some(status_callback),
+                transcript_config.clone(),
+            )
+        });
+        Self::start_with(
+            plan,
+            sample_rate,
+            callback,
+            status_callback,
+            switch_callback,
+            starter,
+        )
+    }
+
+    fn start_with(
+        plan: Vec<TranscriptVendors>,
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        switch_callback: Option<VendorSwitchCallback>,
+        starter: VendorStarter,
+    ) -> Result<Self, String> {
+        let shared = Arc::new(SharedHealth {
+            generation: AtomicU64::new(0),
+            failure: Mutex::new(None),
+            speech: Mutex::new(SpeechWatch::default()),
+            replay: Mutex::new(ReplayBuffer::new(
+                ms_to_samples(REPLAY_BUFFER_MS, sample_rate),
+                ms_to_samples(REPLAY_KEEP_AFTER_COMMIT_MS, sample_rate),
+            )),
+        });
+        let inner = FailoverInner {
+            plan,
+            sample_rate,
+            callback,
+            status_callback,
+            switch_callback,
+            starter,
+            state: Mutex::new(FailoverState {
+                active: None,
+                next_index: 0,
+                consecutive_queue_failures: 0,
+                switching: false,
+                stopped: false,
+            }),
+            shared,
+            exhausted: AtomicBool::new(false),
+        };
+
+        let mut errors = Vec::new();
+        {
+            let mut state = inner.state.lock().unwrap();
+            while state.active.is_none() && state.next_index < inner.plan.len() {
+                let vendor = inner.plan[state.next_index];
+                state.next_index += 1;
+                match inner.start_vendor(vendor) {
+                    Ok(active) => state.active = Some(active),
+                    Err(err) => {
+                        eprintln!("Skipping ASR vendor {}: {err}", vendor.as_str());
+                        errors.push(err);
+                    }
+                }
+            }
+
+            if state.active.is_none() {
+                return Err(errors.join("; "));
+            }
+        }
+
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    fn queue_chunk_at(&self, chunk: Vec<i16>, now: Instant) -> Result<(), String> {
+        let inner = &self.inner;
+        if inner.exhausted.load(Ordering::SeqCst) {
+            return Err("All configured ASR vendors failed".into());
+        }
+
+        // Buffer under the state lock so a switch worker's replay sees each
+        // chunk exactly once: either in the replay or queued afterwards.
+        let mut state = inner.state.lock().unwrap();
+        inner
+            .shared
+            .speech
+            .lock()
+            .unwrap()
+            .observe(chunk_has_speech(&chunk), now);
+        inner.shared.replay.lock().unwrap().push(chunk.clone());
+        if state.switching {
+            return Ok(());
+        }
+
+        let Some(active) = state.active.as_ref() else {
+            return Err("ASR failover has no active vendor".into());
+        };
+
+        if let Some(reason) = inner.check_health(active, &state, now) {
+            // The replay after switching already includes this chunk.
+            inner.begin_switch(&mut state, reason);
+            return Ok(());
+        }
+
+        match active.transcriber.queue_chunk(chunk) {
+            Ok(()) => {
+                state.consecutive_queue_failures = 0;
+                Ok(())
+            }
+            Err(err) => {
+                state.consecutive_queue_failures += 1;
+                eprintln!("ASR failover queue failure: {err}");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FailoverInner {
+    fn start_vendor(&self, vendor: TranscriptVendors) -> Result<ActiveVendor, String> {
+        let generation = self.shared.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.shared.speech.lock().unwrap().reset();
+
+        let callback = {
+            let shared = self.shared.clone();
+            let outer = self.callback.clone();
+            Arc::new(move |event: TranscriptEvent| {
+                // Late events from a replaced vendor would duplicate the replayed audio.
+                if shared.generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                shared.speech.lock().unwrap().reset();
+                if event.kind == TranscriptEventKind::Commit {
+                    shared.replay.lock().unwrap().trim_after_commit();
+                }
+                outer(event);
+            }) as PcmCallback
+        };
+        let status_callback = {
+            let shared = self.shared.clone();
+            Arc::new(move |message: String| {
+                if shared.generation.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let mut failure = shared.failure.lock().unwrap();
+                if failure.is_none() {
+                    *failure = Some((generation, message));
+                }
+            }) as StatusCallback
+        };
+
+        let transcriber = (self.starter)(vendor, callback, status_callback)?;
+
+        Ok(ActiveVendor {
+            vendor,
+            generation,
+            transcriber,
+        })
+    }
+
+    fn take_failure(&self, generation: u64) -> Option<String> {
+        let mut failure = self.shared.failure.lock().unwrap();
+        match failure.take() {
+            Some((failed_generation, message)) if failed_generation == generation => Some(message),
+            // Late reports from a vendor we already replaced.
+            Some(_) | None => None,
+        }
+    }
+
+    fn check_health(
+        &self,
+        active: &ActiveVendor,
+        state: &FailoverState,
+        now: Instant,
+    ) -> Option<String> {
+        if let Some(message) = self.take_failure(active.generation) {
+            return Some(message);
+        }
+
+        if state.consecutive_queue_failures >= MAX_CONSECUTIVE_QUEUE_FAILURES {
+            return Some(format!(
+                "{} stopped accepting audio ({} consecutive queue failures)",
+                active.transcriber.get_vendor_name(),
+                state.consecutive_queue_failures
+            ));
+        }
+
+        if self.shared.speech.lock().unwrap().stalled(now) {
+            return Some(format!(
+                "{} returned no transcript for {}s of speech",
+                active.transcriber.get_vendor_name(),
+                SPEECH_WITHOUT_TRANSCRIPT_TIMEOUT.as_secs()
+            ));
+        }
+
+        None
+    }
+
+    /// Retires the active vendor and hands the reconnect to a worker thread,
+    /// so a slow WebSocket/TLS handshake never stalls audio capture.
+    fn begin_switch(self: &Arc<Self>, state: &mut FailoverState, reason: String) {
+        // Stop forwarding the old vendor's events before its shutdown flushes a commit.
+        self.shared.generation.fetch_add(1, Ordering::SeqCst);
+        let previous = state.active.take();
+        let from = previous
+            .as_ref()
+            .map(|active| active.vendor.as_str().to_string())
+            .unwrap_or_default();
+        if let Some(previous) = previous {
+            thread::spawn(move || previous.transcriber.shutdown());
+        }
+        state.consecutive_queue_failures = 0;
+        state.switching = true;
+
+        let inner = self.clone();
+        thread::spawn(move || inner.switch_vendor(from, reason));
+    }
+
+    fn switch_vendor(&self, from: String, mut reason: String) {
+        loop {
+            let vendor = {
+                let mut state = self.state.lock().unwrap();
+                if state.stopped {
+                    return;
+                }
+                let Some(&vendor) = self.plan.get(state.next_index) else {
+                    break;
+                };
+                state.next_index += 1;
+                vendor
+            };
+
+            match self.start_vendor(vendor) {
+                Ok(active) => {
+                    let mut state = self.state.lock().unwrap();
+                    if state.stopped {
+                        drop(state);
+                        active.transcriber.shutdown();
+                        return;
+                    }
+                    let replayed_ms = self.replay_buffered_audio(&active);
+                    state.active = Some(active);
+                    state.switching = false;
+                    drop(state);
+
+                    write_switch_log(&from, vendor.as_str(), &reason);
+                    if let Some(callback) = self.switch_callback.as_ref() {
+                        callback(VendorSwitchedEvent {
+                            from,
+                            to: vendor.as_str().to_string(),
+                            reason,
+                            replayed_ms,
+                        });
+                    }
+                    return;
+                }
+                Err(err) => {
+                    eprintln!("Skipping ASR vendor {}: {err}", vendor.as_str());
+                    reason = err;
+                }
+            }
+        }
+
+        self.exhausted.store(true, Ordering::SeqCst);
+        self.state.lock().unwrap().switching = false;
+        let message = format!("All configured ASR vendors failed, last error: {reason}");
+        eprintln!("{message}");
+        if let Some(callback) = self.status_callback.as_ref() {
+            callback(message);
+        }
+    }
+
+    fn replay_buffered_audio(&self, active: &ActiveVendor) -> u64 {
+        let chunks = self
+            .shared
+            .replay
+            .lock()
+            .unwrap()
+            .rechunk(ms_to_samples(REPLAY_CHUNK_MS, self.sample_rate));
+        let mut replayed_samples = 0_u64;
+        for chunk in chunks {
+            let len = chunk.len() as u64;
+            if let Err(err) = active.transcriber.queue_chunk(chunk) {
+                eprintln!("Failed to replay buffered audio: {err}");
+                break;
+            }
+            replayed_samples += len;
+        }
+
+        if self.sample_rate == 0 {
+            0
+        } else {
+            replayed_samples * 1000 / self.sample_rate as u64
+        }
+    }
+}
+
+impl StreamingTranscriber for FailoverTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        self.queue_chunk_at(chunk, Instant::now())
+    }
+
+    fn get_vendor_name(&self) -> String {
+        self.inner
+            .state
+            .lock()
+            .unwrap()
+            .active
+            .as_ref()
+            .map(|active| active.transcriber.get_vendor_name())
+            .unwrap_or_else(|| "Failover".to_string())
+    }
+
+    fn force_endpoint(&self) -> Result<(), String> {
+        let state = self.inner.state.lock().unwrap();
+        if let Some(active) = state.active.as_ref() {
+            if let Err(err) = active.transcriber.force_endpoint() {
+                eprintln!("ASR failover force endpoint failed: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    fn shutdown(&self) {
+        let active = {
+            let mut state = self.inner.state.lock().unwrap();
+            state.stopped = true;
+            state.active.take()
+        };
+        if let Some(active) = active {
+            active.transcriber.shutdown();
+        }
+    }
+}
+
+fn write_switch_log(from: &str, to: &str, reason: &str) {
+    println!("ASR vendor switched from {from} to {to}: {reason}");
+}
+
+/// Loud audio heard since the last vendor event. Only continuous talk counts:
+/// noise or music followed by a pause never yields a transcript and is forgotten.
+#[derive(Default)]
+struct SpeechWatch {
+    pending_since: Option<Instant>,
+    silent_since: Option<Instant>,
+}
+
+impl SpeechWatch {
+    fn observe(&mut self, has_speech: bool, now: Instant) {
+        if has_speech {
+            self.pending_since.get_or_insert(now);
+            self.silent_since = None;
+        } else if self.pending_since.is_some() {
+            let silent_since = *self.silent_since.get_or_insert(now);
+            if now.duration_since(silent_since) >= SPEECH_RESET_SILENCE {
+                self.reset();
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn stalled(&self, now: Instant) -> bool {
+        self.pending_since
+            .is_some_and(|since| now.duration_since(since) >= SPEECH_WITHOUT_TRANSCRIPT_TIMEOUT)
+    }
+}
+
+struct ReplayBuffer {
+    chunks: VecDeque<Vec<i16>>,
+    samples: usize,
+    max_samples: usize,
+    keep_after_commit_samples: usize,
+}
+
+impl ReplayBuffer {
+    fn new(max_samples: usize, keep_after_commit_samples: usize) -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            samples: 0,
+            max_samples,
+            keep_after_commit_samples,
+        }
+    }
+
+    fn push(&mut self, chunk: Vec<i16>) {
+        self.samples += chunk.len();
+        self.chunks.push_back(chunk);
+        self.trim_to(self.max_samples);
+    }
+
+    fn trim_after_commit(&mut self) {
+        self.trim_to(self.keep_after_commit_samples);
+    }
+
+    fn trim_to(&mut self, limit: usize) {
+        while let Some(front) = self.chunks.front() {
+            if self.samples - front.len() < limit {
+                break;
+            }
+            self.samples -= front.len();
+            self.chunks.pop_front();
+        }
+    }
+
+    fn rechunk(&self, chunk_samples: usize) -> Vec<Vec<i16>> {
+        let chunk_samples = chunk_samples.max(1);
+        let mut merged = Vec::new();
+        let mut current = Vec::with_capacity(chunk_samples);
+        for sample in self.chunks.iter().flatten() {
+            current.push(*sample);
+            if current.len() == chunk_samples {
+                merged.push(std::mem::replace(
+                    &mut current,
+                    Vec::with_capacity(chunk_samples),
+                ));
+            }
+        }
+        if !current.is_empty() {
+            merged.push(current);
+        }
+        merged
+    }
+}
+
+fn ms_to_samples(ms: u64, sample_rate: u32) -> usize {
+    (ms * sample_rate as u64 / 1000) as usize
+}
+
+fn chunk_has_speech(chunk: &[i16]) -> bool {
+    if chunk.is_empty() {
+        return false;
+    }
+
+    let peak = chunk
+        .iter()
+        .map(|sample| sample.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    let mean_abs = chunk
+        .iter()
+        .map(|sample| sample.unsigned_abs() as f32)
+        .sum::<f32>()
+        / chunk.len() as f32;
+
+    peak >= SPEECH_PEAK_THRESHOLD as u16 || mean_abs >= SPEECH_MEAN_ABS_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        FailoverTranscriber, ReplayBuffer, VendorStarter, VendorSwitchCallback,
+        build_vendor_attempt_plan,
+    };
+    use crate::transcript_vendors::{
+        PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit,
+    };
+    use std::sync::{Arc, Mutex, mpsc};
+    use std::time::{Duration, Instant};
+
+    #[derive(Default)]
+    struct FakeVendor {
+        chunks: Mutex<Vec<Vec<i16>>>,
+    }
+
+    impl StreamingTranscriber for FakeVendor {
+        fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+            self.chunks.lock().unwrap().push(chunk);
+            Ok(())
+        }
+
+        fn get_vendor_name(&self) -> String {
+            "Fake".to_string()
+        }
+
+        fn shutdown(&self) {}
+    }
+
+    type StartedVendor = (PcmCallback, StatusCallback, Arc<FakeVendor>);
+
+    fn fake_starter(started: Arc<Mutex<Vec<TranscriptVendors>>>) -> VendorStarter {
+        Arc::new(move |vendor, _callback, _status_callback| {
+            started.lock().unwrap().push(vendor);
+            Ok(Arc::new(FakeVendor::default()) as Arc<dyn StreamingTranscriber>)
+        })
+    }
+
+    #[test]
+    fn attempt_plan_starts_with_selected_vendor_and_dedupes() {
+        assert_eq!(
+            build_vendor_attempt_plan(
+                TranscriptVendors::DeepGram,
+                Some("speechmatics, deepgram,unknown , dashscope")
+            ),
+            vec![
+                TranscriptVendors::DeepGram,
+                TranscriptVendors::SpeechMatics,
+                TranscriptVendors::DashScope,
+            ]
+        );
+        assert_eq!(
+            build_vendor_attempt_plan(TranscriptVendors::GlaDia, None),
+            vec![TranscriptVendors::GlaDia]
+        );
+    }
+
+    #[test]
+    fn vendor_ids_round_trip_through_from_str() {
+        for vendor in TranscriptVendors::ALL {
+            assert_eq!(vendor.as_str().parse::<TranscriptVendors>(), Ok(vendor));
+        }
+    }
+
+    #[test]
+    fn replay_buffer_drops_oldest_audio_beyond_limit() {
+        let mut buffer = ReplayBuffer::new(8, 4);
+        buffer.push(vec![1; 4]);
+        buffer.push(vec![2; 4]);
+        buffer.push(vec![3; 4]);
+
+        assert_eq!(buffer.samples, 8);
+        assert_eq!(buffer.rechunk(100), vec![vec![2, 2, 2, 2, 3, 3, 3, 3]]);
+    }
+
+    #[test]
+    fn replay_buffer_keeps_tail_after_commit() {
+        let mut buffer = ReplayBuffer::new(100, 3);
+        buffer.push(vec![1; 4]);
+        buffer.push(vec![2; 2]);
+        buffer.push(vec![3; 2]);
+        buffer.trim_after_commit();
+
+        assert_eq!(buffer.rechunk(3), vec![vec![2, 2, 3], vec![3]]);
+    }
+
+    #[test]
+    fn noise_followed_by_silence_does_not_switch_vendors() {
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let switches = Arc::new(Mutex::new(Vec::new()));
+        let switch_callback: VendorSwitchCallback = {
+            let switches = switches.clone();
+            Arc::new(move |event| switches.lock().unwrap().push(event))
+        };
+        let transcriber = FailoverTranscriber::start_with(
+            vec![TranscriptVendors::DeepGram, TranscriptVendors::SpeechMatics],
+            16_000,
+            Arc::new(|_| {}),
+            None,
+            Some(switch_callback),
+            fake_starter(started.clone()),
+        )
+        .unwrap();
+
+        // 20s of noise, 10s of silence, 20s more noise: never 30s of talk in a row.
+        let start = Instant::now();
+        for step in 0..500_u64 {
+            let loud = !(200..300).contains(&step);
+            let sample = if loud { 4_000 } else { 0 };
+            transcriber
+                .queue_chunk_at(
+                    vec![sample; 1_600],
+                    start + Duration::from_millis(step * 100),
+                )
+                .unwrap();
+        }
+
+        assert!(switches.lock().unwrap().is_empty());
+        assert_eq!(*started.lock().unwrap(), vec![TranscriptVendors::DeepGram]);
+    }
+
+    #[test]
+    fn switch_connects_off_the_capture_thread_and_drops_late_commits() {
+        let (release, release_rx) = mpsc::channel::<()>();
+        let release_rx = Mutex::new(release_rx);
+        let vendors: Arc<Mutex<Vec<StartedVendor>>> = Arc::default();
+        let starter: VendorStarter = {
+            let vendors = vendors.clone();
+            Arc::new(move |vendor, callback, status_callback| {
+                if vendor == TranscriptVendors::SpeechMatics {
+                    // A slow handshake; the capture thread must not wait for it.
+                    release_rx.lock().unwrap().recv().unwrap();
+                }
+                let fake = Arc::new(FakeVendor::default());
+                vendors
+                    .lock()
+                    .unwrap()
+                    .push((callback, status_callback, fake.clone()));
+                Ok(fake as Arc<dyn StreamingTranscriber>)
+            })
+        };
+        let committed = Arc::new(Mutex::new(Vec::new()));
+        let callback: PcmCallback = {
+            let committed = committed.clone();
+            Arc::new(move |event| committed.lock().unwrap().push(event.text))
+        };
+        let (switched, switched_rx) = mpsc::channel();
+        let switch_callback: VendorSwitchCallback = Arc::new(move |event| {
+            let _ = switched.send(event);
+        });
+        let transcriber = FailoverTranscriber::start_with(
+            vec![TranscriptVendors::DeepGram, TranscriptVendors::SpeechMatics],
+            1_000,
+            callback,
+            None,
+            Some(switch_callback),
+            starter,
+        )
+        .unwrap();
+        let (first_callback, first_status, _) = vendors.lock().unwrap()[0].clone();
+
+        transcriber.queue_chunk(vec![1; 10]).unwrap();
+        first_status("socket closed".to_string());
+        transcriber.queue_chunk(vec![2; 10]).unwrap();
+        transcriber.queue_chunk(vec![3; 10]).unwrap();
+        emit_commit(&first_callback, "Fake", "flushed on shutdown");
+        release.send(()).unwrap();
+
+        let event = switched_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event.from, "deepgram");
+        assert_eq!(event.to, "speechmatics");
+        assert_eq!(event.reason, "socket closed");
+        assert_eq!(event.replayed_ms, 30);
+        transcriber.queue_chunk(vec![4; 10]).unwrap();
+
+        let second = vendors.lock().unwrap()[1].2.clone();
+        let mut replayed = vec![1; 10];
+        replayed.extend([2; 10]);
+        replayed.extend([3; 10]);
+        assert_eq!(*second.chunks.lock().unwrap(), vec![replayed, vec![4; 10]]);
+        assert!(committed.lock().unwrap().is_empty());
+    }
+}
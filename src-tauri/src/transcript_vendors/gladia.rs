@@ -3,14 +3,19 @@
 ///https://docs.gladia.io/api-reference/v2/live/init
 ///https://docs.gladia.io/api-reference/v2/live/websocket
 use crate::provider_config::{
-    TranscriptRuntimeConfig, resolve_optional_string, resolve_required_string,
-    resolve_string_or_default,
+    GLADIA_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string, resolve_string_or_default,
 };
+use crate::proxy::{ProxySettings, connect_websocket, http_client};
+use crate::transcript_latency::{note_result_offset, note_stream_started};
+use crate::transcript_translation::TranslationSettings;
+use crate::transcript_vendors::language::{DetectedLanguage, LanguageSetting, primary_subtag};
+use crate::transcript_vendors::token_broker::{CredentialSource, VendorCredential};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    emit_translation, report_audio_sent, report_keepalive_sent,
 };
 use futures_util::{SinkExt, StreamExt, future::try_join};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
@@ -21,12 +26,9 @@ use tauri::http::Uri;
 use tokio::runtime::Runtime;
 use tokio::sync::{mpsc, mpsc::error::TrySendError, oneshot, watch};
 use tokio::time::{self, Duration, MissedTickBehavior};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{
-        client::{ClientRequestBuilder, IntoClientRequest},
-        protocol::Message,
-    },
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
 };
 
 pub struct GladiaTranscriber {
@@ -48,6 +50,8 @@ const HEARTBEAT_INTERVAL_SECS: u64 = 20;
 const IDLE_SILENCE_INTERVAL_SECS: u64 = 15;
 const IDLE_SILENCE_CHUNK_MS: u32 = 100;
 const MAX_RECONNECT_BACKOFF_SECS: u64 = 5;
+const DEFAULT_API_URL: &str = "https://api.gladia.io/v2/live";
+const TRANSLATION_MODEL: &str = "base";
 
 impl GladiaTranscriber {
     pub fn start(
@@ -56,20 +60,35 @@ impl GladiaTranscriber {
         status_callback: Option<StatusCallback>,
         transcript_config: TranscriptRuntimeConfig,
     ) -> Result<Self, String> {
-        let api_key = resolve_required_string(
-            transcript_config.gladia_api_key.as_deref(),
-            &["GLADIA_API_KEY"],
+        let credentials = CredentialSource::resolve(
+            TranscriptVendors::GlaDia,
+            &transcript_config,
+            resolve_optional_string(transcript_config.gladia_api_key.as_deref(), GLADIA_ENV_KEYS),
             "GLADIA_API_KEY",
         )?;
-        let language = resolve_optional_string(
-            transcript_config.gladia_language.as_deref(),
-            &["GLADIA_LANGUAGE"],
+        let language = LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.gladia_language.as_deref(),
+                &["GLADIA_LANGUAGE"],
+            ),
         );
         let model = resolve_string_or_default(
             transcript_config.gladia_model.as_deref(),
             &["GLADIA_MODEL"],
             DEFAULT_MODEL,
         );
+        let api_url = resolve_string_or_default(
+            transcript_config.gladia_api_url.as_deref(),
+            &["GLADIA_API_URL"],
+            DEFAULT_API_URL,
+        );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::GlaDia.as_str(), &api_url);
+        let vocabulary = resolve_vocabulary(&transcript_config);
+        let translation_targets = TranslationSettings::resolve(&transcript_config)
+            .vendor_target_languages()
+            .to_vec();
 
         let (sender, receiver) = mpsc::channel::<Vec<i16>>(64);
         let (shutdown, shutdown_rx) = oneshot::channel::<()>();
@@ -79,9 +98,13 @@ impl GladiaTranscriber {
         let handle = thread::spawn(move || {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_stream(
-                api_key,
+                credentials,
+                api_url,
+                proxy,
                 language,
                 model,
+                vocabulary,
+                translation_targets,
                 sample_rate,
                 callback,
                 receiver,
@@ -149,16 +172,41 @@ impl StreamingTranscriber for GladiaTranscriber {
 }
 
 async fn run_stream(
-    api_key: String,
-    language: Option<String>,
+    credentials: CredentialSource,
+    api_url: String,
+    proxy: Option<String>,
+    language: LanguageSetting,
     model: String,
+    vocabulary: Vec<String>,
+    translation_targets: Vec<String>,
     sample_rate: u32,
     callback: PcmCallback,
     mut audio_rx: mpsc::Receiver<Vec<i16>>,
     mut shutdown_rx: oneshot::Receiver<()>,
     stop_requested: Arc<AtomicBool>,
 ) -> Result<(), String> {
-    let ws_url = create_live_session(&api_key, &model, sample_rate, language.as_deref()).await?;
+    let request_body = build_live_session_request(
+        &model,
+        sample_rate,
+        &language,
+        &vocabulary,
+        &translation_targets,
+    );
+    let session_config = serde_json::to_value(&request_body)
+        .map_err(|e| format!("Failed to encode Gladia live session request: {e}"))?;
+    // A token service creates the session itself and hands back its URL.
+    let (ws_url, api_key) = match credentials.fetch(Some(&session_config)).await? {
+        VendorCredential::SessionUrl(url) => (url, None),
+        VendorCredential::ApiKey(api_key) => (
+            create_live_session(&api_url, proxy.as_deref(), &api_key, &request_body).await?,
+            Some(api_key),
+        ),
+        VendorCredential::Token(_) => {
+            return Err("Token service must return a live session URL for Gladia".to_string());
+        }
+    };
+    let detected_language = DetectedLanguage::default();
+    let callback = detected_language.wrap_callback(callback);
     let mut reconnect_attempt = 0_u32;
 
     loop {
@@ -168,9 +216,11 @@ async fn run_stream(
 
         match stream_once(
             &ws_url,
-            &api_key,
+            api_key.as_deref(),
+            proxy.as_deref(),
             sample_rate,
             &callback,
+            &detected_language,
             &mut audio_rx,
             &mut shutdown_rx,
             stop_requested.clone(),
@@ -193,9 +243,11 @@ async fn run_stream(
 
 async fn stream_once(
     ws_url: &str,
-    api_key: &str,
+    api_key: Option<&str>,
+    proxy: Option<&str>,
     sample_rate: u32,
     callback: &PcmCallback,
+    detected_language: &DetectedLanguage,
     audio_rx: &mut mpsc::Receiver<Vec<i16>>,
     mut shutdown_rx: &mut oneshot::Receiver<()>,
     stop_requested: Arc<AtomicBool>,
@@ -203,16 +255,20 @@ async fn stream_once(
     let uri: Uri = ws_url
         .parse()
         .map_err(|e| format!("Failed to parse Gladia websocket URI: {e}"))?;
-    let builder = ClientRequestBuilder::new(uri)
-        .with_header("x-gladia-key", api_key)
-        .with_header("Content-Type", "application/json");
+    let mut builder =
+        ClientRequestBuilder::new(uri).with_header("Content-Type", "application/json");
+    if let Some(api_key) = api_key {
+        builder = builder.with_header("x-gladia-key", api_key);
+    }
     let request = builder
         .into_client_request()
         .map_err(|e| format!("Failed to build Gladia websocket request: {e}"))?;
 
-    let (ws_stream, _) = connect_async(request)
+    let (ws_stream, _) = connect_websocket(request, proxy)
         .await
         .map_err(|e| format!("Failed to connect to Gladia: {e}"))?;
+    // Result offsets restart with every connection.
+    note_stream_started(TranscriptVendors::GlaDia);
 
     let (mut sink, mut stream) = ws_stream.split();
     let (termination_tx, mut termination_rx) = watch::channel(false);
@@ -246,6 +302,7 @@ async fn stream_once(
                         sink.send(Message::Binary(audio_bytes.into()))
                             .await
                             .map_err(|e| format!("Failed to send audio chunk to Gladia: {e}"))?;
+                        report_audio_sent(TranscriptVendors::GlaDia, samples.len(), sample_rate);
                         idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                     }
                     None => break,
@@ -263,6 +320,7 @@ async fn stream_once(
                     sink.send(Message::Binary(audio_bytes.into()))
                         .await
                         .map_err(|e| format!("Failed to send Gladia idle silence chunk: {e}"))?;
+                    report_keepalive_sent(TranscriptVendors::GlaDia, idle_keepalive_chunk.len(), sample_rate);
                     idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                 }
             }
@@ -293,6 +351,7 @@ async fn stream_once(
 
                 match message {
                     Message::Text(payload) => {
+                        detected_language.update(extract_detected_language(&payload).as_deref());
                         if let Some((kind, text)) = parse_transcript(&payload) {
                             let should_emit = match kind {
                                 TranscriptKind::Partial => RECEIVE_PARTIAL_TRANSCRIPTS,
@@ -305,10 +364,15 @@ async fn stream_once(
                                         emit_draft(&callback, "Gladia", text.as_str());
                                     }
                                     TranscriptKind::Final => {
+                                        if let Some(end) = utterance_audio_end(&payload) {
+                                            note_result_offset(TranscriptVendors::GlaDia, end);
+                                        }
                                         emit_commit(&callback, "Gladia", text.as_str());
                                     }
                                 }
                             }
+                        } else if let Some((language, text)) = parse_translation(&payload) {
+                            emit_translation(&callback, "Gladia", &language, text);
                         } else if is_error_payload(&payload) {
                             let _ = termination_tx.send(true);
                             return Err(format!("Gladia returned error payload: {payload}"));
@@ -401,6 +465,37 @@ fn parse_legacy_transcript(value: &Value) -> Option<(TranscriptKind, String)> {
     Some((kind, transcript))
 }
 
+/// Realtime translations arrive as `translation` messages after the final
+/// transcript of the same utterance.
+fn parse_translation(payload: &str) -> Option<(String, String)> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    if value.get("type").and_then(|v| v.as_str())? != "translation" {
+        return None;
+    }
+    let data = value.get("data")?;
+    let translated = data.get("translated_utterance")?;
+    let text = translated.get("text")?.as_str()?.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let language = data
+        .get("target_language")
+        .or_else(|| translated.get("language"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    Some((language.to_string(), text.to_string()))
+}
+
+fn extract_detected_language(payload: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    value
+        .get("data")?
+        .get("utterance")?
+        .get("language")?
+        .as_str()
+        .map(ToString::to_string)
+}
+
 fn transcript_kind(value: &Value) -> TranscriptKind {
     if let Some(kind) = value.get("type").and_then(|v| v.as_str()) {
         if kind.eq_ignore_ascii_case("final") || kind.contains("final") {
@@ -423,6 +518,16 @@ fn transcript_kind(value: &Value) -> TranscriptKind {
     }
 }
 
+/// End of a final utterance, in seconds from the session start.
+fn utterance_audio_end(payload: &str) -> Option<f64> {
+    let value: Value = serde_json::from_str(payload).ok()?;
+    value
+        .get("data")?
+        .get("utterance")?
+        .get("end")
+        .and_then(Value::as_f64)
+}
+
 fn extract_text_from_data(data: &Value) -> Option<String> {
     if let Some(utterance) = data.get("utterance") {
         if let Some(text) = utterance.get("text").and_then(|v| v.as_str()) {
@@ -481,21 +586,28 @@ fn is_error_payload(payload: &str) -> bool {
     false
 }
 
-async fn create_live_session(
-    api_key: &str,
+fn build_live_session_request(
     model: &str,
     sample_rate: u32,
-    language: Option<&str>,
-) -> Result<String, String> {
-    let client = Client::new();
-    let language_config = language
-        .filter(|lang| !lang.is_empty())
-        .map(|lang| LanguageConfig {
-            languages: vec![lang.to_string()],
-            code_switching: false,
-        });
+    language: &LanguageSetting,
+    vocabulary: &[String],
+    translation_targets: &[String],
+) -> LiveSessionRequest {
+    // Gladia detects among `languages` (all languages when empty); with
+    // `code_switching` it re-detects per utterance instead of once per session.
+    let language_config = match language {
+        LanguageSetting::VendorDefault => None,
+        _ => Some(LanguageConfig {
+            languages: language
+                .candidate_languages()
+                .iter()
+                .map(|language| primary_subtag(language))
+                .collect(),
+            code_switching: matches!(language, LanguageSetting::CodeSwitching(_)),
+        }),
+    };
 
-    let request_body = LiveSessionRequest {
+    LiveSessionRequest {
         encoding: SESSION_AUDIO_ENCODING,
         bit_depth: SESSION_BIT_DEPTH,
         sample_rate,
@@ -508,13 +620,46 @@ async fn create_live_session(
             receive_partial_transcripts: RECEIVE_PARTIAL_TRANSCRIPTS,
             receive_final_transcripts: RECEIVE_FINAL_TRANSCRIPTS,
         },
-    };
+        realtime_processing: (!vocabulary.is_empty() || !translation_targets.is_empty()).then(
+            || RealtimeProcessingConfig {
+                custom_vocabulary: !vocabulary.is_empty(),
+                custom_vocabulary_config: (!vocabulary.is_empty()).then(|| {
+                    CustomVocabularyConfig {
+                        vocabulary: vocabulary.to_vec(),
+                    }
+                }),
+                translation: !translation_targets.is_empty(),
+                translation_config: (!translation_targets.is_empty()).then(|| TranslationConfig {
+                    target_languages: gladia_translation_targets(translation_targets),
+                    model: TRANSLATION_MODEL,
+                }),
+            },
+        ),
+    }
+}
 
+fn gladia_translation_targets(targets: &[String]) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for code in targets.iter().map(|target| primary_subtag(target)) {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+async fn create_live_session(
+    api_url: &str,
+    proxy: Option<&str>,
+    api_key: &str,
+    request_body: &LiveSessionRequest,
+) -> Result<String, String> {
+    let client = http_client(proxy)?;
     let response = client
-        .post("https://api.gladia.io/v2/live")
+        .post(api_url)
         .header("x-gladia-key", api_key)
         .header("Content-Type", "application/json")
-        .json(&request_body)
+        .json(request_body)
         .send()
         .await
         .map_err(|e| format!("Failed to create Gladia live session: {e}"))?;
@@ -549,6 +694,8 @@ struct LiveSessionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     language_config: Option<LanguageConfig>,
     messages_config: MessagesConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    realtime_processing: Option<RealtimeProcessingConfig>,
     maximum_duration_without_endpointing: u32,
     endpointing: f32,
 }
@@ -559,6 +706,27 @@ struct LanguageConfig {
     code_switching: bool,
 }
 
+#[derive(Serialize)]
+struct RealtimeProcessingConfig {
+    custom_vocabulary: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_vocabulary_config: Option<CustomVocabularyConfig>,
+    translation: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    translation_config: Option<TranslationConfig>,
+}
+
+#[derive(Serialize)]
+struct TranslationConfig {
+    target_languages: Vec<String>,
+    model: &'static str,
+}
+
+#[derive(Serialize)]
+struct CustomVocabularyConfig {
+    vocabulary: Vec<String>,
+}
+
 #[derive(Serialize)]
 struct MessagesConfig {
     receive_partial_transcripts: bool,
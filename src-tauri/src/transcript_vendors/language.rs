@@ -0,0 +1,230 @@
+//! Unified language setting shared by all vendors: a fixed language, a list
+//! to auto-detect from, or code-switching between several languages within
+//! one utterance. Each vendor maps it onto its own options.
+
+use crate::provider_config::{TranscriptRuntimeConfig, resolve_optional_string};
+use crate::transcript_vendors::{PcmCallback, TranscriptEvent};
+use std::sync::{Arc, Mutex};
+
+/// Chinese-English mixed speech is the common case for our users.
+const DEFAULT_CODE_SWITCHING_LANGUAGES: [&str; 2] = ["zh", "en"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageSetting {
+    /// Nothing configured; the vendor's own default applies.
+    VendorDefault,
+    Fixed(String),
+    /// Detect one language per session or utterance; empty means any.
+    AutoDetect(Vec<String>),
+    CodeSwitching(Vec<String>),
+}
+
+impl LanguageSetting {
+    /// `ASR_LANGUAGE_MODE` selects the unified setting; when it is unset the
+    /// vendor-specific language field keeps working as a fixed language.
+    pub fn resolve(
+        transcript_config: &TranscriptRuntimeConfig,
+        vendor_language: Option<String>,
+    ) -> Self {
+        let mode = resolve_optional_string(
+            transcript_config.asr_language_mode.as_deref(),
+            &["ASR_LANGUAGE_MODE"],
+        );
+        let languages = resolve_optional_string(
+            transcript_config.asr_languages.as_deref(),
+            &["ASR_LANGUAGES"],
+        )
+        .map(|raw| parse_language_list(&raw))
+        .unwrap_or_default();
+
+        let fixed_or_default = |language: Option<String>| {
+            language
+                .map(LanguageSetting::Fixed)
+                .unwrap_or(LanguageSetting::VendorDefault)
+        };
+
+        match mode.map(|mode| mode.to_ascii_lowercase().replace('-', "_")) {
+            None => fixed_or_default(vendor_language),
+            Some(mode) => match mode.as_str() {
+                "fixed" => fixed_or_default(languages.into_iter().next().or(vendor_language)),
+                "auto" | "auto_detect" => LanguageSetting::AutoDetect(languages),
+                "code_switching" | "multi" => {
+                    LanguageSetting::CodeSwitching(if languages.is_empty() {
+                        DEFAULT_CODE_SWITCHING_LANGUAGES
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect()
+                    } else {
+                        languages
+                    })
+                }
+                other => {
+                    eprintln!("Unknown ASR_LANGUAGE_MODE {other:?}, using vendor language");
+                    fixed_or_default(vendor_language)
+                }
+            },
+        }
+    }
+
+    pub fn fixed_language(&self) -> Option<&str> {
+        match self {
+            LanguageSetting::Fixed(language) => Some(language.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn candidate_languages(&self) -> &[String] {
+        match self {
+            LanguageSetting::VendorDefault => &[],
+            LanguageSetting::Fixed(language) => std::slice::from_ref(language),
+            LanguageSetting::AutoDetect(languages) | LanguageSetting::CodeSwitching(languages) => {
+                languages
+            }
+        }
+    }
+
+    pub fn is_multilingual(&self) -> bool {
+        matches!(
+            self,
+            LanguageSetting::AutoDetect(_) | LanguageSetting::CodeSwitching(_)
+        )
+    }
+
+    /// Whether the candidates cover exactly this pair, e.g. a bilingual pack.
+    pub fn covers_pair(&self, first: &str, second: &str) -> bool {
+        let subtags = self
+            .candidate_languages()
+            .iter()
+            .map(|language| primary_subtag(language))
+            .collect::<Vec<_>>();
+        subtags.len() == 2
+            && subtags.iter().any(|subtag| subtag == first)
+            && subtags.iter().any(|subtag| subtag == second)
+    }
+}
+
+pub fn parse_language_list(raw: &str) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for language in raw
+        .split([',', ';', ' ', '\n'])
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+    {
+        if !languages
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(language))
+        {
+            languages.push(language.to_string());
+        }
+    }
+    languages
+}
+
+/// Lower-case ISO 639-1 style primary subtag: `zh_CN` and `cmn` become `zh`.
+pub fn primary_subtag(language: &str) -> String {
+    let subtag = language
+        .trim()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match subtag.as_str() {
+        "cmn" => "zh".to_string(),
+        _ => subtag,
+    }
+}
+
+/// Latest language reported by the vendor, stamped onto transcript events.
+#[derive(Clone, Default)]
+pub struct DetectedLanguage(Arc<Mutex<Option<String>>>);
+
+impl DetectedLanguage {
+    pub fn update(&self, language: Option<&str>) {
+        let Some(language) = language.map(str::trim).filter(|value| !value.is_empty()) else {
+            return;
+        };
+        *self.0.lock().unwrap() = Some(language.to_string());
+    }
+
+    pub fn current(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn wrap_callback(&self, callback: PcmCallback) -> PcmCallback {
+        let detected = self.clone();
+        Arc::new(move |mut event: TranscriptEvent| {
+            if event.language.is_none() {
+                event.language = detected.current();
+            }
+            callback(event);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DetectedLanguage, LanguageSetting, parse_language_list, primary_subtag};
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::{PcmCallback, TranscriptEvent, emit_commit};
+    use std::sync::{Arc, Mutex};
+
+    fn config(mode: Option<&str>, languages: Option<&str>) -> TranscriptRuntimeConfig {
+        TranscriptRuntimeConfig {
+            asr_language_mode: mode.map(ToString::to_string),
+            asr_languages: languages.map(ToString::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolve_prefers_unified_mode_over_vendor_language() {
+        assert_eq!(
+            LanguageSetting::resolve(&config(None, None), Some("en".to_string())),
+            LanguageSetting::Fixed("en".to_string())
+        );
+        assert_eq!(
+            LanguageSetting::resolve(&config(Some("fixed"), Some("ja")), Some("en".to_string())),
+            LanguageSetting::Fixed("ja".to_string())
+        );
+        assert_eq!(
+            LanguageSetting::resolve(&config(Some("auto"), Some("en, zh")), None),
+            LanguageSetting::AutoDetect(vec!["en".to_string(), "zh".to_string()])
+        );
+        assert_eq!(
+            LanguageSetting::resolve(&config(Some("code-switching"), None), None),
+            LanguageSetting::CodeSwitching(vec!["zh".to_string(), "en".to_string()])
+        );
+    }
+
+    #[test]
+    fn language_helpers_normalize_codes() {
+        assert_eq!(
+            parse_language_list("zh-CN, en;EN ja"),
+            vec!["zh-CN", "en", "ja"]
+        );
+        assert_eq!(primary_subtag("zh_CN"), "zh");
+        assert_eq!(primary_subtag("cmn"), "zh");
+        assert!(
+            LanguageSetting::CodeSwitching(vec!["cmn".to_string(), "en-US".to_string()])
+                .covers_pair("zh", "en")
+        );
+    }
+
+    #[test]
+    fn detected_language_is_stamped_on_events() {
+        let received = Arc::new(Mutex::new(Vec::<TranscriptEvent>::new()));
+        let sink = received.clone();
+        let callback: PcmCallback = Arc::new(move |event| sink.lock().unwrap().push(event));
+        let detected = DetectedLanguage::default();
+        let callback = detected.wrap_callback(callback);
+
+        emit_commit(&callback, "Test", "before detection");
+        detected.update(Some("en"));
+        detected.update(Some(" "));
+        emit_commit(&callback, "Test", "after detection");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received[0].language, None);
+        assert_eq!(received[1].language.as_deref(), Some("en"));
+    }
+}
@@ -0,0 +1,992 @@
+#![allow(clippy::collapsible_if)]
+
+//! In-process mock servers that speak the streaming protocols of the
+//! websocket vendors, so sessions can be exercised without live API keys.
+//! Each server listens on a random local port; `transcript_config` returns a
+//! runtime config that points the matching vendor at it.
+
+use crate::provider_config::TranscriptRuntimeConfig;
+use crate::transcript_vendors::TranscriptVendors;
+use crate::transcript_vendors::volcengine::codec::{
+    ClientFrame, decode_client_frame, encode_server_response,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Message, frame::coding::CloseCode};
+use tokio_tungstenite::{WebSocketStream, accept_hdr_async};
+
+const MOCK_API_KEY: &str = "mock-key";
+const GLADIA_SESSION_PATH: &str = "/v2/live";
+const CLOSE_HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// What the mock says once audio arrives, and how it misbehaves.
+#[derive(Debug, Clone)]
+pub struct MockScript {
+    /// Each utterance is streamed word by word as partials, then finalized.
+    pub utterances: Vec<String>,
+    /// The first N websocket connections are closed as soon as audio arrives.
+    pub fail_first_connections: usize,
+    pub failure_close_code: u16,
+    /// Close with this code after the utterances instead of waiting for the client.
+    pub close_code_after_utterances: Option<u16>,
+}
+
+impl Default for MockScript {
+    fn default() -> Self {
+        Self {
+            utterances: vec!["hello world".to_string()],
+            fail_first_connections: 0,
+            failure_close_code: 1011,
+            close_code_after_utterances: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MockRecord {
+    pub connections: usize,
+    pub session_inits: usize,
+    pub request_uris: Vec<String>,
+    pub text_messages: Vec<String>,
+    pub audio_bytes: usize,
+}
+
+pub struct MockAsrServer {
+    vendor: TranscriptVendors,
+    addr: SocketAddr,
+    record: Arc<Mutex<MockRecord>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockAsrServer {
+    pub fn start(vendor: TranscriptVendors, script: MockScript) -> Result<Self, String> {
+        if !matches!(
+            vendor,
+            TranscriptVendors::AssemblyAI
+                | TranscriptVendors::DeepGram
+                | TranscriptVendors::SpeechMatics
+                | TranscriptVendors::RevAI
+                | TranscriptVendors::GlaDia
+                | TranscriptVendors::OpenAiRealtime
+                | TranscriptVendors::DashScope
+                | TranscriptVendors::Volcengine
+        ) {
+            return Err(format!("No mock server for {}", vendor.as_str()));
+        }
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| format!("Failed to bind mock ASR server: {e}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to configure mock ASR server: {e}"))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read mock ASR server address: {e}"))?;
+
+        let record = Arc::new(Mutex::new(MockRecord::default()));
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let record_for_thread = record.clone();
+        let handle = thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to build Tokio runtime");
+            runtime.block_on(async move {
+                let listener = match TcpListener::from_std(listener) {
+                    Ok(listener) => listener,
+                    Err(err) => {
+                        eprintln!("Mock ASR server failed to start: {err}");
+                        return;
+                    }
+                };
+                serve(listener, vendor, script, record_for_thread, shutdown_rx).await;
+            });
+        });
+
+        Ok(Self {
+            vendor,
+            addr,
+            record,
+            shutdown: Some(shutdown),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn ws_url(&self) -> String {
+        let path = match self.vendor {
+            TranscriptVendors::AssemblyAI => "/v3/ws",
+            TranscriptVendors::DeepGram => "/v1/listen",
+            TranscriptVendors::SpeechMatics => "/v2/",
+            TranscriptVendors::RevAI => "/speechtotext/v1/stream",
+            TranscriptVendors::OpenAiRealtime => "/v1/realtime",
+            TranscriptVendors::DashScope => "/api-ws/v1/inference",
+            TranscriptVendors::Volcengine => "/api/v3/sauc/bigmodel",
+            _ => "/v2/live/mock-session",
+        };
+        format!("ws://{}{path}", self.addr)
+    }
+
+    pub fn http_url(&self) -> String {
+        format!("http://{}{GLADIA_SESSION_PATH}", self.addr)
+    }
+
+    /// A config with a dummy key whose endpoint override targets this server.
+    pub fn transcript_config(&self) -> TranscriptRuntimeConfig {
+        let key = Some(MOCK_API_KEY.to_string());
+        let mut config = TranscriptRuntimeConfig::default();
+        match self.vendor {
+            TranscriptVendors::AssemblyAI => {
+                config.assembly_api_key = key;
+                config.assembly_ws_url = Some(self.ws_url());
+            }
+            TranscriptVendors::DeepGram => {
+                config.deepgram_api_key = key;
+                config.deepgram_ws_url = Some(self.ws_url());
+                // Only the raw API client honours a custom websocket URL.
+                config.deepgram_backend = Some("api".to_string());
+            }
+            TranscriptVendors::SpeechMatics => {
+                config.speechmatics_api_key = key;
+                config.speechmatics_rt_url = Some(self.ws_url());
+            }
+            TranscriptVendors::RevAI => {
+                config.revai_api_key = key;
+                config.revai_ws_url = Some(self.ws_url());
+            }
+            TranscriptVendors::GlaDia => {
+                config.gladia_api_key = key;
+                config.gladia_api_url = Some(self.http_url());
+            }
+            TranscriptVendors::OpenAiRealtime => {
+                config.openai_realtime_api_key = key;
+                // The client derives `/realtime` from the REST base.
+                config.openai_realtime_base_url = Some(format!("http://{}/v1", self.addr));
+            }
+            TranscriptVendors::DashScope => {
+                config.dashscope_api_key = key;
+                config.dashscope_ws_url = Some(self.ws_url());
+            }
+            TranscriptVendors::Volcengine => {
+                config.volcengine_app_key = key.clone();
+                config.volcengine_access_key = key;
+                config.volcengine_ws_url = Some(self.ws_url());
+            }
+            _ => {}
+        }
+        config
+    }
+
+    pub fn record(&self) -> MockRecord {
+        self.record.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockAsrServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+async fn serve(
+    listener: TcpListener,
+    vendor: TranscriptVendors,
+    script: MockScript,
+    record: Arc<Mutex<MockRecord>>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let script = script.clone();
+                    let record = record.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle_connection(stream, vendor, script, record).await {
+                            eprintln!("Mock ASR connection error: {err}");
+                        }
+                    });
+                }
+                Err(err) => {
+                    eprintln!("Mock ASR accept error: {err}");
+                    break;
+                }
+            },
+        }
+    }
+}
+
+// The handshake callback signature is fixed by tungstenite.
+#[allow(clippy::result_large_err)]
+async fn handle_connection(
+    stream: TcpStream,
+    vendor: TranscriptVendors,
+    script: MockScript,
+    record: Arc<Mutex<MockRecord>>,
+) -> Result<(), String> {
+    if vendor == TranscriptVendors::GlaDia && is_http_post(&stream).await? {
+        return answer_gladia_session_init(stream, record).await;
+    }
+
+    let uri_record = record.clone();
+    let ws = accept_hdr_async(stream, move |request: &Request, response: Response| {
+        uri_record
+            .lock()
+            .unwrap()
+            .request_uris
+            .push(request.uri().to_string());
+        Ok(response)
+    })
+    .await
+    .map_err(|e| format!("Mock websocket handshake failed: {e}"))?;
+
+    let connection_index = {
+        let mut record = record.lock().unwrap();
+        record.connections += 1;
+        record.connections - 1
+    };
+    run_websocket(
+        ws,
+        vendor,
+        &script,
+        connection_index < script.fail_first_connections,
+        record,
+    )
+    .await
+}
+
+async fn run_websocket(
+    mut ws: WebSocketStream<TcpStream>,
+    vendor: TranscriptVendors,
+    script: &MockScript,
+    failing: bool,
+    record: Arc<Mutex<MockRecord>>,
+) -> Result<(), String> {
+    for greeting in greeting_messages(vendor) {
+        send_payload(&mut ws, vendor, greeting).await?;
+    }
+
+    let mut spoken = false;
+    while let Some(message) = ws.next().await {
+        let message = message.map_err(|e| format!("Mock websocket receive error: {e}"))?;
+        match classify(vendor, message)? {
+            Incoming::Audio { bytes, is_last } => {
+                record.lock().unwrap().audio_bytes += bytes;
+                if is_last {
+                    // Volcengine flushes on the negative sequence and marks its answer as last.
+                    let frame = encode_server_response(&json!({ "result": {} }), -1, true)?;
+                    send_message(&mut ws, Message::Binary(frame.into())).await?;
+                    return close_with(&mut ws, 1000, "").await;
+                }
+                if spoken {
+                    continue;
+                }
+                spoken = true;
+
+                if failing {
+                    return close_with(&mut ws, script.failure_close_code, "mock failure").await;
+                }
+                for (index, utterance) in script.utterances.iter().enumerate() {
+                    for payload in utterance_messages(vendor, index, utterance) {
+                        send_payload(&mut ws, vendor, payload).await?;
+                    }
+                }
+                if let Some(code) = script.close_code_after_utterances {
+                    return close_with(&mut ws, code, "mock close").await;
+                }
+            }
+            Incoming::Control(text) => {
+                record.lock().unwrap().text_messages.push(text.clone());
+                match reply_to_text(vendor, text.as_str()) {
+                    TextReply::Ignore => {}
+                    TextReply::Send(payloads) => {
+                        for payload in payloads {
+                            send_payload(&mut ws, vendor, payload).await?;
+                        }
+                    }
+                    TextReply::SendAndClose(payloads) => {
+                        for payload in payloads {
+                            send_payload(&mut ws, vendor, payload).await?;
+                        }
+                        return close_with(&mut ws, 1000, "").await;
+                    }
+                }
+            }
+            // Keep polling so tungstenite flushes its close reply.
+            Incoming::Other => {}
+        }
+    }
+
+    Ok(())
+}
+
+enum Incoming {
+    Audio { bytes: usize, is_last: bool },
+    Control(String),
+    Other,
+}
+
+/// Separates audio from control messages: OpenAI realtime wraps audio in JSON
+/// and Volcengine frames both kinds in binary.
+fn classify(vendor: TranscriptVendors, message: Message) -> Result<Incoming, String> {
+    let incoming = match message {
+        Message::Binary(bytes) if vendor == TranscriptVendors::Volcengine => {
+            match decode_client_frame(&bytes)? {
+                ClientFrame::FullRequest(payload) => Incoming::Control(payload.to_string()),
+                ClientFrame::Audio { bytes, is_last } => Incoming::Audio { bytes, is_last },
+            }
+        }
+        Message::Binary(bytes) => Incoming::Audio {
+            bytes: bytes.len(),
+            is_last: false,
+        },
+        Message::Text(text) if vendor == TranscriptVendors::OpenAiRealtime => {
+            let value = serde_json::from_str::<Value>(text.as_str()).unwrap_or_default();
+            if value["type"] == "input_audio_buffer.append" {
+                let audio = STANDARD
+                    .decode(value["audio"].as_str().unwrap_or_default())
+                    .map_err(|e| format!("Mock received invalid base64 audio: {e}"))?;
+                Incoming::Audio {
+                    bytes: audio.len(),
+                    is_last: false,
+                }
+            } else {
+                Incoming::Control(text.to_string())
+            }
+        }
+        Message::Text(text) => Incoming::Control(text.to_string()),
+        _ => Incoming::Other,
+    };
+    Ok(incoming)
+}
+
+enum TextReply {
+    Ignore,
+    Send(Vec<Value>),
+    SendAndClose(Vec<Value>),
+}
+
+fn greeting_messages(vendor: TranscriptVendors) -> Vec<Value> {
+    match vendor {
+        TranscriptVendors::AssemblyAI => {
+            vec![json!({ "type": "Begin", "id": "mock-session", "expires_at": 0 })]
+        }
+        TranscriptVendors::RevAI => vec![json!({ "type": "connected", "id": "mock-session" })],
+        _ => Vec::new(),
+    }
+}
+
+fn reply_to_text(vendor: TranscriptVendors, text: &str) -> TextReply {
+    if vendor == TranscriptVendors::RevAI {
+        return if text == "EOS" {
+            TextReply::SendAndClose(Vec::new())
+        } else {
+            TextReply::Ignore
+        };
+    }
+
+    let Ok(value) = serde_json::from_str::<Value>(text) else {
+        return TextReply::Ignore;
+    };
+    let message_type = value
+        .get("type")
+        .or_else(|| value.get("message"))
+        .or_else(|| value.get("header").and_then(|header| header.get("action")))
+        .and_then(|entry| entry.as_str())
+        .unwrap_or_default();
+
+    match (vendor, message_type) {
+        (TranscriptVendors::AssemblyAI, "Terminate") => {
+            TextReply::SendAndClose(vec![json!({ "type": "Termination" })])
+        }
+        (TranscriptVendors::DeepGram, "CloseStream") => TextReply::SendAndClose(vec![
+            json!({ "type": "Metadata", "request_id": "mock-session" }),
+        ]),
+        (TranscriptVendors::SpeechMatics, "StartRecognition") => TextReply::Send(vec![
+            json!({ "message": "RecognitionStarted", "id": "mock-session" }),
+        ]),
+        (TranscriptVendors::SpeechMatics, "EndOfStream") => {
+            TextReply::SendAndClose(vec![json!({ "message": "EndOfTranscript" })])
+        }
+        (TranscriptVendors::OpenAiRealtime, "transcription_session.update") => {
+            TextReply::Send(vec![json!({ "type": "transcription_session.updated" })])
+        }
+        (TranscriptVendors::DashScope, "run-task") => {
+            TextReply::Send(vec![dashscope_event("task-started", json!({}))])
+        }
+        (TranscriptVendors::DashScope, "finish-task") => {
+            TextReply::SendAndClose(vec![dashscope_event("task-finished", json!({}))])
+        }
+        _ => TextReply::Ignore,
+    }
+}
+
+/// Partials grow one word at a time, then the vendor's own way of finishing an utterance.
+fn utterance_messages(vendor: TranscriptVendors, index: usize, utterance: &str) -> Vec<Value> {
+    let words = utterance.split_whitespace().collect::<Vec<_>>();
+    let partials = (1..words.len())
+        .map(|count| words[..count].join(" "))
+        .collect::<Vec<_>>();
+    let full = words.join(" ");
+    let mut messages = Vec::new();
+
+    match vendor {
+        TranscriptVendors::AssemblyAI => {
+            for partial in partials {
+                messages.push(json!({
+                    "type": "Turn",
+                    "transcript": partial,
+                    "end_of_turn": false,
+                    "turn_is_formatted": false
+                }));
+            }
+            messages.push(json!({
+                "type": "Turn",
+                "transcript": full,
+                "end_of_turn": true,
+                "turn_is_formatted": true
+            }));
+        }
+        TranscriptVendors::DeepGram => {
+            for partial in partials {
+                messages.push(deepgram_results(&partial, false));
+            }
+            messages.push(deepgram_results(&full, true));
+            messages.push(json!({ "type": "UtteranceEnd", "last_word_end": 1.0 }));
+        }
+        TranscriptVendors::SpeechMatics => {
+            for partial in partials {
+                messages.push(json!({
+                    "message": "AddPartialTranscript",
+                    "metadata": { "transcript": partial }
+                }));
+            }
+            messages.push(json!({
+                "message": "AddTranscript",
+                "metadata": { "transcript": full }
+            }));
+            messages.push(json!({ "message": "EndOfUtterance", "metadata": {} }));
+        }
+        TranscriptVendors::RevAI => {
+            for partial in partials {
+                messages.push(json!({
+                    "type": "partial",
+                    "ts": 0.0,
+                    "end_ts": 1.0,
+                    "elements": revai_elements(&partial, false)
+                }));
+            }
+            messages.push(json!({
+                "type": "final",
+                "ts": 0.0,
+                "end_ts": 1.0,
+                "elements": revai_elements(&full, true)
+            }));
+        }
+        TranscriptVendors::GlaDia => {
+            for partial in partials {
+                messages.push(gladia_transcript(&partial, false));
+            }
+            messages.push(gladia_transcript(&full, true));
+        }
+        TranscriptVendors::OpenAiRealtime => {
+            let item_id = format!("item_{index}");
+            for (position, word) in words.iter().enumerate() {
+                let delta = if position == 0 {
+                    word.to_string()
+                } else {
+                    format!(" {word}")
+                };
+                messages.push(json!({
+                    "type": "conversation.item.input_audio_transcription.delta",
+                    "item_id": item_id,
+                    "delta": delta
+                }));
+            }
+            messages.push(json!({
+                "type": "conversation.item.input_audio_transcription.completed",
+                "item_id": item_id,
+                "transcript": full
+            }));
+        }
+        TranscriptVendors::DashScope => {
+            for partial in partials {
+                messages.push(dashscope_sentence(&partial, false));
+            }
+            messages.push(dashscope_sentence(&full, true));
+        }
+        TranscriptVendors::Volcengine => {
+            for partial in partials {
+                messages.push(volcengine_result(&partial, false, 0));
+            }
+            // Definite utterances are deduplicated by their end time.
+            messages.push(volcengine_result(&full, true, (index as i64 + 1) * 1_000));
+        }
+        _ => {}
+    }
+
+    messages
+}
+
+fn deepgram_results(transcript: &str, is_final: bool) -> Value {
+    json!({
+        "type": "Results",
+        "is_final": is_final,
+        "speech_final": false,
+        "channel": { "alternatives": [{ "transcript": transcript, "confidence": 0.99 }] }
+    })
+}
+
+fn revai_elements(text: &str, is_final: bool) -> Vec<Value> {
+    let mut elements = Vec::new();
+    for (index, word) in text.split_whitespace().enumerate() {
+        if index > 0 {
+            elements.push(json!({ "type": "punct", "value": " " }));
+        }
+        if is_final {
+            elements.push(json!({ "type": "text", "value": word, "confidence": 0.99 }));
+        } else {
+            elements.push(json!({ "type": "text", "value": word }));
+        }
+    }
+    elements
+}
+
+fn gladia_transcript(text: &str, is_final: bool) -> Value {
+    json!({
+        "type": "transcript",
+        "session_id": "mock-session",
+        "data": {
+            "id": "mock-utterance",
+            "is_final": is_final,
+            "utterance": { "text": text, "language": "en" }
+        }
+    })
+}
+
+fn dashscope_event(event: &str, payload: Value) -> Value {
+    json!({
+        "header": { "task_id": "mock-task", "event": event },
+        "payload": payload
+    })
+}
+
+fn dashscope_sentence(text: &str, sentence_end: bool) -> Value {
+    dashscope_event(
+        "result-generated",
+        json!({ "output": { "sentence": { "text": text, "sentence_end": sentence_end } } }),
+    )
+}
+
+fn volcengine_result(text: &str, definite: bool, end_time: i64) -> Value {
+    json!({
+        "result": {
+            "text": text,
+            "utterances": [{ "text": text, "definite": definite, "end_time": end_time }]
+        }
+    })
+}
+
+/// JSON text for most vendors, a binary server frame for Volcengine.
+async fn send_payload(
+    ws: &mut WebSocketStream<TcpStream>,
+    vendor: TranscriptVendors,
+    payload: Value,
+) -> Result<(), String> {
+    let message = if vendor == TranscriptVendors::Volcengine {
+        Message::Binary(encode_server_response(&payload, 1, false)?.into())
+    } else {
+        Message::Text(payload.to_string().into())
+    };
+    send_message(ws, message).await
+}
+
+async fn send_message(ws: &mut WebSocketStream<TcpStream>, message: Message) -> Result<(), String> {
+    ws.send(message)
+        .await
+        .map_err(|e| format!("Mock websocket send error: {e}"))
+}
+
+async fn close_with(
+    ws: &mut WebSocketStream<TcpStream>,
+    code: u16,
+    reason: &str,
+) -> Result<(), String> {
+    ws.close(Some(CloseFrame {
+        code: CloseCode::from(code),
+        reason: reason.to_string().into(),
+    }))
+    .await
+    .map_err(|e| format!("Mock websocket close error: {e}"))?;
+
+    // Like a real server, wait for the client to finish the closing handshake.
+    let drain = async { while let Some(Ok(_)) = ws.next().await {} };
+    let _ = tokio::time::timeout(CLOSE_HANDSHAKE_TIMEOUT, drain).await;
+    Ok(())
+}
+
+async fn is_http_post(stream: &TcpStream) -> Result<bool, String> {
+    let mut prefix = [0_u8; 4];
+    loop {
+        let read = stream
+            .peek(&mut prefix)
+            .await
+            .map_err(|e| format!("Mock server peek failed: {e}"))?;
+        if read == 0 {
+            return Ok(false);
+        }
+        if read == prefix.len() {
+            return Ok(&prefix == b"POST");
+        }
+    }
+}
+
+/// Answers Gladia's session-init REST call with a websocket URL on this server.
+async fn answer_gladia_session_init(
+    mut stream: TcpStream,
+    record: Arc<Mutex<MockRecord>>,
+) -> Result<(), String> {
+    let mut request = Vec::new();
+    let mut buffer = [0_u8; 4096];
+    let body_start = loop {
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Mock session init read failed: {e}"))?;
+        if read == 0 {
+            return Err("Mock session init closed before headers".into());
+        }
+        request.extend_from_slice(&buffer[..read]);
+        if let Some(index) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index + 4;
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&request[..body_start]).to_ascii_lowercase();
+    let content_length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while request.len() < body_start + content_length {
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Mock session init read failed: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let local_addr = stream
+        .local_addr()
+        .map_err(|e| format!("Mock session init address failed: {e}"))?;
+    {
+        let mut record = record.lock().unwrap();
+        record.session_inits += 1;
+        record
+            .text_messages
+            .push(String::from_utf8_lossy(&request[body_start..]).into_owned());
+    }
+
+    let body = json!({
+        "id": "mock-session",
+        "created_at": "2026-01-01T00:00:00.000Z",
+        "url": format!("ws://{local_addr}{GLADIA_SESSION_PATH}/mock-session"),
+    })
+    .to_string();
+    let response = format!(
+        "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| format!("Mock session init write failed: {e}"))?;
+    stream
+        .shutdown()
+        .await
+        .map_err(|e| format!("Mock session init shutdown failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MockAsrServer, MockScript};
+    use crate::transcript_vendors::{
+        PcmCallback, StatusCallback, StreamingTranscriber, TranscriptEvent, TranscriptEventKind,
+        TranscriptVendors, start_transcriber,
+    };
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    const SAMPLE_RATE: u32 = 16_000;
+    const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    struct Session {
+        transcriber: Arc<dyn StreamingTranscriber>,
+        events: Arc<Mutex<Vec<TranscriptEvent>>>,
+        statuses: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Session {
+        fn start(vendor: TranscriptVendors, server: &MockAsrServer) -> Self {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let statuses = Arc::new(Mutex::new(Vec::new()));
+            let callback = {
+                let events = events.clone();
+                Arc::new(move |event: TranscriptEvent| events.lock().unwrap().push(event))
+                    as PcmCallback
+            };
+            let status_callback = {
+                let statuses = statuses.clone();
+                Arc::new(move |message: String| statuses.lock().unwrap().push(message))
+                    as StatusCallback
+            };
+            let transcriber = start_transcriber(
+                vendor,
+                SAMPLE_RATE,
+                callback,
+                Some(status_callback),
+                server.transcript_config(),
+            )
+            .unwrap();
+
+            Self {
+                transcriber,
+                events,
+                statuses,
+            }
+        }
+
+        fn speak(&self) {
+            for _ in 0..3 {
+                self.transcriber
+                    .queue_chunk(vec![1_000; SAMPLE_RATE as usize / 10])
+                    .unwrap();
+            }
+        }
+
+        fn wait_until(&self, what: &str, done: impl Fn(&[TranscriptEvent], &[String]) -> bool) {
+            let started = Instant::now();
+            while started.elapsed() < WAIT_TIMEOUT {
+                if done(&self.events.lock().unwrap(), &self.statuses.lock().unwrap()) {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+            panic!(
+                "timed out waiting for {what}: events={:?} statuses={:?}",
+                self.events.lock().unwrap(),
+                self.statuses.lock().unwrap()
+            );
+        }
+
+        fn wait_for_commit(&self, text: &str) {
+            self.wait_until("commit", |events, _| {
+                events
+                    .iter()
+                    .any(|event| event.kind == TranscriptEventKind::Commit && event.text == text)
+            });
+        }
+    }
+
+    /// Drafts must only grow towards the commit, and the commit comes last.
+    fn assert_drafts_then_commit(events: &[TranscriptEvent], text: &str) {
+        let (last, drafts) = events.split_last().expect("no transcript events");
+        assert_eq!(last.kind, TranscriptEventKind::Commit, "{events:?}");
+        assert_eq!(last.text, text);
+        assert!(!drafts.is_empty(), "expected drafts before the commit");
+        for draft in drafts {
+            assert_eq!(draft.kind, TranscriptEventKind::Draft, "{events:?}");
+            assert!(text.starts_with(draft.text.as_str()), "{events:?}");
+        }
+    }
+
+    fn run_happy_path(vendor: TranscriptVendors) -> MockAsrServer {
+        let server = MockAsrServer::start(vendor, MockScript::default()).unwrap();
+        let session = Session::start(vendor, &server);
+        session.speak();
+        session.wait_for_commit("hello world");
+        session.transcriber.shutdown();
+
+        assert_drafts_then_commit(&session.events.lock().unwrap(), "hello world");
+        assert!(
+            session.statuses.lock().unwrap().is_empty(),
+            "{:?}",
+            session.statuses.lock().unwrap()
+        );
+        assert!(server.record().audio_bytes > 0);
+        server
+    }
+
+    #[test]
+    fn assemblyai_turns_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::AssemblyAI);
+        let record = server.record();
+        assert!(record.request_uris[0].starts_with("/v3/ws?sample_rate=16000"));
+        assert!(
+            record
+                .text_messages
+                .iter()
+                .any(|message| message.contains("Terminate"))
+        );
+    }
+
+    #[test]
+    fn deepgram_results_and_utterance_end_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::DeepGram);
+        let record = server.record();
+        assert!(record.request_uris[0].starts_with("/v1/listen?model="));
+        assert!(
+            record
+                .text_messages
+                .iter()
+                .any(|message| message.contains("CloseStream"))
+        );
+    }
+
+    #[test]
+    fn speechmatics_partials_and_end_of_utterance_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::SpeechMatics);
+        let record = server.record();
+        assert!(record.text_messages[0].contains("StartRecognition"));
+        assert!(
+            record
+                .text_messages
+                .iter()
+                .any(|message| message.contains("EndOfStream"))
+        );
+    }
+
+    #[test]
+    fn revai_partial_and_final_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::RevAI);
+        assert!(server.record().request_uris[0].contains("access_token=mock-key"));
+    }
+
+    #[test]
+    fn gladia_initializes_session_over_rest_then_streams() {
+        let server = run_happy_path(TranscriptVendors::GlaDia);
+        let record = server.record();
+        assert_eq!(record.session_inits, 1);
+        assert!(record.text_messages[0].contains("\"sample_rate\":16000"));
+    }
+
+    #[test]
+    fn openai_realtime_deltas_and_completion_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::OpenAiRealtime);
+        let record = server.record();
+        assert_eq!(record.request_uris[0], "/v1/realtime?intent=transcription");
+        assert!(record.text_messages[0].contains("transcription_session.update"));
+        assert!(
+            record
+                .text_messages
+                .iter()
+                .all(|message| !message.contains("input_audio_buffer.append"))
+        );
+    }
+
+    #[test]
+    fn dashscope_sentences_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::DashScope);
+        let record = server.record();
+        assert!(record.text_messages[0].contains("run-task"));
+        assert!(
+            record
+                .text_messages
+                .iter()
+                .any(|message| message.contains("finish-task"))
+        );
+    }
+
+    #[test]
+    fn volcengine_binary_frames_become_drafts_then_commit() {
+        let server = run_happy_path(TranscriptVendors::Volcengine);
+        let record = server.record();
+        assert_eq!(record.request_uris[0], "/api/v3/sauc/bigmodel");
+        assert!(record.text_messages[0].contains("show_utterances"));
+    }
+
+    #[test]
+    fn revai_reconnects_after_retryable_close_code() {
+        let script = MockScript {
+            fail_first_connections: 1,
+            failure_close_code: 4013,
+            ..MockScript::default()
+        };
+        let server = MockAsrServer::start(TranscriptVendors::RevAI, script).unwrap();
+        let session = Session::start(TranscriptVendors::RevAI, &server);
+        session.speak();
+        session.wait_until("reconnect", |_, _| server.record().connections >= 2);
+        session.speak();
+        session.wait_for_commit("hello world");
+        session.transcriber.shutdown();
+
+        assert!(session.statuses.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn gladia_reconnects_to_existing_session() {
+        let script = MockScript {
+            fail_first_connections: 1,
+            ..MockScript::default()
+        };
+        let server = MockAsrServer::start(TranscriptVendors::GlaDia, script).unwrap();
+        let session = Session::start(TranscriptVendors::GlaDia, &server);
+        session.speak();
+        session.wait_until("reconnect", |_, _| server.record().connections >= 2);
+        session.speak();
+        session.wait_for_commit("hello world");
+        session.transcriber.shutdown();
+
+        let record = server.record();
+        assert_eq!(record.session_inits, 1);
+        assert!(session.statuses.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unexpected_close_code_is_reported_through_status_callback() {
+        let script = MockScript {
+            close_code_after_utterances: Some(1011),
+            ..MockScript::default()
+        };
+        let server = MockAsrServer::start(TranscriptVendors::SpeechMatics, script).unwrap();
+        let session = Session::start(TranscriptVendors::SpeechMatics, &server);
+        session.speak();
+        session.wait_until("close status", |_, statuses| !statuses.is_empty());
+        session.transcriber.shutdown();
+
+        let statuses = session.statuses.lock().unwrap();
+        assert!(statuses[0].contains("code=Error"), "{statuses:?}");
+        assert!(
+            session
+                .events
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|event| event.kind == TranscriptEventKind::Commit)
+        );
+    }
+
+    #[test]
+    fn mock_server_rejects_vendors_without_a_websocket_mock() {
+        assert!(
+            MockAsrServer::start(TranscriptVendors::OpenAiCompatible, MockScript::default())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,621 @@
+#![allow(clippy::collapsible_if)]
+
+///https://platform.openai.com/docs/api-reference/audio/createTranscription
+use crate::provider_config::{
+    OPENAI_COMPATIBLE_ASR_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string,
+    resolve_required_string, resolve_string_or_default,
+};
+use crate::proxy::{ProxySettings, http_client_builder};
+use crate::transcript_vendors::language::{LanguageSetting, primary_subtag};
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit,
+    report_audio_sent,
+};
+use futures_util::future::try_join;
+use reqwest::Client;
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, mpsc::error::TrySendError};
+use tokio::time::{self, Duration};
+
+const VENDOR_NAME: &str = "OpenAICompatible";
+const DEFAULT_MODEL: &str = "whisper-1";
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const FINAL_FLUSH_TIMEOUT_SECS: u64 = 10;
+const SPEECH_PEAK_THRESHOLD: i16 = 900;
+const SPEECH_MEAN_ABS_THRESHOLD: f32 = 120.0;
+const END_OF_UTTERANCE_SILENCE_MS: u32 = 700;
+const MIN_UTTERANCE_SPEECH_MS: u32 = 300;
+const MAX_UTTERANCE_MS: u32 = 30_000;
+const PRE_ROLL_MS: u32 = 300;
+
+enum StreamCommand {
+    Audio(Vec<i16>),
+    ForceEndpoint,
+}
+
+struct UploadSettings {
+    endpoint: String,
+    api_key: Option<String>,
+    model: String,
+    language: Option<String>,
+    proxy: Option<String>,
+}
+
+enum UploadFailure {
+    Fatal(String),
+    Skipped(String),
+}
+
+pub struct OpenAiCompatibleTranscriber {
+    sender: Mutex<Option<mpsc::Sender<StreamCommand>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl OpenAiCompatibleTranscriber {
+    pub fn start(
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        let base_url = resolve_required_string(
+            transcript_config.openai_compatible_base_url.as_deref(),
+            &["OPENAI_COMPATIBLE_ASR_BASE_URL"],
+            "OPENAI_COMPATIBLE_ASR_BASE_URL",
+        )?;
+        let api_key = resolve_optional_string(
+            transcript_config.openai_compatible_api_key.as_deref(),
+            OPENAI_COMPATIBLE_ASR_ENV_KEYS,
+        );
+        let model = resolve_string_or_default(
+            transcript_config.openai_compatible_model.as_deref(),
+            &["OPENAI_COMPATIBLE_ASR_MODEL"],
+            DEFAULT_MODEL,
+        );
+        let language = openai_language(
+            &LanguageSetting::resolve(
+                &transcript_config,
+                resolve_optional_string(
+                    transcript_config.openai_compatible_language.as_deref(),
+                    &["OPENAI_COMPATIBLE_ASR_LANGUAGE"],
+                ),
+            ),
+            VENDOR_NAME,
+        );
+        let endpoint = build_transcriptions_endpoint(&base_url);
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::OpenAiCompatible.as_str(), &endpoint);
+        let settings = UploadSettings {
+            endpoint,
+            api_key,
+            model,
+            language,
+            proxy,
+        };
+
+        let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let stop_requested_for_thread = stop_requested.clone();
+
+        let handle = thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to build Tokio runtime");
+            if let Err(err) = runtime.block_on(run_session(
+                settings,
+                sample_rate,
+                callback,
+                receiver,
+                stop_requested_for_thread,
+            )) {
+                if let Some(cb) = status_callback.as_ref() {
+                    cb(format!("openai_compatible: {err}"));
+                }
+                eprintln!("OpenAI-compatible transcription error: {err}");
+            }
+        });
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            handle: Mutex::new(Some(handle)),
+            stop_requested,
+        })
+    }
+
+    fn current_sender(&self) -> Result<mpsc::Sender<StreamCommand>, String> {
+        self.sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| "OpenAI-compatible transcriber is not running".to_string())
+    }
+
+    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        match self.current_sender()?.try_send(StreamCommand::Audio(chunk)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_chunk)) => Err(
+                "Failed to queue PCM chunk for OpenAI-compatible ASR: channel is full (consumer stalled)"
+                    .into(),
+            ),
+            Err(TrySendError::Closed(_chunk)) => {
+                Err("Failed to queue PCM chunk for OpenAI-compatible ASR: channel closed".into())
+            }
+        }
+    }
+
+    pub fn request_force_endpoint(&self) -> Result<(), String> {
+        self.current_sender()?
+            .blocking_send(StreamCommand::ForceEndpoint)
+            .map_err(|e| format!("Failed to queue OpenAI-compatible force endpoint: {e}"))
+    }
+
+    /// Closing the command channel lets the worker upload the utterance that is
+    /// still being collected before the thread exits.
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        self.sender.lock().unwrap().take();
+
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for OpenAiCompatibleTranscriber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl StreamingTranscriber for OpenAiCompatibleTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        self.enqueue_chunk(chunk)
+    }
+
+    fn get_vendor_name(&self) -> String {
+        VENDOR_NAME.to_string()
+    }
+
+    fn force_endpoint(&self) -> Result<(), String> {
+        self.request_force_endpoint()
+    }
+
+    fn shutdown(&self) {
+        self.stop();
+        println!("OpenAI-compatible transcriber shutdown invoked");
+    }
+}
+
+async fn run_session(
+    settings: UploadSettings,
+    sample_rate: u32,
+    callback: PcmCallback,
+    mut command_rx: mpsc::Receiver<StreamCommand>,
+    stop_requested: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let client = http_client_builder(settings.proxy.as_deref())?
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to build OpenAI-compatible HTTP client: {e}"))?;
+    let (utterance_tx, mut utterance_rx) = mpsc::unbounded_channel::<Vec<i16>>();
+
+    let segment_audio = async move {
+        let mut segmenter = VadSegmenter::new(sample_rate);
+
+        while let Some(command) = command_rx.recv().await {
+            let utterance = match command {
+                StreamCommand::Audio(samples) => segmenter.push(&samples),
+                StreamCommand::ForceEndpoint => segmenter.flush(),
+            };
+
+            if let Some(utterance) = utterance {
+                if utterance_tx.send(utterance).is_err() {
+                    return Ok::<(), String>(());
+                }
+            }
+        }
+
+        if let Some(utterance) = segmenter.flush() {
+            let _ = utterance_tx.send(utterance);
+        }
+
+        Ok::<(), String>(())
+    };
+
+    let upload_utterances = async {
+        while let Some(utterance) = utterance_rx.recv().await {
+            let upload = transcribe_utterance(&client, &settings, sample_rate, &utterance);
+            let result = if stop_requested.load(Ordering::SeqCst) {
+                match time::timeout(Duration::from_secs(FINAL_FLUSH_TIMEOUT_SECS), upload).await {
+                    Ok(result) => result,
+                    Err(_) => Err(UploadFailure::Skipped(
+                        "final utterance upload timed out during shutdown".into(),
+                    )),
+                }
+            } else {
+                upload.await
+            };
+
+            match result {
+                Ok(text) => emit_commit(&callback, VENDOR_NAME, text),
+                Err(UploadFailure::Fatal(err)) => return Err(err),
+                Err(UploadFailure::Skipped(err)) => {
+                    eprintln!("OpenAI-compatible utterance skipped: {err}");
+                }
+            }
+        }
+
+        Ok::<(), String>(())
+    };
+
+    try_join(segment_audio, upload_utterances).await?;
+    println!("OpenAI-compatible transcription session stop completed");
+    Ok(())
+}
+
+async fn transcribe_utterance(
+    client: &Client,
+    settings: &UploadSettings,
+    sample_rate: u32,
+    samples: &[i16],
+) -> Result<String, UploadFailure> {
+    let wav = encode_wav(samples, sample_rate).map_err(UploadFailure::Skipped)?;
+    let file = Part::bytes(wav)
+        .file_name("utterance.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| UploadFailure::Skipped(format!("Failed to build upload part: {e}")))?;
+
+    let mut form = Form::new()
+        .part("file", file)
+        .text("model", settings.model.clone())
+        .text("response_format", "json");
+    if let Some(language) = settings.language.as_ref() {
+        form = form.text("language", language.clone());
+    }
+
+    let mut request = client.post(&settings.endpoint).multipart(form);
+    if let Some(api_key) = settings.api_key.as_ref() {
+        request = request.header("Authorization", format!("Bearer {api_key}"));
+    }
+
+    let response = request.send().await.map_err(|e| {
+        if e.is_connect() {
+            UploadFailure::Fatal(format!("Failed to connect to {}: {e}", settings.endpoint))
+        } else {
+            UploadFailure::Skipped(format!("Transcription request failed: {e}"))
+        }
+    })?;
+    // Billed per uploaded utterance, whatever the response.
+    report_audio_sent(
+        TranscriptVendors::OpenAiCompatible,
+        samples.len(),
+        sample_rate,
+    );
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Failed to read response body".to_string());
+
+    if !status.is_success() {
+        let message = format!("Transcription request failed ({status}): {body}");
+        return Err(if is_fatal_status(status.as_u16()) {
+            UploadFailure::Fatal(message)
+        } else {
+            UploadFailure::Skipped(message)
+        });
+    }
+
+    Ok(parse_transcription_response(&body).unwrap_or_default())
+}
+
+/// OpenAI takes one ISO-639-1 `language` and detects the language itself when
+/// it is omitted; candidate lists and code-switching cannot be expressed.
+pub(super) fn openai_language(language: &LanguageSetting, vendor: &str) -> Option<String> {
+    match language {
+        LanguageSetting::VendorDefault => None,
+        LanguageSetting::Fixed(language) => Some(primary_subtag(language)),
+        LanguageSetting::AutoDetect(candidates) => {
+            if !candidates.is_empty() {
+                eprintln!(
+                    "{vendor} cannot restrict auto-detection to {}; detecting any language",
+                    candidates.join(", ")
+                );
+            }
+            None
+        }
+        LanguageSetting::CodeSwitching(_) => {
+            eprintln!("{vendor} cannot code-switch within an utterance; detecting per utterance");
+            None
+        }
+    }
+}
+
+fn build_transcriptions_endpoint(base_url: &str) -> String {
+    let base_url = base_url.trim().trim_end_matches('/');
+    if base_url.ends_with("/audio/transcriptions") {
+        return base_url.to_string();
+    }
+
+    format!("{base_url}/audio/transcriptions")
+}
+
+fn is_fatal_status(status: u16) -> bool {
+    matches!(status, 401..=404)
+}
+
+fn parse_transcription_response(body: &str) -> Option<String> {
+    let text = match serde_json::from_str::<Value>(body) {
+        Ok(value) => value.get("text")?.as_str()?.to_string(),
+        Err(_) => body.to_string(),
+    };
+
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+fn encode_wav(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>, String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(samples.len() * 2 + 44));
+
+    {
+        let mut writer = hound::WavWriter::new(&mut cursor, spec)
+            .map_err(|e| format!("Failed to create WAV writer: {e}"))?;
+        for sample in samples {
+            writer
+                .write_sample(*sample)
+                .map_err(|e| format!("Failed to write WAV sample: {e}"))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| format!("Failed to finalize WAV buffer: {e}"))?;
+    }
+
+    Ok(cursor.into_inner())
+}
+
+/// Energy based utterance segmentation, using the same speech thresholds as the
+/// AssemblyAI force-endpoint detector in `loopback.rs`.
+struct VadSegmenter {
+    sample_rate: u32,
+    pre_roll: Vec<i16>,
+    utterance: Vec<i16>,
+    in_speech: bool,
+    speech_ms: u32,
+    silence_ms: u32,
+}
+
+impl VadSegmenter {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            pre_roll: Vec::new(),
+            utterance: Vec::new(),
+            in_speech: false,
+            speech_ms: 0,
+            silence_ms: 0,
+        }
+    }
+
+    fn push(&mut self, chunk: &[i16]) -> Option<Vec<i16>> {
+        if chunk.is_empty() {
+            return None;
+        }
+
+        let has_speech = chunk_has_speech(chunk);
+        let chunk_ms = self.duration_ms(chunk.len());
+
+        if !self.in_speech {
+            if !has_speech {
+                self.pre_roll.extend_from_slice(chunk);
+                let max_pre_roll = self.samples_for_ms(PRE_ROLL_MS);
+                if self.pre_roll.len() > max_pre_roll {
+                    let excess = self.pre_roll.len() - max_pre_roll;
+                    self.pre_roll.drain(..excess);
+                }
+                return None;
+            }
+
+            self.in_speech = true;
+            self.utterance = std::mem::take(&mut self.pre_roll);
+            self.speech_ms = 0;
+            self.silence_ms = 0;
+        }
+
+        self.utterance.extend_from_slice(chunk);
+        if has_speech {
+            self.speech_ms = self.speech_ms.saturating_add(chunk_ms);
+            self.silence_ms = 0;
+        } else {
+            self.silence_ms = self.silence_ms.saturating_add(chunk_ms);
+        }
+
+        if self.silence_ms >= END_OF_UTTERANCE_SILENCE_MS
+            || self.duration_ms(self.utterance.len()) >= MAX_UTTERANCE_MS
+        {
+            return self.finish();
+        }
+
+        None
+    }
+
+    fn flush(&mut self) -> Option<Vec<i16>> {
+        if !self.in_speech {
+            return None;
+        }
+
+        self.finish()
+    }
+
+    fn finish(&mut self) -> Option<Vec<i16>> {
+        self.in_speech = false;
+        self.silence_ms = 0;
+        let speech_ms = std::mem::take(&mut self.speech_ms);
+        let utterance = std::mem::take(&mut self.utterance);
+
+        if speech_ms < MIN_UTTERANCE_SPEECH_MS {
+            return None;
+        }
+
+        Some(utterance)
+    }
+
+    fn duration_ms(&self, samples: usize) -> u32 {
+        ((samples as u64) * 1000 / self.sample_rate as u64) as u32
+    }
+
+    fn samples_for_ms(&self, ms: u32) -> usize {
+        ((self.sample_rate as u64) * ms as u64 / 1000) as usize
+    }
+}
+
+fn chunk_has_speech(chunk: &[i16]) -> bool {
+    if chunk.is_empty() {
+        return false;
+    }
+
+    let peak = chunk
+        .iter()
+        .map(|sample| sample.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    let mean_abs = chunk
+        .iter()
+        .map(|sample| sample.unsigned_abs() as f32)
+        .sum::<f32>()
+        / chunk.len() as f32;
+
+    peak >= SPEECH_PEAK_THRESHOLD as u16 || mean_abs >= SPEECH_MEAN_ABS_THRESHOLD
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        VadSegmenter, build_transcriptions_endpoint, encode_wav, openai_language,
+        parse_transcription_response,
+    };
+    use crate::transcript_vendors::language::LanguageSetting;
+
+    const SAMPLE_RATE: u32 = 16_000;
+
+    fn chunk(amplitude: i16) -> Vec<i16> {
+        vec![amplitude; (SAMPLE_RATE / 10) as usize]
+    }
+
+    #[test]
+    fn only_a_fixed_language_is_sent() {
+        assert_eq!(
+            openai_language(&LanguageSetting::Fixed("zh-CN".to_string()), "Test").as_deref(),
+            Some("zh")
+        );
+        assert_eq!(
+            openai_language(&LanguageSetting::VendorDefault, "Test"),
+            None
+        );
+        let pair = vec!["zh".to_string(), "en".to_string()];
+        assert_eq!(
+            openai_language(&LanguageSetting::AutoDetect(pair.clone()), "Test"),
+            None
+        );
+        assert_eq!(
+            openai_language(&LanguageSetting::CodeSwitching(pair), "Test"),
+            None
+        );
+    }
+
+    #[test]
+    fn build_transcriptions_endpoint_appends_audio_path_once() {
+        assert_eq!(
+            build_transcriptions_endpoint("http://localhost:8000/v1/"),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            build_transcriptions_endpoint("https://api.groq.com/openai/v1/audio/transcriptions"),
+            "https://api.groq.com/openai/v1/audio/transcriptions"
+        );
+    }
+
+    #[test]
+    fn parse_transcription_response_reads_json_and_plain_text() {
+        assert_eq!(
+            parse_transcription_response(r#"{"text":" 你好世界 "}"#),
+            Some("你好世界".to_string())
+        );
+        assert_eq!(
+            parse_transcription_response("hello world\n"),
+            Some("hello world".to_string())
+        );
+        assert_eq!(parse_transcription_response(r#"{"text":""}"#), None);
+    }
+
+    #[test]
+    fn vad_segmenter_emits_utterance_after_trailing_silence() {
+        let mut segmenter = VadSegmenter::new(SAMPLE_RATE);
+
+        assert_eq!(segmenter.push(&chunk(0)), None);
+        for _ in 0..5 {
+            assert_eq!(segmenter.push(&chunk(4_000)), None);
+        }
+        for _ in 0..6 {
+            assert_eq!(segmenter.push(&chunk(0)), None);
+        }
+
+        let utterance = segmenter
+            .push(&chunk(0))
+            .expect("utterance should be emitted");
+        // 100ms pre-roll + 500ms speech + 700ms trailing silence.
+        assert_eq!(utterance.len(), (SAMPLE_RATE as usize / 10) * 13);
+        assert_eq!(segmenter.flush(), None);
+    }
+
+    #[test]
+    fn vad_segmenter_drops_short_noise_bursts() {
+        let mut segmenter = VadSegmenter::new(SAMPLE_RATE);
+
+        segmenter.push(&chunk(4_000));
+        for _ in 0..7 {
+            assert_eq!(segmenter.push(&chunk(0)), None);
+        }
+        assert_eq!(segmenter.flush(), None);
+    }
+
+    #[test]
+    fn vad_segmenter_flush_returns_active_utterance() {
+        let mut segmenter = VadSegmenter::new(SAMPLE_RATE);
+
+        for _ in 0..4 {
+            segmenter.push(&chunk(4_000));
+        }
+
+        assert_eq!(
+            segmenter.flush().map(|utterance| utterance.len()),
+            Some((SAMPLE_RATE as usize / 10) * 4)
+        );
+    }
+
+    #[test]
+    fn encode_wav_writes_mono_pcm_header() {
+        let wav = encode_wav(&[0, 1, -1, 2], SAMPLE_RATE).unwrap();
+
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(wav.len(), 44 + 8);
+    }
+}
@@ -0,0 +1,690 @@
+#![allow(clippy::collapsible_if)]
+
+///https://platform.openai.com/docs/guides/realtime-transcription
+use crate::provider_config::{
+    OPENAI_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string, resolve_required_string,
+    resolve_string_or_default,
+};
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_vendors::language::LanguageSetting;
+use crate::transcript_vendors::openai_compatible::openai_language;
+use crate::transcript_vendors::resample::StreamResampler;
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    report_audio_sent, report_keepalive_sent,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use futures_util::{SinkExt, StreamExt, future::try_join};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use tauri::http::Uri;
+use tokio::runtime::Runtime;
+use tokio::sync::{Mutex as AsyncMutex, mpsc, mpsc::error::TrySendError, oneshot, watch};
+use tokio::time::{self, Duration, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
+};
+
+const VENDOR_NAME: &str = "OpenAIRealtime";
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-transcribe";
+// The realtime API only accepts 24kHz mono pcm16.
+const TARGET_SAMPLE_RATE: u32 = 24_000;
+const VAD_THRESHOLD: f32 = 0.5;
+const VAD_PREFIX_PADDING_MS: u32 = 300;
+const VAD_SILENCE_DURATION_MS: u32 = 500;
+const HEARTBEAT_INTERVAL_SECS: u64 = 20;
+const IDLE_SILENCE_INTERVAL_SECS: u64 = 15;
+const IDLE_SILENCE_CHUNK_MS: u32 = 100;
+
+enum StreamCommand {
+    Audio(Vec<i16>),
+    ForceEndpoint,
+}
+
+struct SessionSettings {
+    url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+    proxy: Option<String>,
+}
+
+pub struct OpenAiRealtimeTranscriber {
+    sender: Mutex<Option<mpsc::Sender<StreamCommand>>>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl OpenAiRealtimeTranscriber {
+    pub fn start(
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        let api_key = resolve_required_string(
+            transcript_config.openai_realtime_api_key.as_deref(),
+            OPENAI_ENV_KEYS,
+            "OPENAI_API_KEY",
+        )?;
+        let base_url = resolve_string_or_default(
+            transcript_config.openai_realtime_base_url.as_deref(),
+            &["OPENAI_REALTIME_BASE_URL", "OPENAI_BASE_URL"],
+            DEFAULT_BASE_URL,
+        );
+        let model = resolve_string_or_default(
+            transcript_config.openai_realtime_model.as_deref(),
+            &["OPENAI_REALTIME_MODEL"],
+            DEFAULT_MODEL,
+        );
+        let language = openai_language(
+            &LanguageSetting::resolve(
+                &transcript_config,
+                resolve_optional_string(
+                    transcript_config.openai_realtime_language.as_deref(),
+                    &["OPENAI_REALTIME_LANGUAGE"],
+                ),
+            ),
+            VENDOR_NAME,
+        );
+        let url = build_realtime_url(&base_url);
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::OpenAiRealtime.as_str(), &url);
+        let settings = SessionSettings {
+            url,
+            api_key,
+            model,
+            language,
+            proxy,
+        };
+
+        let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let stop_requested_for_thread = stop_requested.clone();
+
+        let handle = thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to build Tokio runtime");
+            if let Err(err) = runtime.block_on(run_session(
+                settings,
+                sample_rate,
+                callback,
+                receiver,
+                shutdown_rx,
+                stop_requested_for_thread,
+            )) {
+                if let Some(cb) = status_callback.as_ref() {
+                    cb(format!("openai_realtime: {err}"));
+                }
+                eprintln!("OpenAI realtime transcription error: {err}");
+            }
+        });
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            shutdown: Mutex::new(Some(shutdown_tx)),
+            handle: Mutex::new(Some(handle)),
+            stop_requested,
+        })
+    }
+
+    fn current_sender(&self) -> Result<mpsc::Sender<StreamCommand>, String> {
+        self.sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| "OpenAI realtime transcriber is not running".to_string())
+    }
+
+    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        match self.current_sender()?.try_send(StreamCommand::Audio(chunk)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_chunk)) => Err(
+                "Failed to queue PCM chunk for OpenAI realtime: channel is full (consumer stalled)"
+                    .into(),
+            ),
+            Err(TrySendError::Closed(_chunk)) => {
+                Err("Failed to queue PCM chunk for OpenAI realtime: channel closed".into())
+            }
+        }
+    }
+
+    pub fn request_force_endpoint(&self) -> Result<(), String> {
+        self.current_sender()?
+            .blocking_send(StreamCommand::ForceEndpoint)
+            .map_err(|e| format!("Failed to queue OpenAI realtime force endpoint: {e}"))
+    }
+
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        self.sender.lock().unwrap().take();
+        if let Some(shutdown) = self.shutdown.lock().unwrap().take() {
+            let _ = shutdown.send(());
+        }
+
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for OpenAiRealtimeTranscriber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl StreamingTranscriber for OpenAiRealtimeTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        self.enqueue_chunk(chunk)
+    }
+
+    fn get_vendor_name(&self) -> String {
+        VENDOR_NAME.to_string()
+    }
+
+    fn force_endpoint(&self) -> Result<(), String> {
+        self.request_force_endpoint()
+    }
+
+    fn shutdown(&self) {
+        self.stop();
+        println!("OpenAI realtime websocket shutdown invoked");
+    }
+}
+
+async fn run_session(
+    settings: SessionSettings,
+    sample_rate: u32,
+    callback: PcmCallback,
+    mut audio_rx: mpsc::Receiver<StreamCommand>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    stop_requested: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let uri: Uri = settings
+        .url
+        .parse()
+        .map_err(|e| format!("Failed to parse OpenAI realtime URI: {e}"))?;
+    let builder = if is_azure_host(uri.host()) {
+        ClientRequestBuilder::new(uri).with_header("api-key", settings.api_key.clone())
+    } else {
+        ClientRequestBuilder::new(uri)
+            .with_header("Authorization", format!("Bearer {}", settings.api_key))
+            .with_header("OpenAI-Beta", "realtime=v1")
+    };
+    let client_request = builder
+        .into_client_request()
+        .map_err(|e| format!("Failed to build OpenAI realtime websocket request: {e}"))?;
+
+    let (ws_stream, _) = connect_websocket(client_request, settings.proxy.as_deref())
+        .await
+        .map_err(|e| format!("Failed to connect to OpenAI realtime: {e}"))?;
+
+    let (mut sink, mut stream) = ws_stream.split();
+    let session_payload =
+        build_transcription_session_payload(&settings.model, settings.language.as_deref());
+
+    sink.send(Message::Text(session_payload.to_string().into()))
+        .await
+        .map_err(|e| format!("Failed to send OpenAI transcription_session.update: {e}"))?;
+
+    let (termination_tx, mut termination_rx) = watch::channel(false);
+    let (started_tx, mut started_rx) = watch::channel(false);
+    let pending_items = Arc::new(AsyncMutex::new(PendingItems::default()));
+
+    let send_audio = async move {
+        let mut heartbeat = time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        heartbeat.tick().await;
+
+        let idle_keepalive_samples =
+            ((TARGET_SAMPLE_RATE as u64 * IDLE_SILENCE_CHUNK_MS as u64) / 1000).max(1) as usize;
+        let idle_keepalive_payload = build_append_payload(&vec![0_i16; idle_keepalive_samples]);
+        let idle_keepalive = time::sleep(Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+        tokio::pin!(idle_keepalive);
+
+        loop {
+            if *started_rx.borrow() {
+                break;
+            }
+
+            tokio::select! {
+                _ = &mut shutdown_rx => return Ok::<(), String>(()),
+                result = termination_rx.changed() => {
+                    if result.is_err() || *termination_rx.borrow() {
+                        return Ok::<(), String>(());
+                    }
+                }
+                result = started_rx.changed() => {
+                    if result.is_err() {
+                        return Err("OpenAI realtime readiness watcher closed unexpectedly".into());
+                    }
+                }
+            }
+        }
+
+        let mut resampler = StreamResampler::new(sample_rate, TARGET_SAMPLE_RATE);
+        let mut should_commit_tail = true;
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                result = termination_rx.changed() => {
+                    if result.is_err() || *termination_rx.borrow() {
+                        should_commit_tail = false;
+                        break;
+                    }
+                }
+                command = audio_rx.recv() => match command {
+                    Some(StreamCommand::Audio(samples)) => {
+                        let samples = resampler.process(&samples);
+                        if samples.is_empty() {
+                            continue;
+                        }
+                        sink.send(Message::Text(build_append_payload(&samples).into()))
+                            .await
+                            .map_err(|e| format!("Failed to send audio chunk to OpenAI realtime: {e}"))?;
+                        report_audio_sent(TranscriptVendors::OpenAiRealtime, samples.len(), TARGET_SAMPLE_RATE);
+                        idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                    }
+                    Some(StreamCommand::ForceEndpoint) => {
+                        sink.send(Message::Text(build_commit_payload().into()))
+                            .await
+                            .map_err(|e| format!("Failed to send OpenAI input_audio_buffer.commit: {e}"))?;
+                    }
+                    None => break,
+                },
+                _ = heartbeat.tick() => {
+                    sink.send(Message::Ping(Vec::new().into()))
+                        .await
+                        .map_err(|e| format!("Failed to send OpenAI realtime heartbeat ping: {e}"))?;
+                }
+                _ = &mut idle_keepalive => {
+                    sink.send(Message::Text(idle_keepalive_payload.clone().into()))
+                        .await
+                        .map_err(|e| format!("Failed to send OpenAI realtime idle silence chunk: {e}"))?;
+                    report_keepalive_sent(TranscriptVendors::OpenAiRealtime, idle_keepalive_samples, TARGET_SAMPLE_RATE);
+                    idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                }
+            }
+        }
+
+        if should_commit_tail {
+            // Best effort: an empty buffer is rejected, which is fine while closing.
+            let _ = sink
+                .send(Message::Text(build_commit_payload().into()))
+                .await;
+        }
+
+        sink.close()
+            .await
+            .map_err(|e| format!("Failed to close OpenAI realtime socket: {e}"))?;
+        Ok::<(), String>(())
+    };
+
+    let receive_events = {
+        let callback = callback.clone();
+        let termination_tx = termination_tx.clone();
+        let started_tx = started_tx.clone();
+        let pending_items = pending_items.clone();
+        let stop_requested = stop_requested.clone();
+
+        async move {
+            while let Some(message) = stream.next().await {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(err) => {
+                        let _ = termination_tx.send(true);
+                        flush_pending_items(&callback, &pending_items).await;
+                        if stop_requested.load(Ordering::SeqCst) {
+                            return Ok::<(), String>(());
+                        }
+                        return Err(format!("OpenAI realtime receive error: {err}"));
+                    }
+                };
+
+                match message {
+                    Message::Text(payload) => {
+                        let value: Value = serde_json::from_str(&payload)
+                            .map_err(|e| format!("Failed to parse OpenAI realtime payload: {e}"))?;
+                        let event_type = value
+                            .get("type")
+                            .and_then(|entry| entry.as_str())
+                            .unwrap_or_default();
+
+                        match event_type {
+                            "transcription_session.updated" => {
+                                let _ = started_tx.send(true);
+                            }
+                            "conversation.item.input_audio_transcription.delta" => {
+                                let item_id = read_str(&value, "item_id");
+                                let delta = read_str(&value, "delta");
+                                let draft = pending_items.lock().await.append_delta(item_id, delta);
+                                emit_draft(&callback, VENDOR_NAME, draft);
+                            }
+                            "conversation.item.input_audio_transcription.completed" => {
+                                let item_id = read_str(&value, "item_id");
+                                let transcript = read_str(&value, "transcript");
+                                let committed =
+                                    pending_items.lock().await.complete(item_id, transcript);
+                                if let Some(text) = committed {
+                                    emit_commit(&callback, VENDOR_NAME, text);
+                                }
+                            }
+                            "conversation.item.input_audio_transcription.failed" => {
+                                let item_id = read_str(&value, "item_id");
+                                pending_items.lock().await.discard(item_id);
+                                eprintln!(
+                                    "OpenAI realtime transcription failed for item {item_id}: {}",
+                                    describe_error(&value)
+                                );
+                            }
+                            "error" => {
+                                if is_ignorable_error(&value) {
+                                    continue;
+                                }
+                                let _ = termination_tx.send(true);
+                                return Err(format!(
+                                    "OpenAI realtime returned error: {}",
+                                    describe_error(&value)
+                                ));
+                            }
+                            _ => {}
+                        }
+                    }
+                    Message::Close(frame) => {
+                        let _ = termination_tx.send(true);
+                        flush_pending_items(&callback, &pending_items).await;
+                        if stop_requested.load(Ordering::SeqCst) {
+                            return Ok::<(), String>(());
+                        }
+                        return Err(describe_close_frame("OpenAI realtime", frame.as_ref()));
+                    }
+                    _ => {}
+                }
+            }
+
+            let _ = termination_tx.send(true);
+            flush_pending_items(&callback, &pending_items).await;
+            if stop_requested.load(Ordering::SeqCst) {
+                Ok::<(), String>(())
+            } else {
+                Err("OpenAI realtime websocket closed unexpectedly without a close frame".into())
+            }
+        }
+    };
+
+    try_join(send_audio, receive_events).await?;
+    Ok(())
+}
+
+/// Transcripts arrive per conversation item; deltas of several items can
+/// interleave when server VAD commits while a previous item is still decoding.
+#[derive(Default)]
+struct PendingItems {
+    items: Vec<(String, String)>,
+}
+
+impl PendingItems {
+    fn append_delta(&mut self, item_id: &str, delta: &str) -> String {
+        match self.items.iter_mut().find(|(id, _)| id == item_id) {
+            Some((_, text)) => {
+                text.push_str(delta);
+                text.clone()
+            }
+            None => {
+                self.items.push((item_id.to_string(), delta.to_string()));
+                delta.to_string()
+            }
+        }
+    }
+
+    fn complete(&mut self, item_id: &str, transcript: &str) -> Option<String> {
+        let accumulated = self.take(item_id);
+        let text = if transcript.trim().is_empty() {
+            accumulated.unwrap_or_default()
+        } else {
+            transcript.to_string()
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text.to_string())
+        }
+    }
+
+    fn discard(&mut self, item_id: &str) {
+        self.take(item_id);
+    }
+
+    fn drain(&mut self) -> Vec<String> {
+        self.items.drain(..).map(|(_, text)| text).collect()
+    }
+
+    fn take(&mut self, item_id: &str) -> Option<String> {
+        let index = self.items.iter().position(|(id, _)| id == item_id)?;
+        Some(self.items.remove(index).1)
+    }
+}
+
+async fn flush_pending_items(
+    callback: &PcmCallback,
+    pending_items: &Arc<AsyncMutex<PendingItems>>,
+) {
+    for text in pending_items.lock().await.drain() {
+        emit_commit(callback, VENDOR_NAME, text);
+    }
+}
+
+fn read_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value
+        .get(key)
+        .and_then(|entry| entry.as_str())
+        .unwrap_or_default()
+}
+
+fn describe_error(value: &Value) -> String {
+    let error = value.get("error");
+    let message = error
+        .and_then(|entry| entry.get("message"))
+        .and_then(|entry| entry.as_str())
+        .unwrap_or("unknown OpenAI realtime error");
+    match error
+        .and_then(|entry| entry.get("code"))
+        .and_then(|entry| entry.as_str())
+    {
+        Some(code) => format!("{message} (code={code})"),
+        None => message.to_string(),
+    }
+}
+
+fn is_ignorable_error(value: &Value) -> bool {
+    matches!(
+        value
+            .get("error")
+            .and_then(|entry| entry.get("code"))
+            .and_then(|entry| entry.as_str()),
+        Some("input_audio_buffer_commit_empty")
+    )
+}
+
+fn describe_close_frame(vendor: &str, frame: Option<&tungstenite::protocol::CloseFrame>) -> String {
+    match frame {
+        Some(frame) => format!(
+            "{vendor} websocket closed unexpectedly (code={:?}, reason={})",
+            frame.code, frame.reason
+        ),
+        None => format!("{vendor} websocket closed unexpectedly without a close frame"),
+    }
+}
+
+fn is_azure_host(host: Option<&str>) -> bool {
+    host.is_some_and(|host| {
+        host.ends_with(".openai.azure.com") || host.ends_with(".cognitiveservices.azure.com")
+    })
+}
+
+/// Accepts either an OpenAI style REST base (`https://api.openai.com/v1`) or a
+/// full realtime endpoint such as Azure's
+/// `wss://<resource>.openai.azure.com/openai/realtime?api-version=...&deployment=...`.
+fn build_realtime_url(base_url: &str) -> String {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let mut url = if let Some(rest) = trimmed.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = trimmed.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        trimmed.to_string()
+    };
+
+    let (path, query) = match url.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (url.clone(), None),
+    };
+    if !path.trim_end_matches('/').ends_with("/realtime") {
+        url = match &query {
+            Some(query) => format!("{}/realtime?{query}", path.trim_end_matches('/')),
+            None => format!("{}/realtime", path.trim_end_matches('/')),
+        };
+    }
+
+    if !query.as_deref().unwrap_or_default().contains("intent=") {
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url.push(separator);
+        url.push_str("intent=transcription");
+    }
+
+    url
+}
+
+fn build_transcription_session_payload(model: &str, language: Option<&str>) -> Value {
+    let mut transcription = json!({ "model": model });
+    if let Some(language) = language {
+        transcription["language"] = json!(language);
+    }
+
+    json!({
+        "type": "transcription_session.update",
+        "session": {
+            "input_audio_format": "pcm16",
+            "input_audio_transcription": transcription,
+            "turn_detection": {
+                "type": "server_vad",
+                "threshold": VAD_THRESHOLD,
+                "prefix_padding_ms": VAD_PREFIX_PADDING_MS,
+                "silence_duration_ms": VAD_SILENCE_DURATION_MS
+            }
+        }
+    })
+}
+
+fn build_append_payload(samples: &[i16]) -> String {
+    let mut bytes = Vec::with_capacity(samples.len() * 2);
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    json!({
+        "type": "input_audio_buffer.append",
+        "audio": STANDARD.encode(bytes)
+    })
+    .to_string()
+}
+
+fn build_commit_payload() -> String {
+    json!({ "type": "input_audio_buffer.commit" }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        PendingItems, build_append_payload, build_realtime_url,
+        build_transcription_session_payload, is_azure_host, is_ignorable_error,
+    };
+    use serde_json::{Value, json};
+
+    #[test]
+    fn realtime_url_is_derived_from_rest_base_url() {
+        assert_eq!(
+            build_realtime_url("https://api.openai.com/v1/"),
+            "wss://api.openai.com/v1/realtime?intent=transcription"
+        );
+        assert_eq!(
+            build_realtime_url("http://localhost:8080/v1"),
+            "ws://localhost:8080/v1/realtime?intent=transcription"
+        );
+    }
+
+    #[test]
+    fn realtime_url_keeps_azure_query_parameters() {
+        assert_eq!(
+            build_realtime_url(
+                "wss://demo.openai.azure.com/openai/realtime?api-version=2025-04-01-preview&deployment=gpt-4o-transcribe"
+            ),
+            "wss://demo.openai.azure.com/openai/realtime?api-version=2025-04-01-preview&deployment=gpt-4o-transcribe&intent=transcription"
+        );
+        assert!(is_azure_host(Some("demo.openai.azure.com")));
+        assert!(!is_azure_host(Some("api.openai.com")));
+    }
+
+    #[test]
+    fn session_payload_enables_server_vad_and_optional_language() {
+        let payload = build_transcription_session_payload("gpt-4o-transcribe", Some("zh"));
+        assert_eq!(payload["type"], "transcription_session.update");
+        assert_eq!(payload["session"]["input_audio_format"], "pcm16");
+        assert_eq!(
+            payload["session"]["input_audio_transcription"]["language"],
+            "zh"
+        );
+        assert_eq!(payload["session"]["turn_detection"]["type"], "server_vad");
+
+        let payload = build_transcription_session_payload("whisper-1", None);
+        assert!(
+            payload["session"]["input_audio_transcription"]
+                .get("language")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn append_payload_base64_encodes_little_endian_pcm() {
+        let payload: Value = serde_json::from_str(&build_append_payload(&[1, -1])).unwrap();
+        assert_eq!(payload["type"], "input_audio_buffer.append");
+        assert_eq!(payload["audio"], "AQD//w==");
+    }
+
+    #[test]
+    fn pending_items_accumulate_deltas_per_item() {
+        let mut items = PendingItems::default();
+        assert_eq!(items.append_delta("a", "你好"), "你好");
+        assert_eq!(items.append_delta("b", "next"), "next");
+        assert_eq!(items.append_delta("a", "世界"), "你好世界");
+
+        assert_eq!(items.complete("a", ""), Some("你好世界".to_string()));
+        assert_eq!(
+            items.complete("b", "next one"),
+            Some("next one".to_string())
+        );
+        assert!(items.drain().is_empty());
+    }
+
+    #[test]
+    fn empty_commit_error_is_ignored() {
+        assert!(is_ignorable_error(&json!({
+            "type": "error",
+            "error": { "code": "input_audio_buffer_commit_empty" }
+        })));
+        assert!(!is_ignorable_error(&json!({
+            "type": "error",
+            "error": { "code": "invalid_api_key" }
+        })));
+    }
+}
@@ -0,0 +1,111 @@
+//! Streaming linear resampler for vendors that need a fixed upload rate.
+//! The read position and the last input sample carry over between chunks, so
+//! chunk boundaries interpolate exactly like the middle of a chunk.
+
+pub struct StreamResampler {
+    input_rate: u32,
+    output_rate: u32,
+    /// Next output position in units of 1/`output_rate` input samples,
+    /// relative to the next chunk's first sample. Negative values lie between
+    /// `previous` and that sample.
+    position: i64,
+    previous: Option<i16>,
+}
+
+impl StreamResampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Self {
+        Self {
+            input_rate,
+            output_rate,
+            position: 0,
+            previous: None,
+        }
+    }
+
+    pub fn process(&mut self, chunk: &[i16]) -> Vec<i16> {
+        if self.input_rate == 0 || self.output_rate == 0 || self.input_rate == self.output_rate {
+            return chunk.to_vec();
+        }
+        let Some(&last_sample) = chunk.last() else {
+            return Vec::new();
+        };
+
+        let denominator = self.output_rate as i64;
+        let previous = self.previous.unwrap_or(chunk[0]);
+        let sample_at = |index: i64| {
+            if index < 0 {
+                previous
+            } else {
+                chunk[index as usize]
+            }
+        };
+        let last_position = (chunk.len() as i64 - 1) * denominator;
+        let mut output = Vec::with_capacity(
+            (chunk.len() as u64 * self.output_rate as u64 / self.input_rate as u64) as usize + 1,
+        );
+
+        while self.position <= last_position {
+            let base = self.position.div_euclid(denominator);
+            let frac = self.position.rem_euclid(denominator) as f64 / denominator as f64;
+            let current = sample_at(base) as f64;
+            let next = if frac > 0.0 {
+                sample_at(base + 1) as f64
+            } else {
+                current
+            };
+            let value = current + (next - current) * frac;
+            output.push(value.round().clamp(i16::MIN as f64, i16::MAX as f64) as i16);
+            self.position += self.input_rate as i64;
+        }
+
+        self.position -= chunk.len() as i64 * denominator;
+        self.previous = Some(last_sample);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamResampler;
+
+    #[test]
+    fn converts_to_target_rate() {
+        let input: Vec<i16> = (0..160).map(|value| value as i16).collect();
+        let mut resampler = StreamResampler::new(16_000, 24_000);
+        let output = resampler.process(&input);
+        // The last position needs the next chunk's first sample.
+        assert_eq!(output.len(), 239);
+        assert_eq!(output[0], 0);
+        assert_eq!(output[3], 2);
+        assert_eq!(resampler.process(&input).len(), 240);
+        assert_eq!(StreamResampler::new(24_000, 24_000).process(&input), input);
+
+        let input = vec![0_i16; 480];
+        assert_eq!(
+            StreamResampler::new(48_000, 16_000).process(&input).len(),
+            160
+        );
+    }
+
+    #[test]
+    fn chunked_input_matches_one_pass() {
+        let input: Vec<i16> = (0..4_410)
+            .map(|index| ((index as f64 * 0.05).sin() * 12_000.0) as i16)
+            .collect();
+        let whole = StreamResampler::new(44_100, 16_000).process(&input);
+
+        let mut resampler = StreamResampler::new(44_100, 16_000);
+        let chunked: Vec<i16> = input
+            .chunks(441)
+            .flat_map(|chunk| resampler.process(chunk))
+            .collect();
+
+        assert_eq!(chunked.len(), whole.len());
+        assert!(
+            chunked
+                .iter()
+                .zip(&whole)
+                .all(|(a, b)| (*a as i32 - *b as i32).abs() <= 1)
+        );
+    }
+}
@@ -4,9 +4,14 @@
 ///https://docs.rev.ai/api/streaming/responses
 use crate::provider_config::{
     TranscriptRuntimeConfig, resolve_optional_string, resolve_required_string,
+    resolve_string_or_default,
 };
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_vendors::language::{LanguageSetting, primary_subtag};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    encode_query_component, report_audio_sent, report_keepalive_sent,
 };
 use futures_util::{SinkExt, StreamExt, future::try_join};
 #[cfg(target_os = "windows")]
@@ -27,17 +32,14 @@ use tokio_tungstenite::tungstenite::{
     Error as WsError,
     handshake::client::{Request as WsRequest, Response as WsResponse},
 };
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
+};
 #[cfg(target_os = "windows")]
 use tokio_tungstenite::{
     Connector, MaybeTlsStream, WebSocketStream, connect_async_tls_with_config,
 };
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{
-        client::{ClientRequestBuilder, IntoClientRequest},
-        protocol::Message,
-    },
-};
 
 pub struct RevAiTranscriber {
     sender: mpsc::Sender<Vec<i16>>,
@@ -71,10 +73,29 @@ impl RevAiTranscriber {
             transcript_config.revai_metadata.as_deref(),
             &["REVAI_METADATA"],
         );
-        let language = resolve_optional_string(
-            transcript_config.revai_language.as_deref(),
-            &["REVAI_LANGUAGE"],
+        let language = revai_language(&LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.revai_language.as_deref(),
+                &["REVAI_LANGUAGE"],
+            ),
+        ));
+        let ws_url = resolve_string_or_default(
+            transcript_config.revai_ws_url.as_deref(),
+            &["REVAI_WS_URL"],
+            BASE_URL,
+        );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::RevAI.as_str(), &ws_url);
+        let custom_vocabulary_id = resolve_optional_string(
+            transcript_config.revai_custom_vocabulary_id.as_deref(),
+            &["REVAI_CUSTOM_VOCABULARY_ID"],
         );
+        if custom_vocabulary_id.is_none() && !resolve_vocabulary(&transcript_config).is_empty() {
+            // RevAI streaming only accepts vocabularies pre-submitted through its
+            // custom vocabulary API, referenced by id.
+            eprintln!("RevAI ignores ASR_VOCABULARY; set REVAI_CUSTOM_VOCABULARY_ID instead");
+        }
 
         let (sender, receiver) = mpsc::channel::<Vec<i16>>(64);
         let (shutdown, shutdown_rx) = oneshot::channel::<()>();
@@ -85,8 +106,11 @@ impl RevAiTranscriber {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_stream(
                 api_key,
+                ws_url,
+                proxy,
                 metadata,
                 language,
+                custom_vocabulary_id,
                 sample_rate,
                 callback,
                 receiver,
@@ -147,10 +171,26 @@ impl StreamingTranscriber for RevAiTranscriber {
     }
 }
 
+/// RevAI streaming takes exactly one language and cannot detect or switch.
+fn revai_language(language: &LanguageSetting) -> Option<String> {
+    if language.is_multilingual() {
+        let first = language.candidate_languages().first()?;
+        eprintln!("RevAI streaming cannot auto-detect or code-switch; using {first}");
+        return Some(match primary_subtag(first).as_str() {
+            "zh" => "cmn".to_string(),
+            other => other.to_string(),
+        });
+    }
+    language.fixed_language().map(ToString::to_string)
+}
+
 async fn run_stream(
     api_key: String,
+    ws_url: String,
+    proxy: Option<String>,
     metadata: Option<String>,
     language: Option<String>,
+    custom_vocabulary_id: Option<String>,
     sample_rate: u32,
     callback: PcmCallback,
     mut audio_rx: mpsc::Receiver<Vec<i16>>,
@@ -166,8 +206,11 @@ async fn run_stream(
 
         match stream_once(
             &api_key,
+            &ws_url,
+            proxy.as_deref(),
             metadata.as_deref(),
             language.as_deref(),
+            custom_vocabulary_id.as_deref(),
             sample_rate,
             &callback,
             &mut audio_rx,
@@ -193,8 +236,11 @@ async fn run_stream(
 
 async fn stream_once(
     api_key: &str,
+    ws_url: &str,
+    proxy: Option<&str>,
     metadata: Option<&str>,
     language: Option<&str>,
+    custom_vocabulary_id: Option<&str>,
     sample_rate: u32,
     callback: &PcmCallback,
     audio_rx: &mut mpsc::Receiver<Vec<i16>>,
@@ -234,12 +280,21 @@ async fn stream_once(
         }
     }
 
+    if let Some(custom_vocabulary_id) = custom_vocabulary_id {
+        if !custom_vocabulary_id.trim().is_empty() {
+            params.push((
+                "custom_vocabulary_id".to_string(),
+                custom_vocabulary_id.trim().to_string(),
+            ));
+        }
+    }
+
     let query = params
         .into_iter()
-        .map(|(key, value)| format!("{key}={}", encode_component(&value)))
+        .map(|(key, value)| format!("{key}={}", encode_query_component(&value)))
         .collect::<Vec<_>>()
         .join("&");
-    let url = format!("{BASE_URL}?{query}");
+    let url = format!("{ws_url}?{query}");
 
     let uri: Uri = url
         .parse()
@@ -250,11 +305,11 @@ async fn stream_once(
         .map_err(|e| format!("Failed to build RevAI websocket request: {e}"))?;
 
     #[cfg(target_os = "windows")]
-    let (ws_stream, _) = connect_revai_socket(client_request)
+    let (ws_stream, _) = connect_revai_socket(client_request, proxy)
         .await
         .map_err(|e| format!("Failed to connect to RevAI: {e}"))?;
     #[cfg(not(target_os = "windows"))]
-    let (ws_stream, _) = connect_async(client_request)
+    let (ws_stream, _) = connect_websocket(client_request, proxy)
         .await
         .map_err(|e| format!("Failed to connect to RevAI: {e}"))?;
 
@@ -317,6 +372,7 @@ async fn stream_once(
                         sink.send(Message::Binary(audio_bytes.into()))
                             .await
                             .map_err(|e| format!("Failed to send audio chunk to RevAI: {e}"))?;
+                        report_audio_sent(TranscriptVendors::RevAI, samples.len(), sample_rate);
                         idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                     }
                     None => break,
@@ -335,6 +391,7 @@ async fn stream_once(
                     sink.send(Message::Binary(audio_bytes.into()))
                         .await
                         .map_err(|e| format!("Failed to send RevAI idle silence chunk: {e}"))?;
+                    report_keepalive_sent(TranscriptVendors::RevAI, idle_keepalive_chunk.len(), sample_rate);
                     idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                 }
             }
@@ -477,8 +534,8 @@ fn should_retry_connection(err: &str, attempt: u32) -> bool {
         || err.contains("code=Abnormal")
         || err.contains("code=Error")
         || err.contains("code=Again")
-        || err.contains("code=4006")
-        || err.contains("code=4013")
+        || err.contains("code=Library(4006)")
+        || err.contains("code=Library(4013)")
 }
 
 async fn flush_last_partial_as_final(
@@ -501,7 +558,11 @@ async fn flush_last_partial_as_final(
 #[cfg(target_os = "windows")]
 async fn connect_revai_socket(
     request: WsRequest,
+    proxy: Option<&str>,
 ) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, WsResponse), WsError> {
+    if proxy.is_some() {
+        return connect_websocket(request, proxy).await;
+    }
     match TlsConnector::builder().build() {
         Ok(connector) => {
             match connect_async_tls_with_config(
@@ -521,7 +582,7 @@ async fn connect_revai_socket(
         Err(err) => eprintln!("Failed to build native TLS connector: {err}"),
     }
 
-    connect_async(request).await
+    connect_websocket(request, None).await
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -615,19 +676,6 @@ fn is_connected_payload(payload: &str) -> bool {
     false
 }
 
-fn encode_component(value: &str) -> String {
-    let mut encoded = String::new();
-    for byte in value.bytes() {
-        match byte {
-            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
-                encoded.push(byte as char);
-            }
-            _ => encoded.push_str(&format!("%{byte:02X}")),
-        }
-    }
-    encoded
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
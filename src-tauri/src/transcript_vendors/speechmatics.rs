@@ -2,10 +2,17 @@
 
 /// https://docs.speechmatics.com/api-ref/realtime-transcription-websocket#addtranslation
 use crate::provider_config::{
-    TranscriptRuntimeConfig, resolve_optional_string, resolve_required_string,
+    SPEECHMATICS_ENV_KEYS, TranscriptRuntimeConfig, resolve_optional_string,
 };
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_latency::{note_result_offset, note_stream_started};
+use crate::transcript_translation::TranslationSettings;
+use crate::transcript_vendors::language::{DetectedLanguage, LanguageSetting, primary_subtag};
+use crate::transcript_vendors::token_broker::{CredentialSource, VendorCredential};
+use crate::transcript_vendors::vocabulary::resolve_vocabulary;
 use crate::transcript_vendors::{
-    PcmCallback, StatusCallback, StreamingTranscriber, emit_commit, emit_draft,
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    emit_translation, report_audio_sent, report_keepalive_sent,
 };
 use futures_util::{SinkExt, StreamExt, future::try_join};
 use serde_json::{Value, json};
@@ -17,16 +24,19 @@ use tauri::http::Uri;
 use tokio::runtime::Runtime;
 use tokio::sync::{Mutex as AsyncMutex, mpsc, mpsc::error::TrySendError, oneshot, watch};
 use tokio::time::{self, Duration, MissedTickBehavior};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{
-        client::{ClientRequestBuilder, IntoClientRequest},
-        protocol::Message,
-    },
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
 };
 
 const DEFAULT_RT_URL: &str = "wss://eu2.rt.speechmatics.com/v2/";
 const DEFAULT_LANGUAGE: &str = "cmn";
+const DEFAULT_BILINGUAL_PACK: &str = "cmn_en";
+const BILINGUAL_PACKS: [(&str, &str, &str); 3] = [
+    ("cmn_en", "zh", "en"),
+    ("en_ms", "en", "ms"),
+    ("en_ta", "en", "ta"),
+];
 const END_OF_UTTERANCE_SILENCE_TRIGGER: f32 = 0.4;
 const MAX_DELAY_SECONDS: f32 = 0.8;
 const HEARTBEAT_INTERVAL_SECS: u64 = 20;
@@ -52,19 +62,35 @@ impl SpeechmaticsTranscriber {
         status_callback: Option<StatusCallback>,
         transcript_config: TranscriptRuntimeConfig,
     ) -> Result<Self, String> {
-        let api_key = resolve_required_string(
-            transcript_config.speechmatics_api_key.as_deref(),
-            &["SPEECHMATICS_API_KEY"],
+        let credentials = CredentialSource::resolve(
+            TranscriptVendors::SpeechMatics,
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.speechmatics_api_key.as_deref(),
+                SPEECHMATICS_ENV_KEYS,
+            ),
             "SPEECHMATICS_API_KEY",
         )?;
-        let language = resolve_optional_string(
-            transcript_config.speechmatics_language.as_deref(),
-            &["SPEECHMATICS_LANGUAGE"],
-        );
+        let language = speechmatics_language(&LanguageSetting::resolve(
+            &transcript_config,
+            resolve_optional_string(
+                transcript_config.speechmatics_language.as_deref(),
+                &["SPEECHMATICS_LANGUAGE"],
+            ),
+        ));
         let url = resolve_optional_string(
             transcript_config.speechmatics_rt_url.as_deref(),
             &["SPEECHMATICS_RT_URL"],
         );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config).proxy_for(
+            TranscriptVendors::SpeechMatics.as_str(),
+            url.as_deref().unwrap_or(DEFAULT_RT_URL),
+        );
+        let vocabulary = resolve_vocabulary(&transcript_config);
+        let translation_targets = speechmatics_translation_targets(
+            TranslationSettings::resolve(&transcript_config).vendor_target_languages(),
+            &language,
+        );
 
         let (sender, receiver) = mpsc::channel::<StreamCommand>(64);
         let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
@@ -76,9 +102,12 @@ impl SpeechmaticsTranscriber {
         let handle = thread::spawn(move || {
             let runtime = Runtime::new().expect("Failed to build Tokio runtime");
             if let Err(err) = runtime.block_on(run_session(
-                api_key,
+                credentials,
                 url,
+                proxy,
                 language,
+                vocabulary,
+                translation_targets,
                 sample_rate,
                 callback_clone,
                 receiver,
@@ -179,9 +208,12 @@ impl StreamingTranscriber for SpeechmaticsTranscriber {
 }
 
 async fn run_session(
-    api_key: String,
+    credentials: CredentialSource,
     rt_url: Option<String>,
-    language: Option<String>,
+    proxy: Option<String>,
+    language: String,
+    vocabulary: Vec<String>,
+    translation_targets: Vec<String>,
     sample_rate: u32,
     callback: PcmCallback,
     mut audio_rx: mpsc::Receiver<StreamCommand>,
@@ -189,22 +221,33 @@ async fn run_session(
     stop_requested: Arc<AtomicBool>,
 ) -> Result<(), String> {
     let url = rt_url.unwrap_or_else(|| DEFAULT_RT_URL.to_string());
-    let language = language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+    let detected_language = DetectedLanguage::default();
+    let callback = detected_language.wrap_callback(callback);
     let uri: Uri = url
         .parse()
         .map_err(|e| format!("Failed to parse Speechmatics streaming URI: {e}"))?;
+    // Temporary keys authenticate exactly like the long-lived key.
+    let secret = match credentials.fetch(None).await? {
+        VendorCredential::ApiKey(secret) | VendorCredential::Token(secret) => secret,
+        VendorCredential::SessionUrl(_) => {
+            return Err("Token service returned a session URL for Speechmatics".to_string());
+        }
+    };
     let builder =
-        ClientRequestBuilder::new(uri).with_header("Authorization", format!("Bearer {api_key}"));
+        ClientRequestBuilder::new(uri).with_header("Authorization", format!("Bearer {secret}"));
     let client_request = builder
         .into_client_request()
         .map_err(|e| format!("Failed to build Speechmatics websocket request: {e}"))?;
 
-    let (ws_stream, _) = connect_async(client_request)
+    let (ws_stream, _) = connect_websocket(client_request, proxy.as_deref())
         .await
         .map_err(|e| format!("Failed to connect to Speechmatics: {e}"))?;
+    // Result offsets restart with every connection.
+    note_stream_started(TranscriptVendors::SpeechMatics);
 
     let (mut sink, mut stream) = ws_stream.split();
-    let start_payload = build_start_recognition_payload(&language, sample_rate);
+    let start_payload =
+        build_start_recognition_payload(&language, &vocabulary, &translation_targets, sample_rate);
 
     sink.send(Message::Text(start_payload.to_string().into()))
         .await
@@ -214,6 +257,7 @@ async fn run_session(
     let (started_tx, mut started_rx) = watch::channel(false);
     let utterance_buffer = Arc::new(AsyncMutex::new(String::new()));
     let last_partial = Arc::new(AsyncMutex::new(None::<String>));
+    let translations = Arc::new(AsyncMutex::new(PendingTranslations::default()));
     let eos_sent = Arc::new(AtomicBool::new(false));
 
     let send_audio = {
@@ -264,10 +308,12 @@ async fn run_session(
                     }
                     command = audio_rx.recv() => match command {
                         Some(StreamCommand::Audio(bytes)) => {
-                            total_samples_sent = total_samples_sent.saturating_add((bytes.len() / 2) as u64);
+                            let samples = bytes.len() / 2;
+                            total_samples_sent = total_samples_sent.saturating_add(samples as u64);
                             sink.send(Message::Binary(bytes.into()))
                                 .await
                                 .map_err(|e| format!("Failed to send audio chunk to Speechmatics: {e}"))?;
+                            report_audio_sent(TranscriptVendors::SpeechMatics, samples, sample_rate);
                             chunk_seq_no += 1;
                             idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                         }
@@ -293,6 +339,7 @@ async fn run_session(
                         sink.send(Message::Binary(idle_keepalive_chunk.clone().into()))
                             .await
                             .map_err(|e| format!("Failed to send Speechmatics idle silence chunk: {e}"))?;
+                        report_keepalive_sent(TranscriptVendors::SpeechMatics, idle_keepalive_samples, sample_rate);
                         chunk_seq_no += 1;
                         idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
                     }
@@ -323,8 +370,10 @@ async fn run_session(
         let started_tx = started_tx.clone();
         let utterance_buffer = utterance_buffer.clone();
         let last_partial = last_partial.clone();
+        let translations = translations.clone();
         let eos_sent = eos_sent.clone();
         let stop_requested = stop_requested.clone();
+        let detected_language = detected_language.clone();
 
         async move {
             while let Some(message) = stream.next().await {
@@ -349,7 +398,9 @@ async fn run_session(
                             "RecognitionStarted" => {
                                 let _ = started_tx.send(true);
                             }
-                            "AddPartialTranscript" | "AddPartialTranslation" => {
+                            "AddPartialTranscript" => {
+                                detected_language
+                                    .update(extract_detected_language(&value).as_deref());
                                 if let Some(text) = extract_payload_text(&value) {
                                     *last_partial.lock().await = Some(text.clone());
                                     let draft = {
@@ -359,7 +410,13 @@ async fn run_session(
                                     emit_draft(&callback, "SpeechMatics", draft);
                                 }
                             }
-                            "AddTranscript" | "AddTranslation" => {
+                            "AddTranscript" => {
+                                detected_language
+                                    .update(extract_detected_language(&value).as_deref());
+                                translations.lock().await.note_transcript(&value);
+                                if let Some(end_time) = result_time(&value, "end_time", f64::max) {
+                                    note_result_offset(TranscriptVendors::SpeechMatics, end_time);
+                                }
                                 if let Some(text) = extract_payload_text(&value) {
                                     {
                                         let mut buffer = utterance_buffer.lock().await;
@@ -369,11 +426,34 @@ async fn run_session(
                                     *last_partial.lock().await = None;
                                 }
                             }
+                            "AddTranslation" => {
+                                let language = value
+                                    .get("language")
+                                    .and_then(|entry| entry.as_str())
+                                    .unwrap_or_default()
+                                    .to_string();
+                                if let Some(text) = extract_payload_text(&value) {
+                                    let ready = translations.lock().await.push(
+                                        language,
+                                        text,
+                                        result_time(&value, "start_time", f64::min),
+                                    );
+                                    if let Some((language, text)) = ready {
+                                        emit_translation(
+                                            &callback,
+                                            "SpeechMatics",
+                                            &language,
+                                            text,
+                                        );
+                                    }
+                                }
+                            }
                             "EndOfUtterance" => {
                                 flush_current_utterance(
                                     &callback,
                                     &utterance_buffer,
                                     &last_partial,
+                                    &translations,
                                 )
                                 .await;
                             }
@@ -382,6 +462,7 @@ async fn run_session(
                                     &callback,
                                     &utterance_buffer,
                                     &last_partial,
+                                    &translations,
                                 )
                                 .await;
                                 let _ = termination_tx.send(true);
@@ -405,7 +486,13 @@ async fn run_session(
                     }
                     Message::Close(frame) => {
                         let _ = termination_tx.send(true);
-                        flush_current_utterance(&callback, &utterance_buffer, &last_partial).await;
+                        flush_current_utterance(
+                            &callback,
+                            &utterance_buffer,
+                            &last_partial,
+                            &translations,
+                        )
+                        .await;
                         if eos_sent.load(Ordering::SeqCst) || stop_requested.load(Ordering::SeqCst)
                         {
                             return Ok::<(), String>(());
@@ -417,7 +504,8 @@ async fn run_session(
             }
 
             let _ = termination_tx.send(true);
-            flush_current_utterance(&callback, &utterance_buffer, &last_partial).await;
+            flush_current_utterance(&callback, &utterance_buffer, &last_partial, &translations)
+                .await;
             if eos_sent.load(Ordering::SeqCst) || stop_requested.load(Ordering::SeqCst) {
                 Ok::<(), String>(())
             } else {
@@ -440,8 +528,13 @@ fn describe_close_frame(vendor: &str, frame: Option<&tungstenite::protocol::Clos
     }
 }
 
-fn build_start_recognition_payload(language: &str, sample_rate: u32) -> Value {
-    json!({
+fn build_start_recognition_payload(
+    language: &str,
+    vocabulary: &[String],
+    translation_targets: &[String],
+    sample_rate: u32,
+) -> Value {
+    let mut payload = json!({
         "message": "StartRecognition",
         "audio_format": {
             "type": "raw",
@@ -456,7 +549,137 @@ fn build_start_recognition_payload(language: &str, sample_rate: u32) -> Value {
                 "end_of_utterance_silence_trigger": END_OF_UTTERANCE_SILENCE_TRIGGER
             }
         }
-    })
+    });
+
+    if !vocabulary.is_empty() {
+        payload["transcription_config"]["additional_vocab"] = vocabulary
+            .iter()
+            .map(|term| json!({ "content": term }))
+            .collect();
+    }
+    if !translation_targets.is_empty() {
+        // Only finals: partial translations would churn the parallel stream.
+        payload["translation_config"] = json!({
+            "target_languages": translation_targets,
+            "enable_partials": false
+        });
+    }
+    payload
+}
+
+/// Speechmatics codes for the translation targets, minus the source language.
+fn speechmatics_translation_targets(targets: &[String], source_language: &str) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for code in targets
+        .iter()
+        .map(|target| speechmatics_language_code(target))
+    {
+        if code != source_language && !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+/// Final translations wait for the utterance they belong to; ones for audio
+/// that was already committed are released straight away.
+#[derive(Default)]
+struct PendingTranslations {
+    transcript_end: f64,
+    committed_until: f64,
+    pending: Vec<(String, String)>,
+}
+
+impl PendingTranslations {
+    fn note_transcript(&mut self, value: &Value) {
+        if let Some(end_time) = result_time(value, "end_time", f64::max) {
+            self.transcript_end = self.transcript_end.max(end_time);
+        }
+    }
+
+    fn push(
+        &mut self,
+        language: String,
+        text: String,
+        start_time: Option<f64>,
+    ) -> Option<(String, String)> {
+        if start_time.is_some_and(|start_time| start_time < self.committed_until) {
+            return Some((language, text));
+        }
+        self.pending.push((language, text));
+        None
+    }
+
+    fn commit(&mut self) -> Vec<(String, String)> {
+        self.committed_until = self.transcript_end;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+fn result_time(value: &Value, key: &str, pick: fn(f64, f64) -> f64) -> Option<f64> {
+    value
+        .get("results")
+        .and_then(|results| results.as_array())?
+        .iter()
+        .filter_map(|result| result.get(key).and_then(|entry| entry.as_f64()))
+        .reduce(pick)
+}
+
+/// Maps the unified setting onto a Speechmatics language code. Mixed speech
+/// needs one of the bilingual packs such as `cmn_en`.
+fn speechmatics_language(language: &LanguageSetting) -> String {
+    match language {
+        LanguageSetting::VendorDefault => DEFAULT_LANGUAGE.to_string(),
+        LanguageSetting::Fixed(language) => speechmatics_language_code(language),
+        LanguageSetting::AutoDetect(_) | LanguageSetting::CodeSwitching(_) => {
+            if let Some((pack, _, _)) = BILINGUAL_PACKS
+                .iter()
+                .find(|(_, first, second)| language.covers_pair(first, second))
+            {
+                return pack.to_string();
+            }
+            if language.candidate_languages().is_empty() {
+                return DEFAULT_BILINGUAL_PACK.to_string();
+            }
+            let fallback = speechmatics_language_code(&language.candidate_languages()[0]);
+            eprintln!(
+                "Speechmatics has no bilingual pack for {:?}; using {fallback}",
+                language.candidate_languages()
+            );
+            fallback
+        }
+    }
+}
+
+fn speechmatics_language_code(language: &str) -> String {
+    let trimmed = language.trim();
+    // Already a bilingual pack name such as `cmn_en`.
+    if let Some((pack, _, _)) = BILINGUAL_PACKS
+        .iter()
+        .find(|(pack, _, _)| pack.eq_ignore_ascii_case(trimmed))
+    {
+        return pack.to_string();
+    }
+    match primary_subtag(trimmed).as_str() {
+        "zh" => "cmn".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn extract_detected_language(value: &Value) -> Option<String> {
+    value
+        .get("results")
+        .and_then(|results| results.as_array())?
+        .iter()
+        .filter_map(|result| {
+            result
+                .get("alternatives")?
+                .get(0)?
+                .get("language")?
+                .as_str()
+        })
+        .next()
+        .map(ToString::to_string)
 }
 
 fn extract_payload_text(value: &Value) -> Option<String> {
@@ -517,10 +740,14 @@ async fn flush_current_utterance(
     callback: &PcmCallback,
     utterance_buffer: &Arc<AsyncMutex<String>>,
     last_partial: &Arc<AsyncMutex<Option<String>>>,
+    translations: &Arc<AsyncMutex<PendingTranslations>>,
 ) {
     if let Some(text) = flush_last_partial_as_final(utterance_buffer, last_partial).await {
         emit_commit(callback, "SpeechMatics", text);
     }
+    for (language, text) in translations.lock().await.commit() {
+        emit_translation(callback, "SpeechMatics", &language, text);
+    }
 }
 
 fn append_utterance_segment(buffer: &mut String, segment: &str) {
@@ -614,25 +841,127 @@ fn is_spacing_punctuation(ch: char) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        append_utterance_segment, build_start_recognition_payload, extract_payload_text,
-        merge_segments,
+        PendingTranslations, append_utterance_segment, build_start_recognition_payload,
+        extract_detected_language, extract_payload_text, merge_segments, result_time,
+        speechmatics_language, speechmatics_translation_targets,
     };
+    use crate::transcript_vendors::language::LanguageSetting;
     use serde_json::json;
 
     #[test]
     fn start_payload_includes_conversation_config_and_partials() {
-        let payload = build_start_recognition_payload("cmn", 16_000);
+        let payload = build_start_recognition_payload("cmn", &[], &[], 16_000);
 
         assert_eq!(payload["message"], "StartRecognition");
         assert_eq!(payload["audio_format"]["sample_rate"], 16_000);
         assert_eq!(payload["transcription_config"]["language"], "cmn");
         assert_eq!(payload["transcription_config"]["enable_partials"], true);
+        assert!(payload["transcription_config"]["additional_vocab"].is_null());
         assert_eq!(
             payload["transcription_config"]["conversation_config"]["end_of_utterance_silence_trigger"],
             0.5
         );
     }
 
+    #[test]
+    fn language_setting_maps_to_speechmatics_codes() {
+        let pair = vec!["zh".to_string(), "en".to_string()];
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::VendorDefault),
+            "cmn"
+        );
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::Fixed("zh_CN".to_string())),
+            "cmn"
+        );
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::Fixed("cmn_en".to_string())),
+            "cmn_en"
+        );
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::CodeSwitching(pair.clone())),
+            "cmn_en"
+        );
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::AutoDetect(pair)),
+            "cmn_en"
+        );
+        assert_eq!(
+            speechmatics_language(&LanguageSetting::CodeSwitching(vec![
+                "fr".to_string(),
+                "de".to_string()
+            ])),
+            "fr"
+        );
+    }
+
+    #[test]
+    fn extract_detected_language_reads_result_alternatives() {
+        let value = json!({
+            "message": "AddTranscript",
+            "results": [
+                { "alternatives": [{ "content": "你好", "language": "cmn" }] }
+            ]
+        });
+        assert_eq!(extract_detected_language(&value).as_deref(), Some("cmn"));
+    }
+
+    #[test]
+    fn start_payload_maps_vocabulary_to_additional_vocab() {
+        let vocabulary = vec!["Kubernetes".to_string(), "Tokio".to_string()];
+        let payload = build_start_recognition_payload("en", &vocabulary, &[], 16_000);
+
+        assert_eq!(
+            payload["transcription_config"]["additional_vocab"],
+            json!([{ "content": "Kubernetes" }, { "content": "Tokio" }])
+        );
+    }
+
+    #[test]
+    fn start_payload_requests_final_translations_without_source_language() {
+        let targets = speechmatics_translation_targets(
+            &["en".to_string(), "zh-CN".to_string(), "ja".to_string()],
+            "cmn",
+        );
+        assert_eq!(targets, vec!["en", "ja"]);
+
+        let payload = build_start_recognition_payload("cmn", &[], &targets, 16_000);
+        assert_eq!(
+            payload["translation_config"],
+            json!({ "target_languages": ["en", "ja"], "enable_partials": false })
+        );
+    }
+
+    #[test]
+    fn translations_wait_for_their_utterance_commit() {
+        let mut translations = PendingTranslations::default();
+        translations.note_transcript(&json!({
+            "results": [{ "start_time": 0.2, "end_time": 1.4 }]
+        }));
+        assert_eq!(
+            translations.push("en".to_string(), "Hello".to_string(), Some(0.2)),
+            None
+        );
+        assert_eq!(
+            translations.commit(),
+            vec![("en".to_string(), "Hello".to_string())]
+        );
+
+        // A late translation for audio that was already committed is released at once.
+        assert_eq!(
+            translations.push("en".to_string(), "again".to_string(), Some(0.9)),
+            Some(("en".to_string(), "again".to_string()))
+        );
+        assert_eq!(
+            result_time(
+                &json!({ "results": [{ "end_time": 2.0 }, { "end_time": 3.5 }] }),
+                "end_time",
+                f64::max
+            ),
+            Some(3.5)
+        );
+    }
+
     #[test]
     fn extract_payload_text_prefers_transcript_metadata() {
         let value = json!({
@@ -0,0 +1,420 @@
+//! Short-lived vendor credentials. With a token service configured the client
+//! never holds vendor master keys: every session asks the service for a token
+//! scoped to that one connection.
+//!
+//! Token service protocol: `POST {url}` with
+//! `{"vendor": "assemblyai", "expiresInSeconds": 600, "sessionConfig": {..}}`
+//! (and `Authorization: Bearer {auth}` when configured) answers
+//! `{"token": ".."}`, or `{"url": ".."}` for Gladia, whose token is the
+//! pre-authorised live session itself.
+
+use crate::provider_config::{
+    TranscriptRuntimeConfig, resolve_optional_bool, resolve_optional_string,
+};
+use crate::proxy::{ProxySettings, http_client};
+use crate::transcript_vendors::TranscriptVendors;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{Value, json};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+// AssemblyAI caps streaming tokens at 600 seconds; a session only needs the
+// token to open its websocket, so the others use the same window.
+pub const DEFAULT_TOKEN_TTL_SECS: u32 = 600;
+const ASSEMBLY_TOKEN_URL: &str = "https://streaming.assemblyai.com/v3/token";
+const DEEPGRAM_GRANT_URL: &str = "https://api.deepgram.com/v1/auth/grant";
+const SPEECHMATICS_TEMP_KEY_URL: &str = "https://mp.speechmatics.com/v1/api_keys?type=rt";
+
+/// What a vendor session authenticates its websocket with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VendorCredential {
+    /// Long-lived key from the settings or the environment.
+    ApiKey(String),
+    /// Short-lived token minted for this session.
+    Token(String),
+    /// Gladia live session URL that already carries its token.
+    SessionUrl(String),
+}
+
+pub struct TokenRequest<'a> {
+    pub vendor: TranscriptVendors,
+    pub expires_in_secs: u32,
+    /// Gladia's live session body; the service creates the session with it.
+    pub session_config: Option<&'a Value>,
+}
+
+pub type TokenFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<VendorCredential, String>> + Send + 'a>>;
+
+/// Mints the credential a session connects with. Implement this to plug in
+/// a different token source than the built-in ones.
+pub trait TokenFetcher: Send + Sync {
+    fn fetch<'a>(&'a self, request: TokenRequest<'a>) -> TokenFuture<'a>;
+}
+
+/// Hands out the configured key unchanged; the behaviour without a broker.
+pub struct StaticKeyFetcher {
+    api_key: String,
+}
+
+impl TokenFetcher for StaticKeyFetcher {
+    fn fetch<'a>(&'a self, _request: TokenRequest<'a>) -> TokenFuture<'a> {
+        Box::pin(async move { Ok(VendorCredential::ApiKey(self.api_key.clone())) })
+    }
+}
+
+/// Exchanges the local key for a per-session vendor token, so the key only
+/// ever reaches the vendor's token endpoint and never a streaming socket.
+pub struct VendorGrantFetcher {
+    api_key: String,
+    client: Client,
+}
+
+impl TokenFetcher for VendorGrantFetcher {
+    fn fetch<'a>(&'a self, request: TokenRequest<'a>) -> TokenFuture<'a> {
+        Box::pin(async move {
+            let ttl = request.expires_in_secs;
+            let (builder, field) = match request.vendor {
+                TranscriptVendors::AssemblyAI => (
+                    self.client
+                        .get(format!("{ASSEMBLY_TOKEN_URL}?expires_in_seconds={ttl}"))
+                        .header("Authorization", &self.api_key),
+                    "token",
+                ),
+                TranscriptVendors::DeepGram => (
+                    self.client
+                        .post(DEEPGRAM_GRANT_URL)
+                        .header("Authorization", format!("Token {}", self.api_key))
+                        .json(&json!({ "ttl_seconds": ttl })),
+                    "access_token",
+                ),
+                TranscriptVendors::SpeechMatics => (
+                    self.client
+                        .post(SPEECHMATICS_TEMP_KEY_URL)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .json(&json!({ "ttl": ttl })),
+                    "key_value",
+                ),
+                // Gladia sessions are already created server-side with the key
+                // and streamed through a tokenised URL.
+                _ => return Ok(VendorCredential::ApiKey(self.api_key.clone())),
+            };
+
+            let value = send_token_request(builder, request.vendor).await?;
+            read_string_field(&value, field)
+                .map(VendorCredential::Token)
+                .ok_or_else(|| format!("{} token response has no {field}", request.vendor.as_str()))
+        })
+    }
+}
+
+/// Asks a local or company token service, which keeps the master keys.
+pub struct TokenServiceFetcher {
+    url: String,
+    auth: Option<String>,
+    client: Client,
+}
+
+impl TokenServiceFetcher {
+    /// `client` should come from `http_client` so the proxy settings apply.
+    pub fn with_client(url: String, auth: Option<String>, client: Client) -> Self {
+        Self { url, auth, client }
+    }
+}
+
+impl TokenFetcher for TokenServiceFetcher {
+    fn fetch<'a>(&'a self, request: TokenRequest<'a>) -> TokenFuture<'a> {
+        Box::pin(async move {
+            let mut builder = self.client.post(&self.url).json(&json!({
+                "vendor": request.vendor.as_str(),
+                "expiresInSeconds": request.expires_in_secs,
+                "sessionConfig": request.session_config,
+            }));
+            if let Some(auth) = self.auth.as_deref() {
+                builder = builder.header("Authorization", format!("Bearer {auth}"));
+            }
+
+            let value = send_token_request(builder, request.vendor).await?;
+            if let Some(url) = read_string_field(&value, "url") {
+                return Ok(VendorCredential::SessionUrl(url));
+            }
+            read_string_field(&value, "token")
+                .map(VendorCredential::Token)
+                .ok_or_else(|| {
+                    format!(
+                        "Token service response for {} has neither token nor url",
+                        request.vendor.as_str()
+                    )
+                })
+        })
+    }
+}
+
+/// A vendor session's way to its credential, resolved once at start.
+#[derive(Clone)]
+pub struct CredentialSource {
+    vendor: TranscriptVendors,
+    fetcher: Arc<dyn TokenFetcher>,
+}
+
+impl CredentialSource {
+    pub fn with_fetcher(vendor: TranscriptVendors, fetcher: Arc<dyn TokenFetcher>) -> Self {
+        Self { vendor, fetcher }
+    }
+
+    /// Token service when configured; otherwise the local key, exchanged for
+    /// a vendor token when `asr_vendor_tokens` is on. Only a missing key with
+    /// no token service is an error.
+    pub fn resolve(
+        vendor: TranscriptVendors,
+        transcript_config: &TranscriptRuntimeConfig,
+        api_key: Option<String>,
+        key_label: &str,
+    ) -> Result<Self, String> {
+        let proxies = ProxySettings::from_transcript_config(transcript_config);
+        if let Some(url) = resolve_optional_string(
+            transcript_config.asr_token_service_url.as_deref(),
+            &["ASR_TOKEN_SERVICE_URL"],
+        ) {
+            let auth = resolve_optional_string(
+                transcript_config.asr_token_service_auth.as_deref(),
+                &["ASR_TOKEN_SERVICE_AUTH"],
+            );
+            let client = http_client(proxies.proxy_for(vendor.as_str(), &url).as_deref())?;
+            return Ok(Self::with_fetcher(
+                vendor,
+                Arc::new(TokenServiceFetcher::with_client(url, auth, client)),
+            ));
+        }
+
+        let api_key = api_key.ok_or_else(|| {
+            format!(
+                "缺少 {key_label} 配置，请在设置中填写，或通过 ASR_TOKEN_SERVICE_URL 配置令牌服务"
+            )
+        })?;
+        let vendor_tokens =
+            resolve_optional_bool(transcript_config.asr_vendor_tokens, &["ASR_VENDOR_TOKENS"])
+                .unwrap_or(false);
+        let fetcher: Arc<dyn TokenFetcher> = if vendor_tokens {
+            let grant_url = vendor_grant_url(vendor).unwrap_or_default();
+            Arc::new(VendorGrantFetcher {
+                api_key,
+                client: http_client(proxies.proxy_for(vendor.as_str(), grant_url).as_deref())?,
+            })
+        } else {
+            Arc::new(StaticKeyFetcher { api_key })
+        };
+
+        Ok(Self::with_fetcher(vendor, fetcher))
+    }
+
+    pub async fn fetch(&self, session_config: Option<&Value>) -> Result<VendorCredential, String> {
+        self.fetcher
+            .fetch(TokenRequest {
+                vendor: self.vendor,
+                expires_in_secs: DEFAULT_TOKEN_TTL_SECS,
+                session_config,
+            })
+            .await
+            .map_err(|e| format!("Failed to obtain {} credential: {e}", self.vendor.as_str()))
+    }
+}
+
+fn vendor_grant_url(vendor: TranscriptVendors) -> Option<&'static str> {
+    match vendor {
+        TranscriptVendors::AssemblyAI => Some(ASSEMBLY_TOKEN_URL),
+        TranscriptVendors::DeepGram => Some(DEEPGRAM_GRANT_URL),
+        TranscriptVendors::SpeechMatics => Some(SPEECHMATICS_TEMP_KEY_URL),
+        _ => None,
+    }
+}
+
+/// Vendors whose sessions can run on brokered tokens.
+pub fn supports_token_broker(vendor: TranscriptVendors) -> bool {
+    matches!(
+        vendor,
+        TranscriptVendors::AssemblyAI
+            | TranscriptVendors::DeepGram
+            | TranscriptVendors::SpeechMatics
+            | TranscriptVendors::GlaDia
+    )
+}
+
+/// True when `vendor` gets its credentials from a token service, so no key
+/// needs to be configured locally.
+pub fn uses_token_service(
+    vendor: TranscriptVendors,
+    transcript_config: &TranscriptRuntimeConfig,
+) -> bool {
+    supports_token_broker(vendor)
+        && resolve_optional_string(
+            transcript_config.asr_token_service_url.as_deref(),
+            &["ASR_TOKEN_SERVICE_URL"],
+        )
+        .is_some()
+}
+
+async fn send_token_request(
+    builder: RequestBuilder,
+    vendor: TranscriptVendors,
+) -> Result<Value, String> {
+    let vendor = vendor.as_str();
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| format!("{vendor} token request failed: {e}"))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Failed to read error body".to_string());
+        return Err(format!("{vendor} token request failed ({status}): {body}"));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse {vendor} token response: {e}"))
+}
+
+fn read_string_field(value: &Value, field: &str) -> Option<String> {
+    value
+        .get(field)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CredentialSource, VendorCredential, uses_token_service};
+    use crate::provider_config::TranscriptRuntimeConfig;
+    use crate::transcript_vendors::TranscriptVendors;
+    use serde_json::json;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0_u8; 4096];
+            loop {
+                let read = socket.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, payload)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if payload.len() >= length {
+                        break;
+                    }
+                }
+            }
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+        (url, handle)
+    }
+
+    fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn token_service_replaces_the_local_key() {
+        let (url, server) = serve_once(r#"{"token":"temp-123"}"#);
+        let config = TranscriptRuntimeConfig {
+            asr_token_service_url: Some(url),
+            asr_token_service_auth: Some("employee".to_string()),
+            ..TranscriptRuntimeConfig::default()
+        };
+        assert!(uses_token_service(TranscriptVendors::AssemblyAI, &config));
+        assert!(!uses_token_service(TranscriptVendors::RevAI, &config));
+
+        let source =
+            CredentialSource::resolve(TranscriptVendors::AssemblyAI, &config, None, "KEY").unwrap();
+        let credential = block_on(source.fetch(None)).unwrap();
+        assert_eq!(credential, VendorCredential::Token("temp-123".to_string()));
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /token"));
+        assert!(request.contains("Bearer employee"));
+        assert!(request.contains(r#""vendor":"assemblyai""#));
+    }
+
+    #[test]
+    fn gladia_service_returns_a_session_url() {
+        let (url, server) = serve_once(r#"{"url":"wss://live/abc?token=t"}"#);
+        let config = TranscriptRuntimeConfig {
+            asr_token_service_url: Some(url),
+            ..TranscriptRuntimeConfig::default()
+        };
+        let source =
+            CredentialSource::resolve(TranscriptVendors::GlaDia, &config, None, "KEY").unwrap();
+        let body = json!({ "model": "solaria-1" });
+        let credential = block_on(source.fetch(Some(&body))).unwrap();
+        assert_eq!(
+            credential,
+            VendorCredential::SessionUrl("wss://live/abc?token=t".to_string())
+        );
+        assert!(
+            server
+                .join()
+                .unwrap()
+                .contains(r#""sessionConfig":{"model":"solaria-1"}"#)
+        );
+    }
+
+    #[test]
+    fn local_key_is_required_without_a_token_service() {
+        if std::env::var("ASR_TOKEN_SERVICE_URL").is_ok() {
+            return;
+        }
+        let config = TranscriptRuntimeConfig::default();
+        let err = CredentialSource::resolve(
+            TranscriptVendors::SpeechMatics,
+            &config,
+            None,
+            "SPEECHMATICS_API_KEY",
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("SPEECHMATICS_API_KEY"));
+
+        let source = CredentialSource::resolve(
+            TranscriptVendors::SpeechMatics,
+            &config,
+            Some("key".to_string()),
+            "SPEECHMATICS_API_KEY",
+        )
+        .unwrap();
+        if std::env::var("ASR_VENDOR_TOKENS").is_err() {
+            assert_eq!(
+                block_on(source.fetch(None)).unwrap(),
+                VendorCredential::ApiKey("key".to_string())
+            );
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Shared custom vocabulary (jargon, product and people names) that every
+//! vendor translates into its own boosting option.
+
+use crate::provider_config::{TranscriptRuntimeConfig, resolve_optional_string};
+
+/// Most vendors cap boosted terms around here (AssemblyAI and Deepgram at 100).
+pub const MAX_VOCABULARY_TERMS: usize = 100;
+const MAX_TERM_CHARS: usize = 50;
+
+/// Terms are separated by commas, semicolons or newlines so multi-word
+/// phrases like "Ruby on Rails" stay intact.
+pub fn parse_vocabulary(raw: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in raw
+        .split([',', ';', '\n', '，', '；'])
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+    {
+        if term.chars().count() > MAX_TERM_CHARS {
+            eprintln!("Skipping vocabulary term longer than {MAX_TERM_CHARS} chars: {term}");
+            continue;
+        }
+        if terms.iter().any(|known| known.eq_ignore_ascii_case(term)) {
+            continue;
+        }
+        if terms.len() == MAX_VOCABULARY_TERMS {
+            eprintln!("Custom vocabulary truncated to {MAX_VOCABULARY_TERMS} terms");
+            break;
+        }
+        terms.push(term.to_string());
+    }
+    terms
+}
+
+pub fn resolve_vocabulary(transcript_config: &TranscriptRuntimeConfig) -> Vec<String> {
+    resolve_optional_string(
+        transcript_config.asr_vocabulary.as_deref(),
+        &["ASR_VOCABULARY"],
+    )
+    .map(|raw| parse_vocabulary(&raw))
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_VOCABULARY_TERMS, parse_vocabulary};
+
+    #[test]
+    fn parse_vocabulary_splits_trims_and_dedupes() {
+        assert_eq!(
+            parse_vocabulary("Kubernetes, gRPC;\nRuby on Rails，kubernetes,, Tokio"),
+            vec!["Kubernetes", "gRPC", "Ruby on Rails", "Tokio"]
+        );
+    }
+
+    #[test]
+    fn parse_vocabulary_caps_term_count_and_length() {
+        let raw = (0..150)
+            .map(|index| format!("term{index}"))
+            .chain(std::iter::once("x".repeat(80)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let terms = parse_vocabulary(&raw);
+        assert_eq!(terms.len(), MAX_VOCABULARY_TERMS);
+        assert_eq!(terms.last().map(String::as_str), Some("term99"));
+    }
+}
@@ -0,0 +1,699 @@
+#![allow(clippy::collapsible_if)]
+
+pub(super) mod codec;
+
+///https://www.volcengine.com/docs/6561/1354869
+use crate::provider_config::{
+    TranscriptRuntimeConfig, VOLCENGINE_ASR_ACCESS_ENV_KEYS, VOLCENGINE_ASR_APP_ENV_KEYS,
+    resolve_required_string, resolve_string_or_default,
+};
+use crate::proxy::{ProxySettings, connect_websocket};
+use crate::transcript_vendors::language::{LanguageSetting, primary_subtag};
+use crate::transcript_vendors::resample::StreamResampler;
+use crate::transcript_vendors::{
+    PcmCallback, StatusCallback, StreamingTranscriber, TranscriptVendors, emit_commit, emit_draft,
+    report_audio_sent, report_keepalive_sent,
+};
+use codec::{ServerFrame, decode_server_frame, encode_audio_request, encode_full_client_request};
+use futures_util::{SinkExt, StreamExt, future::try_join};
+use rand::{RngExt, rng as thread_rng};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use tauri::http::Uri;
+use tokio::runtime::Runtime;
+use tokio::sync::{Mutex as AsyncMutex, mpsc, mpsc::error::TrySendError, oneshot, watch};
+use tokio::time::{self, Duration, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::{
+    client::{ClientRequestBuilder, IntoClientRequest},
+    protocol::Message,
+};
+
+const VENDOR_NAME: &str = "Volcengine";
+const DEFAULT_WS_URL: &str = "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel";
+const DEFAULT_RESOURCE_ID: &str = "volc.bigasr.sauc.duration";
+// The big-model streaming endpoint only accepts 16kHz mono pcm16.
+const TARGET_SAMPLE_RATE: u32 = 16_000;
+const HEARTBEAT_INTERVAL_SECS: u64 = 20;
+const IDLE_SILENCE_INTERVAL_SECS: u64 = 15;
+const IDLE_SILENCE_CHUNK_MS: u32 = 100;
+const FINAL_PACKET_TIMEOUT_SECS: u64 = 3;
+
+struct SessionSettings {
+    url: String,
+    app_key: String,
+    access_key: String,
+    resource_id: String,
+    language: Option<String>,
+    proxy: Option<String>,
+}
+
+pub struct VolcengineTranscriber {
+    sender: Mutex<Option<mpsc::Sender<Vec<i16>>>>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl VolcengineTranscriber {
+    pub fn start(
+        sample_rate: u32,
+        callback: PcmCallback,
+        status_callback: Option<StatusCallback>,
+        transcript_config: TranscriptRuntimeConfig,
+    ) -> Result<Self, String> {
+        let app_key = resolve_required_string(
+            transcript_config.volcengine_app_key.as_deref(),
+            VOLCENGINE_ASR_APP_ENV_KEYS,
+            "VOLCENGINE_ASR_APP_ID",
+        )?;
+        let access_key = resolve_required_string(
+            transcript_config.volcengine_access_key.as_deref(),
+            VOLCENGINE_ASR_ACCESS_ENV_KEYS,
+            "VOLCENGINE_ASR_ACCESS_TOKEN",
+        )?;
+        let resource_id = resolve_string_or_default(
+            transcript_config.volcengine_resource_id.as_deref(),
+            &["VOLCENGINE_ASR_RESOURCE_ID"],
+            DEFAULT_RESOURCE_ID,
+        );
+        let url = resolve_string_or_default(
+            transcript_config.volcengine_ws_url.as_deref(),
+            &["VOLCENGINE_ASR_WS_URL"],
+            DEFAULT_WS_URL,
+        );
+        let proxy = ProxySettings::from_transcript_config(&transcript_config)
+            .proxy_for(TranscriptVendors::Volcengine.as_str(), &url);
+        // Volcengine has no vendor language field; only the unified mode applies.
+        let language = volcengine_language(&LanguageSetting::resolve(&transcript_config, None));
+        let settings = SessionSettings {
+            url,
+            app_key,
+            access_key,
+            resource_id,
+            language,
+            proxy,
+        };
+
+        let (sender, receiver) = mpsc::channel::<Vec<i16>>(64);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let stop_requested = Arc::new(AtomicBool::new(false));
+        let stop_requested_for_thread = stop_requested.clone();
+
+        let handle = thread::spawn(move || {
+            let runtime = Runtime::new().expect("Failed to build Tokio runtime");
+            if let Err(err) = runtime.block_on(run_session(
+                settings,
+                sample_rate,
+                callback,
+                receiver,
+                shutdown_rx,
+                stop_requested_for_thread,
+            )) {
+                if let Some(cb) = status_callback.as_ref() {
+                    cb(format!("volcengine: {err}"));
+                }
+                eprintln!("Volcengine streaming error: {err}");
+            }
+        });
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            shutdown: Mutex::new(Some(shutdown_tx)),
+            handle: Mutex::new(Some(handle)),
+            stop_requested,
+        })
+    }
+
+    pub fn enqueue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        let sender = self
+            .sender
+            .lock()
+            .unwrap()
+            .as_ref()
+            .cloned()
+            .ok_or_else(|| "Volcengine transcriber is not running".to_string())?;
+
+        match sender.try_send(chunk) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_chunk)) => Err(
+                "Failed to queue PCM chunk for Volcengine: channel is full (consumer stalled)"
+                    .into(),
+            ),
+            Err(TrySendError::Closed(_chunk)) => {
+                Err("Failed to queue PCM chunk for Volcengine: channel closed".into())
+            }
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        self.sender.lock().unwrap().take();
+        if let Some(shutdown) = self.shutdown.lock().unwrap().take() {
+            let _ = shutdown.send(());
+        }
+
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for VolcengineTranscriber {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl StreamingTranscriber for VolcengineTranscriber {
+    fn queue_chunk(&self, chunk: Vec<i16>) -> Result<(), String> {
+        self.enqueue_chunk(chunk)
+    }
+
+    fn get_vendor_name(&self) -> String {
+        VENDOR_NAME.to_string()
+    }
+
+    fn shutdown(&self) {
+        self.stop();
+        println!("Volcengine websocket shutdown invoked");
+    }
+}
+
+async fn run_session(
+    settings: SessionSettings,
+    sample_rate: u32,
+    callback: PcmCallback,
+    mut audio_rx: mpsc::Receiver<Vec<i16>>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    stop_requested: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let uri: Uri = settings
+        .url
+        .parse()
+        .map_err(|e| format!("Failed to parse Volcengine streaming URI: {e}"))?;
+    let builder = ClientRequestBuilder::new(uri)
+        .with_header("X-Api-App-Key", settings.app_key.clone())
+        .with_header("X-Api-Access-Key", settings.access_key.clone())
+        .with_header("X-Api-Resource-Id", settings.resource_id.clone())
+        .with_header("X-Api-Connect-Id", generate_connect_id());
+    let client_request = builder
+        .into_client_request()
+        .map_err(|e| format!("Failed to build Volcengine websocket request: {e}"))?;
+
+    let (ws_stream, _) = connect_websocket(client_request, settings.proxy.as_deref())
+        .await
+        .map_err(|e| format!("Failed to connect to Volcengine: {e}"))?;
+
+    let (mut sink, mut stream) = ws_stream.split();
+    let request_frame = encode_full_client_request(
+        &build_full_client_request_payload(settings.language.as_deref()),
+        1,
+    )?;
+    sink.send(Message::Binary(request_frame.into()))
+        .await
+        .map_err(|e| format!("Failed to send Volcengine full client request: {e}"))?;
+
+    let (termination_tx, mut termination_rx) = watch::channel(false);
+    let tracker = Arc::new(AsyncMutex::new(UtteranceTracker::default()));
+    let last_packet_sent = Arc::new(AtomicBool::new(false));
+
+    let send_audio = {
+        let last_packet_sent = last_packet_sent.clone();
+        async move {
+            let mut sequence = 1_i32;
+            let mut heartbeat = time::interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
+            heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            heartbeat.tick().await;
+
+            let idle_keepalive_samples =
+                ((TARGET_SAMPLE_RATE as u64 * IDLE_SILENCE_CHUNK_MS as u64) / 1000).max(1) as usize;
+            let idle_keepalive_chunk = vec![0_u8; idle_keepalive_samples * 2];
+            let idle_keepalive = time::sleep(Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+            tokio::pin!(idle_keepalive);
+
+            let mut resampler = StreamResampler::new(sample_rate, TARGET_SAMPLE_RATE);
+            let mut should_send_last_packet = true;
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    result = termination_rx.changed() => {
+                        if result.is_err() || *termination_rx.borrow() {
+                            should_send_last_packet = false;
+                            break;
+                        }
+                    }
+                    chunk = audio_rx.recv() => match chunk {
+                        Some(samples) => {
+                            let samples = resampler.process(&samples);
+                            if samples.is_empty() {
+                                continue;
+                            }
+                            sequence += 1;
+                            let frame = encode_audio_request(&pcm_to_bytes(&samples), sequence, false)?;
+                            sink.send(Message::Binary(frame.into()))
+                                .await
+                                .map_err(|e| format!("Failed to send audio chunk to Volcengine: {e}"))?;
+                            report_audio_sent(TranscriptVendors::Volcengine, samples.len(), TARGET_SAMPLE_RATE);
+                            idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                        }
+                        None => break,
+                    },
+                    _ = heartbeat.tick() => {
+                        sink.send(Message::Ping(Vec::new().into()))
+                            .await
+                            .map_err(|e| format!("Failed to send Volcengine heartbeat ping: {e}"))?;
+                    }
+                    _ = &mut idle_keepalive => {
+                        sequence += 1;
+                        let frame = encode_audio_request(&idle_keepalive_chunk, sequence, false)?;
+                        sink.send(Message::Binary(frame.into()))
+                            .await
+                            .map_err(|e| format!("Failed to send Volcengine idle silence chunk: {e}"))?;
+                        report_keepalive_sent(TranscriptVendors::Volcengine, idle_keepalive_samples, TARGET_SAMPLE_RATE);
+                        idle_keepalive.as_mut().reset(time::Instant::now() + Duration::from_secs(IDLE_SILENCE_INTERVAL_SECS));
+                    }
+                }
+            }
+
+            if should_send_last_packet {
+                sequence += 1;
+                let frame = encode_audio_request(&[], sequence, true)?;
+                sink.send(Message::Binary(frame.into()))
+                    .await
+                    .map_err(|e| format!("Failed to send Volcengine last packet: {e}"))?;
+                last_packet_sent.store(true, Ordering::SeqCst);
+
+                // The server answers the negative sequence with its final result.
+                let _ = time::timeout(Duration::from_secs(FINAL_PACKET_TIMEOUT_SECS), async {
+                    while !*termination_rx.borrow() {
+                        if termination_rx.changed().await.is_err() {
+                            break;
+                        }
+                    }
+                })
+                .await;
+            }
+
+            sink.close()
+                .await
+                .map_err(|e| format!("Failed to close Volcengine socket: {e}"))?;
+            Ok::<(), String>(())
+        }
+    };
+
+    let receive_events = {
+        let callback = callback.clone();
+        let termination_tx = termination_tx.clone();
+        let tracker = tracker.clone();
+        let last_packet_sent = last_packet_sent.clone();
+        let stop_requested = stop_requested.clone();
+
+        async move {
+            while let Some(message) = stream.next().await {
+                let message = match message {
+                    Ok(message) => message,
+                    Err(err) => {
+                        let _ = termination_tx.send(true);
+                        return Err(format!("Volcengine receive error: {err}"));
+                    }
+                };
+
+                match message {
+                    Message::Binary(payload) => match decode_server_frame(&payload)? {
+                        ServerFrame::Response {
+                            is_last, payload, ..
+                        } => {
+                            let update = tracker.lock().await.apply(&payload);
+                            for text in update.commits {
+                                emit_commit(&callback, VENDOR_NAME, text);
+                            }
+                            if let Some(draft) = update.draft {
+                                emit_draft(&callback, VENDOR_NAME, draft);
+                            }
+                            if is_last {
+                                flush_pending_draft(&callback, &tracker).await;
+                                let _ = termination_tx.send(true);
+                                return Ok::<(), String>(());
+                            }
+                        }
+                        ServerFrame::Error { code, message } => {
+                            let _ = termination_tx.send(true);
+                            return Err(format!(
+                                "Volcengine returned error: {message} (code={code})"
+                            ));
+                        }
+                    },
+                    Message::Close(frame) => {
+                        let _ = termination_tx.send(true);
+                        flush_pending_draft(&callback, &tracker).await;
+                        if last_packet_sent.load(Ordering::SeqCst)
+                            || stop_requested.load(Ordering::SeqCst)
+                        {
+                            return Ok::<(), String>(());
+                        }
+                        return Err(describe_close_frame("Volcengine", frame.as_ref()));
+                    }
+                    _ => {}
+                }
+            }
+
+            let _ = termination_tx.send(true);
+            flush_pending_draft(&callback, &tracker).await;
+            if last_packet_sent.load(Ordering::SeqCst) || stop_requested.load(Ordering::SeqCst) {
+                Ok::<(), String>(())
+            } else {
+                Err("Volcengine websocket closed unexpectedly without a close frame".into())
+            }
+        }
+    };
+
+    try_join(send_audio, receive_events).await?;
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TrackerUpdate {
+    commits: Vec<String>,
+    draft: Option<String>,
+}
+
+/// The server resends every utterance of the session on each response, so
+/// definite utterances are committed once by tracking their end time.
+#[derive(Default)]
+struct UtteranceTracker {
+    committed_until_ms: i64,
+    pending_draft: Option<String>,
+}
+
+impl UtteranceTracker {
+    fn apply(&mut self, payload: &Value) -> TrackerUpdate {
+        let mut update = TrackerUpdate::default();
+        let Some(utterances) = payload
+            .get("result")
+            .and_then(|result| result.get("utterances"))
+            .and_then(|entry| entry.as_array())
+        else {
+            return update;
+        };
+
+        let mut draft = String::new();
+        for utterance in utterances {
+            let text = utterance
+                .get("text")
+                .and_then(|entry| entry.as_str())
+                .map(str::trim)
+                .unwrap_or_default();
+            let end_time = utterance
+                .get("end_time")
+                .and_then(|entry| entry.as_i64())
+                .unwrap_or_default();
+            let definite = utterance
+                .get("definite")
+                .and_then(|entry| entry.as_bool())
+                .unwrap_or(false);
+
+            if definite {
+                if end_time > self.committed_until_ms {
+                    self.committed_until_ms = end_time;
+                    if !text.is_empty() {
+                        update.commits.push(text.to_string());
+                    }
+                }
+            } else if end_time <= 0 || end_time > self.committed_until_ms {
+                append_utterance_segment(&mut draft, text);
+            }
+        }
+
+        if draft.is_empty() {
+            self.pending_draft = None;
+        } else {
+            self.pending_draft = Some(draft.clone());
+            update.draft = Some(draft);
+        }
+
+        update
+    }
+
+    fn take_pending_draft(&mut self) -> Option<String> {
+        self.pending_draft.take()
+    }
+}
+
+async fn flush_pending_draft(callback: &PcmCallback, tracker: &Arc<AsyncMutex<UtteranceTracker>>) {
+    if let Some(text) = tracker.lock().await.take_pending_draft() {
+        emit_commit(callback, VENDOR_NAME, text);
+    }
+}
+
+/// The big model recognizes Chinese-English mixed speech on its own, so only a
+/// fixed language is sent; other candidates cannot be detected.
+fn volcengine_language(language: &LanguageSetting) -> Option<String> {
+    if language.is_multilingual() {
+        let unsupported = language
+            .candidate_languages()
+            .iter()
+            .filter(|candidate| !matches!(primary_subtag(candidate).as_str(), "zh" | "en"))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !unsupported.is_empty() {
+            eprintln!(
+                "Volcengine only auto-detects Chinese and English; {} will not be recognized",
+                unsupported.join(", ")
+            );
+        }
+        return None;
+    }
+    language.fixed_language().map(ToString::to_string)
+}
+
+fn build_full_client_request_payload(language: Option<&str>) -> Value {
+    let mut payload = json!({
+        "user": {
+            "uid": "audio-courier"
+        },
+        "audio": {
+            "format": "pcm",
+            "codec": "raw",
+            "rate": TARGET_SAMPLE_RATE,
+            "bits": 16,
+            "channel": 1
+        },
+        "request": {
+            "model_name": "bigmodel",
+            "enable_itn": true,
+            "enable_punc": true,
+            "show_utterances": true,
+            "result_type": "full"
+        }
+    });
+    if let Some(language) = language {
+        payload["audio"]["language"] = json!(language);
+    }
+    payload
+}
+
+fn describe_close_frame(vendor: &str, frame: Option<&tungstenite::protocol::CloseFrame>) -> String {
+    match frame {
+        Some(frame) => format!(
+            "{vendor} websocket closed unexpectedly (code={:?}, reason={})",
+            frame.code, frame.reason
+        ),
+        None => format!("{vendor} websocket closed unexpectedly without a close frame"),
+    }
+}
+
+fn generate_connect_id() -> String {
+    let mut rng = thread_rng();
+    let value = rng.random::<u128>();
+    let hex = format!("{value:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn pcm_to_bytes(samples: &[i16]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(samples.len() * 2);
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    bytes
+}
+
+fn append_utterance_segment(buffer: &mut String, segment: &str) {
+    let segment = segment.trim();
+    if segment.is_empty() {
+        return;
+    }
+
+    if buffer.is_empty() {
+        buffer.push_str(segment);
+        return;
+    }
+
+    if should_join_without_space(buffer, segment) {
+        buffer.push_str(segment);
+    } else {
+        buffer.push(' ');
+        buffer.push_str(segment);
+    }
+}
+
+fn should_join_without_space(prefix: &str, suffix: &str) -> bool {
+    let Some(last) = prefix.chars().next_back() else {
+        return true;
+    };
+    let Some(first) = suffix.chars().next() else {
+        return true;
+    };
+
+    last.is_whitespace()
+        || first.is_whitespace()
+        || is_cjk(last)
+        || is_cjk(first)
+        || is_spacing_punctuation(last)
+        || is_spacing_punctuation(first)
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x4E00..=0x9FFF
+            | 0x3400..=0x4DBF
+            | 0x3040..=0x30FF
+            | 0xAC00..=0xD7AF
+            | 0xF900..=0xFAFF
+    )
+}
+
+fn is_spacing_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        ',' | '.'
+            | '!'
+            | '?'
+            | ':'
+            | ';'
+            | ')'
+            | ']'
+            | '}'
+            | '，'
+            | '。'
+            | '！'
+            | '？'
+            | '：'
+            | '；'
+            | '）'
+            | '】'
+            | '」'
+            | '、'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TrackerUpdate, UtteranceTracker, build_full_client_request_payload, generate_connect_id,
+        volcengine_language,
+    };
+    use crate::transcript_vendors::language::LanguageSetting;
+    use serde_json::json;
+
+    #[test]
+    fn full_client_request_asks_for_utterances() {
+        let payload = build_full_client_request_payload(None);
+        assert_eq!(payload["audio"]["rate"], 16_000);
+        assert_eq!(payload["audio"]["format"], "pcm");
+        assert!(payload["audio"].get("language").is_none());
+        assert_eq!(payload["request"]["model_name"], "bigmodel");
+        assert_eq!(payload["request"]["show_utterances"], true);
+
+        let payload = build_full_client_request_payload(Some("en-US"));
+        assert_eq!(payload["audio"]["language"], "en-US");
+    }
+
+    #[test]
+    fn only_a_fixed_language_is_sent() {
+        assert_eq!(
+            volcengine_language(&LanguageSetting::Fixed("ja-JP".to_string())).as_deref(),
+            Some("ja-JP")
+        );
+        assert_eq!(
+            volcengine_language(&LanguageSetting::CodeSwitching(vec![
+                "zh".to_string(),
+                "en".to_string()
+            ])),
+            None
+        );
+    }
+
+    #[test]
+    fn tracker_commits_definite_utterances_once() {
+        let mut tracker = UtteranceTracker::default();
+        let first = json!({
+            "result": {
+                "utterances": [
+                    { "text": "你好。", "definite": true, "end_time": 1200 },
+                    { "text": "今天", "definite": false, "end_time": 1800 }
+                ]
+            }
+        });
+        assert_eq!(
+            tracker.apply(&first),
+            TrackerUpdate {
+                commits: vec!["你好。".to_string()],
+                draft: Some("今天".to_string()),
+            }
+        );
+
+        let second = json!({
+            "result": {
+                "utterances": [
+                    { "text": "你好。", "definite": true, "end_time": 1200 },
+                    { "text": "今天天气不错。", "definite": true, "end_time": 2600 }
+                ]
+            }
+        });
+        assert_eq!(
+            tracker.apply(&second),
+            TrackerUpdate {
+                commits: vec!["今天天气不错。".to_string()],
+                draft: None,
+            }
+        );
+        assert_eq!(tracker.take_pending_draft(), None);
+    }
+
+    #[test]
+    fn tracker_keeps_last_draft_for_flush() {
+        let mut tracker = UtteranceTracker::default();
+        tracker.apply(&json!({
+            "result": { "utterances": [ { "text": "hello", "definite": false, "end_time": 900 } ] }
+        }));
+        assert_eq!(tracker.take_pending_draft(), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn tracker_ignores_payloads_without_utterances() {
+        let mut tracker = UtteranceTracker::default();
+        assert_eq!(
+            tracker.apply(&json!({ "audio_info": { "duration": 100 } })),
+            TrackerUpdate::default()
+        );
+    }
+
+    #[test]
+    fn connect_id_is_uuid_shaped() {
+        let id = generate_connect_id();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.matches('-').count(), 4);
+    }
+}
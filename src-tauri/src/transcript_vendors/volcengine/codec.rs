@@ -0,0 +1,363 @@
+//! Binary framing for the Volcengine big-model streaming ASR protocol.
+//!
+//! Every frame starts with a 4 byte header:
+//! `[version|header_size] [message_type|flags] [serialization|compression] [reserved]`,
+//! optionally followed by a big-endian `i32` sequence, then a big-endian `u32`
+//! payload size and the payload itself.
+//!
+//! https://www.volcengine.com/docs/6561/1354869
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::Value;
+use std::io::{Read, Write};
+
+const PROTOCOL_VERSION: u8 = 0b0001;
+const HEADER_SIZE_WORDS: u8 = 0b0001;
+
+const FULL_CLIENT_REQUEST: u8 = 0b0001;
+const AUDIO_ONLY_REQUEST: u8 = 0b0010;
+const FULL_SERVER_RESPONSE: u8 = 0b1001;
+const SERVER_ERROR_RESPONSE: u8 = 0b1111;
+
+const FLAG_POSITIVE_SEQUENCE: u8 = 0b0001;
+const FLAG_LAST_PACKET: u8 = 0b0010;
+const FLAG_NEGATIVE_SEQUENCE: u8 = 0b0011;
+
+const SERIALIZATION_NONE: u8 = 0b0000;
+const SERIALIZATION_JSON: u8 = 0b0001;
+const COMPRESSION_GZIP: u8 = 0b0001;
+
+#[derive(Debug, PartialEq)]
+pub enum ServerFrame {
+    Response {
+        sequence: Option<i32>,
+        is_last: bool,
+        payload: Value,
+    },
+    Error {
+        code: u32,
+        message: String,
+    },
+}
+
+pub fn encode_full_client_request(payload: &Value, sequence: i32) -> Result<Vec<u8>, String> {
+    let body = serde_json::to_vec(payload)
+        .map_err(|e| format!("Failed to serialize Volcengine request: {e}"))?;
+    encode_frame(
+        FULL_CLIENT_REQUEST,
+        FLAG_POSITIVE_SEQUENCE,
+        SERIALIZATION_JSON,
+        sequence,
+        &body,
+    )
+}
+
+/// The last packet carries a negated sequence so the server can flush.
+pub fn encode_audio_request(audio: &[u8], sequence: i32, is_last: bool) -> Result<Vec<u8>, String> {
+    let (flags, sequence) = if is_last {
+        (FLAG_NEGATIVE_SEQUENCE, -sequence.abs())
+    } else {
+        (FLAG_POSITIVE_SEQUENCE, sequence)
+    };
+    encode_frame(
+        AUDIO_ONLY_REQUEST,
+        flags,
+        SERIALIZATION_NONE,
+        sequence,
+        audio,
+    )
+}
+
+pub fn decode_server_frame(frame: &[u8]) -> Result<ServerFrame, String> {
+    if frame.len() < 4 {
+        return Err(format!("Volcengine frame too short: {} bytes", frame.len()));
+    }
+
+    let header_len = ((frame[0] & 0x0F) as usize) * 4;
+    let message_type = frame[1] >> 4;
+    let flags = frame[1] & 0x0F;
+    let serialization = frame[2] >> 4;
+    let compression = frame[2] & 0x0F;
+    let mut cursor = header_len;
+
+    match message_type {
+        FULL_SERVER_RESPONSE => {
+            let sequence = if flags & FLAG_POSITIVE_SEQUENCE != 0 {
+                let value = read_u32(frame, &mut cursor)? as i32;
+                Some(value)
+            } else {
+                None
+            };
+            let is_last = flags & FLAG_LAST_PACKET != 0;
+            let body = read_sized_payload(frame, &mut cursor)?;
+            let body = decompress(body, compression)?;
+            let payload = if body.is_empty() || serialization != SERIALIZATION_JSON {
+                Value::Null
+            } else {
+                serde_json::from_slice(&body)
+                    .map_err(|e| format!("Failed to parse Volcengine response payload: {e}"))?
+            };
+
+            Ok(ServerFrame::Response {
+                sequence,
+                is_last,
+                payload,
+            })
+        }
+        SERVER_ERROR_RESPONSE => {
+            let code = read_u32(frame, &mut cursor)?;
+            let body = read_sized_payload(frame, &mut cursor)?;
+            let body = decompress(body, compression)?;
+            Ok(ServerFrame::Error {
+                code,
+                message: String::from_utf8_lossy(&body).into_owned(),
+            })
+        }
+        other => Err(format!("Unexpected Volcengine message type: {other:#06b}")),
+    }
+}
+
+/// Server side of the framing, used by the mock server.
+#[cfg(any(test, feature = "mock-asr"))]
+pub fn encode_server_response(
+    payload: &Value,
+    sequence: i32,
+    is_last: bool,
+) -> Result<Vec<u8>, String> {
+    let body = serde_json::to_vec(payload)
+        .map_err(|e| format!("Failed to serialize Volcengine response: {e}"))?;
+    let flags = if is_last {
+        FLAG_NEGATIVE_SEQUENCE
+    } else {
+        FLAG_POSITIVE_SEQUENCE
+    };
+    encode_frame(
+        FULL_SERVER_RESPONSE,
+        flags,
+        SERIALIZATION_JSON,
+        sequence,
+        &body,
+    )
+}
+
+#[cfg(any(test, feature = "mock-asr"))]
+#[derive(Debug, PartialEq)]
+pub enum ClientFrame {
+    FullRequest(Value),
+    Audio { bytes: usize, is_last: bool },
+}
+
+/// Client frames always carry a sequence.
+#[cfg(any(test, feature = "mock-asr"))]
+pub fn decode_client_frame(frame: &[u8]) -> Result<ClientFrame, String> {
+    if frame.len() < 4 {
+        return Err(format!("Volcengine frame too short: {} bytes", frame.len()));
+    }
+
+    let header_len = ((frame[0] & 0x0F) as usize) * 4;
+    let message_type = frame[1] >> 4;
+    let flags = frame[1] & 0x0F;
+    let compression = frame[2] & 0x0F;
+    let mut cursor = header_len;
+    read_u32(frame, &mut cursor)?;
+    let body = read_sized_payload(frame, &mut cursor)?;
+    let body = decompress(body, compression)?;
+
+    match message_type {
+        FULL_CLIENT_REQUEST => serde_json::from_slice(&body)
+            .map(ClientFrame::FullRequest)
+            .map_err(|e| format!("Failed to parse Volcengine request payload: {e}")),
+        AUDIO_ONLY_REQUEST => Ok(ClientFrame::Audio {
+            bytes: body.len(),
+            is_last: flags & FLAG_LAST_PACKET != 0,
+        }),
+        other => Err(format!("Unexpected Volcengine message type: {other:#06b}")),
+    }
+}
+
+fn encode_frame(
+    message_type: u8,
+    flags: u8,
+    serialization: u8,
+    sequence: i32,
+    body: &[u8],
+) -> Result<Vec<u8>, String> {
+    let compressed = gzip(body)?;
+    let mut frame = Vec::with_capacity(12 + compressed.len());
+    frame.push((PROTOCOL_VERSION << 4) | HEADER_SIZE_WORDS);
+    frame.push((message_type << 4) | flags);
+    frame.push((serialization << 4) | COMPRESSION_GZIP);
+    frame.push(0);
+    frame.extend_from_slice(&sequence.to_be_bytes());
+    frame.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&compressed);
+    Ok(frame)
+}
+
+fn read_u32(frame: &[u8], cursor: &mut usize) -> Result<u32, String> {
+    let end = *cursor + 4;
+    let bytes = frame
+        .get(*cursor..end)
+        .ok_or_else(|| "Volcengine frame truncated".to_string())?;
+    *cursor = end;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_sized_payload<'a>(frame: &'a [u8], cursor: &mut usize) -> Result<&'a [u8], String> {
+    let size = read_u32(frame, cursor)? as usize;
+    let end = *cursor + size;
+    let body = frame
+        .get(*cursor..end)
+        .ok_or_else(|| "Volcengine frame payload truncated".to_string())?;
+    *cursor = end;
+    Ok(body)
+}
+
+fn gzip(body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder
+        .write_all(body)
+        .map_err(|e| format!("Failed to gzip Volcengine payload: {e}"))?;
+    encoder
+        .finish()
+        .map_err(|e| format!("Failed to gzip Volcengine payload: {e}"))
+}
+
+fn decompress(body: &[u8], compression: u8) -> Result<Vec<u8>, String> {
+    if compression != COMPRESSION_GZIP || body.is_empty() {
+        return Ok(body.to_vec());
+    }
+
+    let mut decoded = Vec::new();
+    GzDecoder::new(body)
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("Failed to gunzip Volcengine payload: {e}"))?;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        COMPRESSION_GZIP, ClientFrame, FULL_SERVER_RESPONSE, PROTOCOL_VERSION, SERIALIZATION_JSON,
+        SERVER_ERROR_RESPONSE, ServerFrame, decode_client_frame, decode_server_frame, decompress,
+        encode_audio_request, encode_full_client_request, encode_server_response, gzip,
+    };
+    use serde_json::json;
+
+    fn server_frame(message_type: u8, flags: u8, prefix: &[u8], body: &[u8]) -> Vec<u8> {
+        let compressed = gzip(body).unwrap();
+        let mut frame = vec![
+            (PROTOCOL_VERSION << 4) | 1,
+            (message_type << 4) | flags,
+            (SERIALIZATION_JSON << 4) | COMPRESSION_GZIP,
+            0,
+        ];
+        frame.extend_from_slice(prefix);
+        frame.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&compressed);
+        frame
+    }
+
+    #[test]
+    fn full_client_request_has_header_sequence_and_gzip_body() {
+        let payload = json!({ "request": { "model_name": "bigmodel" } });
+        let frame = encode_full_client_request(&payload, 1).unwrap();
+
+        assert_eq!(frame[..4], [0x11, 0x11, 0x11, 0x00]);
+        assert_eq!(i32::from_be_bytes(frame[4..8].try_into().unwrap()), 1);
+        let size = u32::from_be_bytes(frame[8..12].try_into().unwrap()) as usize;
+        assert_eq!(frame.len(), 12 + size);
+        let body = decompress(&frame[12..], COMPRESSION_GZIP).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn last_audio_packet_uses_negative_sequence() {
+        let frame = encode_audio_request(&[1, 2, 3, 4], 7, true).unwrap();
+        assert_eq!(frame[1], 0x23);
+        assert_eq!(frame[2], 0x01);
+        assert_eq!(i32::from_be_bytes(frame[4..8].try_into().unwrap()), -7);
+
+        let frame = encode_audio_request(&[1, 2], 8, false).unwrap();
+        assert_eq!(frame[1], 0x21);
+        assert_eq!(i32::from_be_bytes(frame[4..8].try_into().unwrap()), 8);
+    }
+
+    #[test]
+    fn decodes_server_response_with_sequence() {
+        let body = json!({ "result": { "text": "你好" } }).to_string();
+        let frame = server_frame(
+            FULL_SERVER_RESPONSE,
+            0b0011,
+            &(-3_i32).to_be_bytes(),
+            body.as_bytes(),
+        );
+
+        assert_eq!(
+            decode_server_frame(&frame).unwrap(),
+            ServerFrame::Response {
+                sequence: Some(-3),
+                is_last: true,
+                payload: json!({ "result": { "text": "你好" } }),
+            }
+        );
+    }
+
+    #[test]
+    fn mock_side_round_trips_client_and_server_frames() {
+        let payload = json!({ "request": { "model_name": "bigmodel" } });
+        let frame = encode_full_client_request(&payload, 1).unwrap();
+        assert_eq!(
+            decode_client_frame(&frame).unwrap(),
+            ClientFrame::FullRequest(payload)
+        );
+        let frame = encode_audio_request(&[0; 6], 4, true).unwrap();
+        assert_eq!(
+            decode_client_frame(&frame).unwrap(),
+            ClientFrame::Audio {
+                bytes: 6,
+                is_last: true
+            }
+        );
+
+        let payload = json!({ "result": { "text": "hi" } });
+        let frame = encode_server_response(&payload, 2, false).unwrap();
+        assert_eq!(
+            decode_server_frame(&frame).unwrap(),
+            ServerFrame::Response {
+                sequence: Some(2),
+                is_last: false,
+                payload,
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_server_error_frame() {
+        let frame = server_frame(
+            SERVER_ERROR_RESPONSE,
+            0,
+            &45_000_001_u32.to_be_bytes(),
+            b"invalid params",
+        );
+
+        assert_eq!(
+            decode_server_frame(&frame).unwrap(),
+            ServerFrame::Error {
+                code: 45_000_001,
+                message: "invalid params".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_frames() {
+        assert!(decode_server_frame(&[0x11, 0x91]).is_err());
+        assert!(decode_server_frame(&[0x11, 0x91, 0x11, 0x00, 0, 0, 0, 1, 0, 0, 0, 9]).is_err());
+    }
+}
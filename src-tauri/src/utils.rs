@@ -2,7 +2,6 @@ use crate::RESAMPLE_RATE;
 use cpal::Sample;
 use cpal::traits::DeviceTrait;
 use rubato::ResampleError;
-use std::collections::HashMap;
 use std::env;
 use std::fs::{File, OpenOptions, create_dir_all};
 use std::io::Write;
@@ -120,10 +119,12 @@ fn remove_log_file_if_exists(path: &Path) {
     }
 }
 
+/// Applies vendor keys baked in from `.env.local`; only for internal builds.
+#[cfg(feature = "embedded-env")]
 pub fn load_env_variables() {
     const ENV_CONTENT: &str = include_str!("../.env.local");
 
-    let mut vars: HashMap<String, String> = HashMap::new();
+    let mut vars: std::collections::HashMap<String, String> = std::collections::HashMap::new();
 
     for line in ENV_CONTENT.lines() {
         if line.trim().is_empty() || line.starts_with('#') {
@@ -142,6 +143,7 @@ pub fn load_env_variables() {
     }
 }
 
+#[cfg(feature = "embedded-env")]
 fn parse_line(line: &str) -> Option<(String, String)> {
     if let Some(eq_pos) = line.find('=') {
         let key = line[0..eq_pos].trim().to_string();
@@ -0,0 +1,182 @@
+//! Benchmark ASR vendors over a directory of WAVs with `.txt` references.
+//!
+//! cargo run --bin asr_bench -- <corpus_dir> --vendors deepgram,gladia [--output-dir DIR] [--tail-timeout SECS]
+//! cargo run --features mock-asr --bin asr_bench -- <corpus_dir> --vendors deepgram --mock
+//!
+//! Run from `src-tauri/tools`; real vendors read their keys from `../.env`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tauri_courier_ai_lib::TranscriptVendors;
+use tauri_courier_ai_lib::asr_bench::{
+    BenchResult, CorpusItem, load_corpus, results_csv, run_item, summarize,
+};
+use tauri_courier_ai_lib::transcript_runtime_config_from_env;
+
+const DEFAULT_TAIL_TIMEOUT_SECS: u64 = 15;
+
+struct CliArgs {
+    corpus: PathBuf,
+    vendors: Vec<TranscriptVendors>,
+    output_dir: PathBuf,
+    tail_timeout: Duration,
+    mock: bool,
+}
+
+fn usage() -> String {
+    let vendors = TranscriptVendors::ALL
+        .iter()
+        .map(TranscriptVendors::as_str)
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        "用法: asr_bench <corpus_dir> --vendors <{vendors}> [--output-dir DIR] [--tail-timeout SECS] [--mock]"
+    )
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<CliArgs, String> {
+    let mut corpus = None;
+    let mut vendors = Vec::new();
+    let mut output_dir = PathBuf::from(".");
+    let mut tail_timeout = Duration::from_secs(DEFAULT_TAIL_TIMEOUT_SECS);
+    let mut mock = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-v" | "--vendors" => {
+                let value = args.next().ok_or("--vendors 缺少参数")?;
+                for vendor in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+                    vendors.push(vendor.parse::<TranscriptVendors>()?);
+                }
+            }
+            "-o" | "--output-dir" => {
+                output_dir = PathBuf::from(args.next().ok_or("--output-dir 缺少参数")?);
+            }
+            "--tail-timeout" => {
+                let value = args.next().ok_or("--tail-timeout 缺少参数")?;
+                let secs = value
+                    .parse::<u64>()
+                    .map_err(|err| format!("--tail-timeout 解析失败: {err}"))?;
+                tail_timeout = Duration::from_secs(secs);
+            }
+            "--mock" => mock = true,
+            "-h" | "--help" => return Err(usage()),
+            _ if corpus.is_none() => corpus = Some(PathBuf::from(arg)),
+            _ => return Err(format!("无法识别的参数: {arg}\n{}", usage())),
+        }
+    }
+
+    if vendors.is_empty() {
+        return Err(format!("至少需要一个厂商\n{}", usage()));
+    }
+    Ok(CliArgs {
+        corpus: corpus.ok_or_else(usage)?,
+        vendors,
+        output_dir,
+        tail_timeout,
+        mock,
+    })
+}
+
+fn bench_item(args: &CliArgs, vendor: TranscriptVendors, item: &CorpusItem) -> BenchResult {
+    if args.mock {
+        #[cfg(feature = "mock-asr")]
+        return tauri_courier_ai_lib::asr_bench::run_item_with_mock(
+            vendor,
+            item,
+            args.tail_timeout,
+        );
+    }
+    run_item(
+        vendor,
+        item,
+        transcript_runtime_config_from_env(),
+        args.tail_timeout,
+    )
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args(std::env::args().skip(1))?;
+    if args.mock && !cfg!(feature = "mock-asr") {
+        return Err("--mock 需要以 --features mock-asr 编译".to_string());
+    }
+    if !args.mock {
+        dotenv::from_filename("../.env").ok();
+        dotenv::dotenv().ok();
+    }
+
+    let corpus = load_corpus(&args.corpus)?;
+    if corpus.is_empty() {
+        return Err(format!("{} 中没有带参考文本的 WAV", args.corpus.display()));
+    }
+
+    let mut results = Vec::new();
+    for item in &corpus {
+        for &vendor in &args.vendors {
+            println!("[{}] {} ...", vendor.as_str(), item.name);
+            let result = bench_item(&args, vendor, item);
+            match (&result.error, result.wer) {
+                (Some(err), _) => println!("  失败: {err}"),
+                (None, Some(wer)) => println!(
+                    "  WER {:.2}% CER {:.2}% 首个草稿 {:?}ms 最终 {:?}ms",
+                    wer * 100.0,
+                    result.cer.unwrap_or_default() * 100.0,
+                    result.first_draft_ms,
+                    result.final_ms
+                ),
+                (None, None) => println!("  参考文本为空"),
+            }
+            results.push(result);
+        }
+    }
+
+    let report = summarize(results);
+    std::fs::create_dir_all(&args.output_dir)
+        .map_err(|err| format!("创建 {} 失败: {err}", args.output_dir.display()))?;
+    let json_path = args.output_dir.join("asr_bench.json");
+    let csv_path = args.output_dir.join("asr_bench.csv");
+    let json =
+        serde_json::to_string_pretty(&report).map_err(|err| format!("序列化报告失败: {err}"))?;
+    std::fs::write(&json_path, json)
+        .map_err(|err| format!("写入 {} 失败: {err}", json_path.display()))?;
+    std::fs::write(&csv_path, results_csv(&report.results))
+        .map_err(|err| format!("写入 {} 失败: {err}", csv_path.display()))?;
+
+    println!();
+    for summary in &report.vendors {
+        println!(
+            "{}: {} 个文件 ({} 失败) WER {} CER {} 首个草稿 p50/p95 {:?}/{:?}ms 最终 p50/p95 {:?}/{:?}ms",
+            summary.vendor,
+            summary.files,
+            summary.failures,
+            format_rate(summary.wer),
+            format_rate(summary.cer),
+            summary.first_draft.p50_ms,
+            summary.first_draft.p95_ms,
+            summary.final_latency.p50_ms,
+            summary.final_latency.p95_ms,
+        );
+    }
+    println!(
+        "报告已写入 {} 和 {}",
+        json_path.display(),
+        csv_path.display()
+    );
+    Ok(())
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map(|rate| format!("{:.2}%", rate * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
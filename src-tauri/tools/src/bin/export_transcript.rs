@@ -0,0 +1,76 @@
+//! Export a recorded transcript session from the command line.
+//!
+//! cargo run --bin export_transcript -- <session.jsonl> [--format srt|vtt|md|txt|json] [--output FILE]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tauri_courier_ai_lib::transcript_export::{ExportFormat, export_session};
+use tauri_courier_ai_lib::transcript_history::load_session_file;
+
+struct CliArgs {
+    input: PathBuf,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+}
+
+fn usage() -> String {
+    let formats = ExportFormat::ALL
+        .iter()
+        .map(|format| format.extension())
+        .collect::<Vec<_>>()
+        .join("|");
+    format!("用法: export_transcript <session.jsonl> [--format {formats}] [--output FILE]")
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<CliArgs, String> {
+    let mut input = None;
+    let mut format = ExportFormat::Srt;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--format" => {
+                let value = args.next().ok_or("--format 缺少参数")?;
+                format = ExportFormat::parse(&value)?;
+            }
+            "-o" | "--output" => {
+                output = Some(PathBuf::from(args.next().ok_or("--output 缺少参数")?));
+            }
+            "-h" | "--help" => return Err(usage()),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("无法识别的参数: {arg}\n{}", usage())),
+        }
+    }
+
+    Ok(CliArgs {
+        input: input.ok_or_else(usage)?,
+        format,
+        output,
+    })
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args(std::env::args().skip(1))?;
+    let session = load_session_file(&args.input)?;
+    let export = export_session(&session, args.format)?;
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, export.content)
+                .map_err(|err| format!("写入 {} 失败: {err}", path.display()))?;
+            println!("已导出到 {}", path.display());
+        }
+        None => print!("{}", export.content),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -3,10 +3,13 @@ use std::sync::Arc;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 use tauri_courier_ai_lib::{
-    PcmCallback, SelectedDeepgramTranscriber, StatusCallback, StreamingTranscriber,
-    TranscriptEvent, TranscriptRuntimeConfig, assemblyai::AssemblyAiTranscriber,
-    gladia::GladiaTranscriber, revai::RevAiTranscriber, speechmatics::SpeechmaticsTranscriber,
-    transcript_runtime_config_from_env,
+    DeepgramBackend, PcmCallback, StatusCallback, StreamingTranscriber, TranscriptEvent,
+    TranscriptRuntimeConfig, assemblyai::AssemblyAiTranscriber, dashscope::DashScopeTranscriber,
+    deepgram_api::DeepgramApiTranscriber, deepgram_sdk::DeepgramTranscriber,
+    gladia::GladiaTranscriber, openai_compatible::OpenAiCompatibleTranscriber,
+    openai_realtime::OpenAiRealtimeTranscriber, revai::RevAiTranscriber,
+    speechmatics::SpeechmaticsTranscriber, transcript_runtime_config_from_env,
+    volcengine::VolcengineTranscriber,
 };
 
 const PROBE_DURATION: Duration = Duration::from_secs(180);
@@ -18,7 +21,17 @@ fn main() {
 
     let vendor = Select::new(
         "请选择要测试的供应商:",
-        vec!["assemblyai", "deepgram", "gladia", "revai", "speechmatics"],
+        vec![
+            "assemblyai",
+            "deepgram",
+            "gladia",
+            "revai",
+            "speechmatics",
+            "openai_compatible",
+            "openai_realtime",
+            "dashscope",
+            "volcengine",
+        ],
     )
     .prompt()
     .expect("选择失败");
@@ -116,10 +129,16 @@ fn start_transcriber(
             AssemblyAiTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
                 .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
         }
-        "deepgram" => {
-            SelectedDeepgramTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
-                .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
-        }
+        "deepgram" => match DeepgramBackend::resolve(&config) {
+            DeepgramBackend::Api => {
+                DeepgramApiTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                    .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+            }
+            DeepgramBackend::Sdk => {
+                DeepgramTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                    .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+            }
+        },
         "gladia" => GladiaTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
             .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>),
         "revai" => RevAiTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
@@ -128,6 +147,22 @@ fn start_transcriber(
             SpeechmaticsTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
                 .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
         }
+        "openai_compatible" => {
+            OpenAiCompatibleTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+        }
+        "openai_realtime" => {
+            OpenAiRealtimeTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+        }
+        "dashscope" => {
+            DashScopeTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+        }
+        "volcengine" => {
+            VolcengineTranscriber::start(SAMPLE_RATE, callback, Some(status_callback), config)
+                .map(|transcriber| Box::new(transcriber) as Box<dyn StreamingTranscriber>)
+        }
         _ => Err(format!("unsupported vendor: {vendor}")),
     }
 }
@@ -6,11 +6,7 @@ use std::{
 };
 
 use tauri::{AppHandle, Listener, Wry};
-use tauri_courier_ai_lib::{
-    FlowArgs, ali_qwen_2_5, ali_qwen_max, ali_qwen_plus_latest, deepseek_api, doubao_lite,
-    doubao_pro, doubao_seed, doubao_seed_flash, siliconflow_free_models,
-    siliconflow_free_with_model, siliconflow_pro_models, siliconflow_pro_with_model,
-};
+use tauri_courier_ai_lib::{FlowArgs, LlmProviderRegistry, chat_with_registered_model};
 use tokio::{sync::oneshot, time::timeout};
 
 const RUNS_PER_MODEL: usize = 10;
@@ -38,6 +34,8 @@ fn build_request_id(label: &str, iteration: usize) -> String {
 async fn main() -> Result<(), Box<dyn Error>> {
     dotenv::from_filename("../.env").ok();
     dotenv::dotenv().ok();
+    // 可选参数: 只测试指定的供应商 ID, 例如 `llm_bench deepseek_api doubao_lite`
+    let only: Vec<String> = std::env::args().skip(1).collect();
     println!("LLM 启动耗时基准测试, 每个模型运行 {RUNS_PER_MODEL} 次");
 
     let app = tauri::Builder::default()
@@ -45,58 +43,48 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .expect("无法创建 tauri App 用于基准测试");
     let app_handle = app.handle();
 
-    bench_models(app_handle).await;
+    bench_models(app_handle, &only).await;
     Ok(())
 }
 
-#[allow(dead_code)]
-async fn bench_base(app_handle: &AppHandle<Wry>) {
-    macro_rules! bench {
-        ($label:literal, $func:path, $app:expr) => {
-            if let Err(err) = run_bench_for($label, $app, $func, None).await {
-                eprintln!("模型 {label} 基准测试终止: {err}", label = $label);
-            }
-        };
-    }
+async fn bench_models(app_handle: &AppHandle<Wry>, only: &[String]) {
+    let registry = Arc::new(LlmProviderRegistry::load(app_handle));
 
-    bench!("doubao_lite", doubao_lite, app_handle);
-    bench!("doubao_pro", doubao_pro, app_handle);
-    bench!("doubao_seed_flash", doubao_seed_flash, app_handle);
-    bench!("doubao_seed", doubao_seed, app_handle);
-    bench!("deepseek_api", deepseek_api, app_handle);
-    bench!("ali_qwen_2_5", ali_qwen_2_5, app_handle);
-    bench!("ali_qwen_plus_latest", ali_qwen_plus_latest, app_handle);
-    bench!("ali_qwen_max", ali_qwen_max, app_handle);
-
-    for &model in siliconflow_free_models() {
-        let label = format!("siliconflow_free::{model}");
-        if let Err(err) = run_bench_for(
-            label.as_str(),
-            app_handle,
-            move |app, args| siliconflow_free_with_model(app, args, model),
-            None,
-        )
-        .await
-        {
-            eprintln!("模型 {label} 基准测试终止: {err}");
+    for entry in registry.entries() {
+        if !only.is_empty() && !only.contains(&entry.id) {
+            continue;
         }
-    }
-}
 
-async fn bench_models(app_handle: &AppHandle<Wry>) {
-    bench_base(app_handle).await;
-
-    for &model in siliconflow_pro_models() {
-        let label = format!("siliconflow_pro::{model}");
-        if let Err(err) = run_bench_for(
-            label.as_str(),
-            app_handle,
-            move |app, args| siliconflow_pro_with_model(app, args, model),
-            Some(Duration::from_secs(3)),
-        )
-        .await
-        {
-            eprintln!("模型 {label} 基准测试终止: {err}");
+        // 轮换型供应商逐个模型测试, 沿用对话时的 3 秒首包限制
+        let (models, abort_timeout) = if entry.is_rotating() {
+            (
+                entry.models.iter().cloned().map(Some).collect(),
+                Some(Duration::from_secs(3)),
+            )
+        } else {
+            (vec![None], None)
+        };
+
+        for model in models {
+            let label = match &model {
+                Some(model) => format!("{}::{model}", entry.id),
+                None => entry.id.clone(),
+            };
+            let registry = Arc::clone(&registry);
+            let provider = entry.id.clone();
+            let func = move |app: AppHandle<Wry>, args: FlowArgs| {
+                let registry = Arc::clone(&registry);
+                let provider = provider.clone();
+                let model = model.clone();
+                async move {
+                    chat_with_registered_model(app, &registry, &provider, model.as_deref(), args)
+                        .await
+                }
+            };
+
+            if let Err(err) = run_bench_for(label.as_str(), app_handle, func, abort_timeout).await {
+                eprintln!("模型 {label} 基准测试终止: {err}");
+            }
         }
     }
 }
@@ -108,7 +96,7 @@ async fn run_bench_for<F, Fut>(
     abort_timeout: Option<Duration>,
 ) -> Result<(), String>
 where
-    F: Fn(AppHandle<Wry>, FlowArgs) -> Fut + Copy + Send + Sync + 'static,
+    F: Fn(AppHandle<Wry>, FlowArgs) -> Fut + Clone + Send + Sync + 'static,
     Fut: Future<Output = Result<String, String>> + Send + 'static,
 {
     println!("\n=== {label} ===");
@@ -116,7 +104,7 @@ where
     let mut failures = 0usize;
 
     for iteration in 0..RUNS_PER_MODEL {
-        let single_run = execute_single_run(label, iteration, app_handle, func);
+        let single_run = execute_single_run(label, iteration, app_handle, func.clone());
         let result = if let Some(timeout_duration) = abort_timeout {
             match timeout(timeout_duration, single_run).await {
                 Ok(res) => res,
@@ -176,7 +164,7 @@ async fn execute_single_run<F, Fut>(
     func: F,
 ) -> Result<Duration, String>
 where
-    F: Fn(AppHandle<Wry>, FlowArgs) -> Fut + Copy + Send + Sync + 'static,
+    F: Fn(AppHandle<Wry>, FlowArgs) -> Fut + Clone + Send + Sync + 'static,
     Fut: Future<Output = Result<String, String>> + Send + 'static,
 {
     let request_id = build_request_id(label, iteration);
@@ -1,4 +1,6 @@
 import type React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { useCallback, useEffect, useRef, useState } from "react";
 import { toast } from "sonner";
 import TitleBar from "@/components/TitleBar.tsx";
@@ -12,9 +14,33 @@ export interface Message {
 	sender: "user" | "robot";
 }
 
+interface AutoQuestionEvent {
+	id: number;
+	vendor: string;
+	question: string;
+	requestId?: string;
+}
+
+interface AutoAnswerEvent {
+	questionId: number;
+	requestId: string;
+	provider: string;
+	question: string;
+	answer?: string;
+	error?: string;
+}
+
+function createThreadId() {
+	return `chat_${Date.now()}_${Math.random().toString(36).substring(2, 8)}`;
+}
+
 export const ChatContainer: React.FC = () => {
 	const didRun = useRef(false);
 	const nextMessageIdRef = useRef(1);
+	// 后端按会话 ID 保存之前的问答，追问时作为上下文
+	const threadIdRef = useRef(createThreadId());
+	// 新提问会取消旧回答，旧回答结束时不能关掉新回答的输入提示
+	const activeBotIdRef = useRef<number | null>(null);
 	// 用 ref 存消息，避免 React 状态更新导致未更新完成的旧的状态丢失
 	const messagesRef = useRef<Message[]>([
 		{
@@ -83,6 +109,10 @@ export const ChatContainer: React.FC = () => {
 				return;
 			}
 
+			const { cancelActiveLlmRequests, llmInterviewChatStreamOutput } =
+				await import("@/lib/llm.ts");
+			await cancelActiveLlmRequests();
+
 			const userMsg: Message = {
 				id: nextMessageIdRef.current,
 				text,
@@ -104,11 +134,11 @@ export const ChatContainer: React.FC = () => {
 
 			const thisBotId = botMsg.id; // ← 记录本轮机器人消息 ID
 
+			activeBotIdRef.current = thisBotId;
 			setIsTyping(true);
 			let didReceiveResponse = false;
 
 			try {
-				const { llmInterviewChatStreamOutput } = await import("@/lib/llm.ts");
 				await llmInterviewChatStreamOutput(
 					text,
 					useInterviewPrompt ? interviewPromptStore : llmPromptStore,
@@ -118,13 +148,16 @@ export const ChatContainer: React.FC = () => {
 						setIsTyping(false);
 						updateSpecificBotMessage(thisBotId, content); // ← 更新特定机器人消息
 					},
+					threadIdRef.current,
 				);
 			} catch {
 				if (!didReceiveResponse) {
 					removeMessageById(thisBotId);
 				}
 			} finally {
-				setIsTyping(false);
+				if (activeBotIdRef.current === thisBotId) {
+					setIsTyping(false);
+				}
 			}
 		},
 		[
@@ -137,7 +170,93 @@ export const ChatContainer: React.FC = () => {
 		],
 	);
 
+	// 后台识别到对方提问时推送到对话；后台回答模式下直接接收流式回答
+	const handleSendMessageRef = useRef(handleSendMessage);
+	handleSendMessageRef.current = handleSendMessage;
+	useEffect(() => {
+		const pendingAnswers = new Map<
+			string,
+			{ botId: number; unlisten: UnlistenFn | null }
+		>();
+		const unlisteners: Promise<UnlistenFn>[] = [
+			listen<AutoQuestionEvent>("auto_question", async (event) => {
+				const { question, requestId } = event.payload;
+				if (!requestId) {
+					await handleSendMessageRef.current(question);
+					return;
+				}
+
+				messagesRef.current.push({
+					id: nextMessageIdRef.current,
+					text: question,
+					sender: "user",
+				});
+				nextMessageIdRef.current += 1;
+				const botId = nextMessageIdRef.current;
+				messagesRef.current.push({ id: botId, text: "", sender: "robot" });
+				nextMessageIdRef.current += 1;
+				setMessages([...messagesRef.current]);
+
+				const pending = { botId, unlisten: null as UnlistenFn | null };
+				pendingAnswers.set(requestId, pending);
+				let streamed = "";
+				const unlisten = await listen<string>(
+					`llm_stream_${requestId}`,
+					(chunk) => {
+						streamed += chunk.payload;
+						updateSpecificBotMessage(botId, streamed);
+					},
+				);
+				if (pendingAnswers.get(requestId) !== pending) {
+					unlisten();
+					return;
+				}
+				pending.unlisten = unlisten;
+				// 后台收到确认后才开始请求，避免丢失最早的流式片段
+				await invoke("auto_answer_stream_ready", { requestId }).catch(
+					(error) => {
+						console.error("confirm auto answer stream failed", error);
+					},
+				);
+			}),
+			listen<AutoAnswerEvent>("auto_answer", (event) => {
+				const { requestId, answer, error } = event.payload;
+				const pending = pendingAnswers.get(requestId);
+				if (!pending) return;
+
+				pendingAnswers.delete(requestId);
+				pending.unlisten?.();
+				if (answer) {
+					updateSpecificBotMessage(pending.botId, answer);
+				} else {
+					updateSpecificBotMessage(pending.botId, `自动回答失败: ${error}`);
+				}
+			}),
+		];
+
+		return () => {
+			for (const pending of pendingAnswers.values()) {
+				pending.unlisten?.();
+			}
+			pendingAnswers.clear();
+			for (const unlisten of unlisteners) {
+				unlisten.then((fn) => fn()).catch(() => {});
+			}
+		};
+	}, [updateSpecificBotMessage]);
+
 	const handleClearConversation = () => {
+		import("@/lib/llm.ts")
+			.then(({ cancelActiveLlmRequests }) => cancelActiveLlmRequests())
+			.catch((error) => {
+				console.error("cancel llm requests failed", error);
+			});
+		invoke("clear_llm_thread", { threadId: threadIdRef.current }).catch(
+			(error) => {
+				console.error("clear llm thread failed", error);
+			},
+		);
+		threadIdRef.current = createThreadId();
 		nextMessageIdRef.current = 1;
 		messagesRef.current = [
 			{
@@ -70,7 +70,7 @@ export function LlmProviderDialog({
 					<DialogDescription className="text-slate-300">
 						这里的配置只影响大模型请求，不影响转录供应商。API Key 留空时会回退到
 						dev 模式下的 `.env` 或 production 模式下内置的 `.env.local`
-						预设；OpenAI、Gemini 和自定义兼容供应商还支持自定义 Base URL
+						预设；OpenAI、Gemini、Anthropic 和自定义兼容供应商还支持自定义 Base URL
 						与模型名。每个字段右侧都可以直接打开对应供应商官网。
 					</DialogDescription>
 				</DialogHeader>
@@ -262,6 +262,60 @@ export function LlmProviderDialog({
 						</div>
 					</Section>
 
+					<Section
+						title="Anthropic"
+						description="直接调用 Anthropic Messages API，无需第三方网关。"
+					>
+						<div className="grid gap-4 md:grid-cols-2">
+							<ProviderConfigField
+								label="Anthropic API Key"
+								value={draft.anthropicApiKey}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										anthropicApiKey: value,
+									}))
+								}
+								placeholder={getLlmProviderStatus(
+									draft.anthropicApiKey,
+									presets.anthropicApiKey,
+								)}
+								status={getLlmProviderStatus(
+									draft.anthropicApiKey,
+									presets.anthropicApiKey,
+								)}
+								description="如果这里留空，后端会回退到 ANTHROPIC_API_KEY。"
+								officialLink={llmProviderOfficialLinks.anthropicApiKey}
+							/>
+							<ProviderConfigField
+								label="Anthropic Model"
+								value={draft.anthropicModel}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										anthropicModel: value,
+									}))
+								}
+								placeholder="claude-sonnet-4-5"
+								officialLink={llmProviderOfficialLinks.anthropicModel}
+							/>
+							<div className="md:col-span-2">
+								<ProviderConfigField
+									label="Anthropic Base URL"
+									value={draft.anthropicBaseUrl}
+									onChange={(value) =>
+										setDraft((current) => ({
+											...current,
+											anthropicBaseUrl: value,
+										}))
+									}
+									placeholder="https://api.anthropic.com/v1"
+									officialLink={llmProviderOfficialLinks.anthropicBaseUrl}
+								/>
+							</div>
+						</div>
+					</Section>
+
 					<Section
 						title="自定义 OpenAI 兼容供应商"
 						description="适用于任何遵循 OpenAI Chat Completions 规范的自建或第三方接口。"
@@ -324,6 +378,66 @@ export function LlmProviderDialog({
 							/>
 						</div>
 					</Section>
+
+					<Section
+						title="网络代理"
+						description="大模型请求经由代理发出，支持 http:// 和 socks5://（可带用户名密码）。按供应商覆盖时写 openai=socks5://127.0.0.1:1080，direct 表示直连。留空时读取 HTTPS_PROXY / ALL_PROXY / NO_PROXY 环境变量。"
+					>
+						<div className="grid gap-4 md:grid-cols-2">
+							<ProviderConfigField
+								label="Proxy URL"
+								value={draft.proxyUrl}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										proxyUrl: value,
+									}))
+								}
+								placeholder="http://127.0.0.1:7890"
+							/>
+							<ProviderConfigField
+								label="No Proxy"
+								value={draft.noProxy}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										noProxy: value,
+									}))
+								}
+								placeholder="localhost,127.0.0.1,.internal"
+							/>
+							<ProviderConfigField
+								label="Proxy Overrides"
+								value={draft.proxyOverrides}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										proxyOverrides: value,
+									}))
+								}
+								placeholder="openai=http://127.0.0.1:7890, doubao_pro=direct"
+							/>
+						</div>
+					</Section>
+
+					<Section
+						title="多轮对话上下文"
+						description="追问时会带上本轮对话之前的问答和最近的转录内容，超出预算时优先丢弃最早的问答。预算按估算的 token 数计算，留空默认 6000。"
+					>
+						<div className="grid gap-4 md:grid-cols-2">
+							<ProviderConfigField
+								label="Context Token Budget"
+								value={draft.contextTokenBudget}
+								onChange={(value) =>
+									setDraft((current) => ({
+										...current,
+										contextTokenBudget: value,
+									}))
+								}
+								placeholder="6000"
+							/>
+						</div>
+					</Section>
 				</div>
 
 				<div className="flex shrink-0 flex-row items-center justify-between gap-3 border-t border-white/10 pt-4">
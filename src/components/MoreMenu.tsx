@@ -24,6 +24,7 @@ import {
 import { Textarea } from "@/components/ui/textarea.tsx";
 import { extractResumeTextFromFile } from "@/lib/resumeImport.ts";
 import { runUpdater } from "@/lib/updater.ts";
+import { getTranscriptVendorCapabilities } from "@/lib/vendorCapabilities.ts";
 import useAppStateStore, {
 	type TranscribeVendor,
 	type UiTextTone,
@@ -32,10 +33,16 @@ import {
 	type AudioChannelOption,
 	findAudioChannelValue,
 } from "@/types/audio.ts";
-import { HOTKEYS, MODEL_LABELS, MODEL_OPTIONS } from "@/types/llm.ts";
+import {
+	HOTKEYS,
+	type LlmProviderEntry,
+	MODEL_LABELS,
+	MODEL_OPTIONS,
+} from "@/types/llm.ts";
 import {
 	hasAnyTranscriptApiKeyConfigured,
 	TRANSCRIBE_VENDOR_LABELS,
+	type TranscriptVendorCapabilities,
 } from "@/types/provider.ts";
 
 export function MoreMenu() {
@@ -52,6 +59,9 @@ export function MoreMenu() {
 		(state) => state.updateCurrentSelectedModel,
 	);
 	const [audioChannels, setAudioChannels] = useState<AudioChannelOption[]>([]);
+	const [llmProviders, setLlmProviders] = useState<
+		Pick<LlmProviderEntry, "id" | "label">[]
+	>(MODEL_OPTIONS.map((id) => ({ id, label: MODEL_LABELS[id] })));
 	const [isPromptDialogOpen, setIsPromptDialogOpen] = useState(false);
 	const [isLlmConfigDialogOpen, setIsLlmConfigDialogOpen] = useState(false);
 	const [isTranscriptConfigDialogOpen, setIsTranscriptConfigDialogOpen] =
@@ -63,6 +73,9 @@ export function MoreMenu() {
 		"llm" | "interview" | null
 	>(null);
 	const didAutoOpenTranscriptDialog = useRef(false);
+	const [vendorCapabilities, setVendorCapabilities] = useState<
+		Partial<Record<TranscribeVendor, TranscriptVendorCapabilities>>
+	>({});
 	const [promptDraft, setPromptDraft] = useState(appState.llmPrompt);
 	const [interviewPromptDraft, setInterviewPromptDraft] = useState(
 		appState.interviewPrompt,
@@ -73,6 +86,10 @@ export function MoreMenu() {
 		"gladia",
 		"revai",
 		"speechmatics",
+		"openai_compatible",
+		"openai_realtime",
+		"dashscope",
+		"volcengine",
 	];
 	const UI_OPACITY_OPTIONS = [
 		100, 95, 90, 85, 80, 75, 70, 65, 60, 55, 50, 45, 40, 35, 30,
@@ -85,12 +102,10 @@ export function MoreMenu() {
 		(import.meta.env.DEV ? import.meta.env.VITE_PROMPT : "") || "";
 	const defaultInterviewPrompt =
 		(import.meta.env.DEV ? import.meta.env.VITE_INTERVIEW_PROMPT : "") || "";
-	const modelLabels = {
-		...MODEL_LABELS,
-		custom_openai:
-			appState.llmProviderSettings.customOpenAiName.trim() ||
-			MODEL_LABELS.custom_openai,
-	};
+	const modelLabel = (provider: Pick<LlmProviderEntry, "id" | "label">) =>
+		provider.id === "custom_openai"
+			? appState.llmProviderSettings.customOpenAiName.trim() || provider.label
+			: provider.label || provider.id;
 	const shouldOpenPromptDialogOnStartup =
 		appState.llmPrompt.trim().length === 0 &&
 		appState.interviewPrompt.trim().length === 0;
@@ -100,6 +115,18 @@ export function MoreMenu() {
 	const isUsingDefaultPrompt =
 		promptDraft === defaultPrompt &&
 		interviewPromptDraft === defaultInterviewPrompt;
+	useEffect(() => {
+		void invoke<LlmProviderEntry[]>("list_llm_providers")
+			.then((result) => {
+				if (result.length > 0) {
+					setLlmProviders(result);
+				}
+			})
+			.catch((error) => {
+				console.error("list llm providers failed", error);
+			});
+	}, []);
+
 	useEffect(() => {
 		void invoke<AudioChannelOption[]>("get_audio_stream_devices_names")
 			.then((result) => {
@@ -127,6 +154,21 @@ export function MoreMenu() {
 			});
 	}, [currentAudioChannel, updateCurrentAudioChannel]);
 
+	useEffect(() => {
+		void getTranscriptVendorCapabilities(
+			appState.transcriptProviderSettings,
+			appState.llmProviderSettings,
+		)
+			.then((result) => {
+				setVendorCapabilities(
+					Object.fromEntries(result.map((entry) => [entry.vendor, entry])),
+				);
+			})
+			.catch((error) => {
+				console.error("failed to load vendor capabilities:", error);
+			});
+	}, [appState.transcriptProviderSettings, appState.llmProviderSettings]);
+
 	useEffect(() => {
 		if (!isPromptDialogOpen) {
 			return;
@@ -422,16 +464,16 @@ export function MoreMenu() {
 							大模型
 						</DropdownMenuSubTrigger>
 						<DropdownMenuSubContent className="w-48 bg-gray-600 text-white border-0">
-							{MODEL_OPTIONS.map((model) => (
+							{llmProviders.map((provider) => (
 								<DropdownMenuItem
-									key={model}
+									key={provider.id}
 									className={`data-[highlighted]:bg-gray-500 ${
-										currentModel === model ? "font-bold" : ""
+										currentModel === provider.id ? "font-bold" : ""
 									}`}
-									onClick={() => updateCurrentSelectedModel(model)}
+									onClick={() => updateCurrentSelectedModel(provider.id)}
 								>
-									{modelLabels[model]}
-									{currentModel === model && (
+									{modelLabel(provider)}
+									{currentModel === provider.id && (
 										<span className="ml-2 text-green-400">✔</span>
 									)}
 								</DropdownMenuItem>
@@ -451,6 +493,7 @@ export function MoreMenu() {
 							{TRANSCRIBE_VENDORS.map((vendor) => (
 								<DropdownMenuItem
 									key={vendor}
+									disabled={vendorCapabilities[vendor]?.compiledIn === false}
 									className={`data-[highlighted]:bg-gray-500 ${
 										appState.useRemoteModelTranscribe === vendor
 											? "font-bold"
@@ -459,6 +502,10 @@ export function MoreMenu() {
 									onClick={() => appState.updateRemoteModelTranscribe(vendor)}
 								>
 									{TRANSCRIBE_VENDOR_LABELS[vendor]}
+									{(vendorCapabilities[vendor]?.missingConfig.length ?? 0) >
+										0 && (
+										<span className="ml-2 text-xs text-amber-300">未配置</span>
+									)}
 									{appState.useRemoteModelTranscribe === vendor && (
 										<span className="ml-2 text-green-400">✔</span>
 									)}
@@ -4,6 +4,7 @@ import { toast } from "sonner";
 import { logError, logInfo } from "@/lib/logger.ts";
 import { setRecordingStateImmediately } from "@/lib/recordingState.ts";
 import useAppStateStore from "@/stores";
+import type { BudgetAlert } from "@/types/usage.ts";
 
 let traditionalChineseConverter: ((content: string) => string) | null = null;
 
@@ -11,6 +12,49 @@ interface TranscriptEvent {
 	vendor: string;
 	kind: "draft" | "commit";
 	text: string;
+	language?: string;
+	utteranceId?: number;
+}
+
+export interface TranslationEvent {
+	utteranceId: number;
+	vendor: string;
+	engine: string;
+	sourceText: string;
+	sourceLanguage?: string;
+	targetLanguage: string;
+	text: string;
+}
+
+interface VendorSwitchedEvent {
+	from: string;
+	to: string;
+	reason: string;
+	replayedMs: number;
+}
+
+interface VendorComparisonMetrics {
+	vendor: string;
+	utterances: number;
+	lastFirstDraftMs: number | null;
+	avgFirstDraftMs: number | null;
+	lastFinalMs: number | null;
+	avgFinalMs: number | null;
+	lastCommit: string | null;
+	// Only present in the final report sent when the session ends.
+	committedText?: string;
+	error: string | null;
+}
+
+export interface ComparisonReport {
+	vendors: VendorComparisonMetrics[];
+	disagreements: { left: string; right: string; rate: number }[];
+}
+
+interface ComparisonUpdate {
+	vendor: string;
+	event: TranscriptEvent | null;
+	report: ComparisonReport | null;
 }
 
 async function convertTraditionalChinese(content: string) {
@@ -26,6 +70,20 @@ async function convertTraditionalChinese(content: string) {
 
 let unlistener: UnlistenFn | null = null;
 let errorUnlistener: UnlistenFn | null = null;
+let switchUnlistener: UnlistenFn | null = null;
+let comparisonUnlistener: UnlistenFn | null = null;
+let translationUnlistener: UnlistenFn | null = null;
+let budgetAlertUnlistener: UnlistenFn | null = null;
+let latestComparisonReport: ComparisonReport | null = null;
+const translationsByUtterance = new Map<number, TranslationEvent[]>();
+
+export function getLatestComparisonReport() {
+	return latestComparisonReport;
+}
+
+export function getTranslationsForUtterance(utteranceId: number) {
+	return translationsByUtterance.get(utteranceId) ?? [];
+}
 
 export async function startAudioLoopbackRecognition(
 	onMessageCapture: (message: string) => void,
@@ -44,12 +102,32 @@ export async function startAudioLoopbackRecognition(
 		errorUnlistener();
 		errorUnlistener = null;
 	}
+	if (switchUnlistener) {
+		switchUnlistener();
+		switchUnlistener = null;
+	}
+	if (comparisonUnlistener) {
+		comparisonUnlistener();
+		comparisonUnlistener = null;
+	}
+	if (translationUnlistener) {
+		translationUnlistener();
+		translationUnlistener = null;
+	}
+	if (budgetAlertUnlistener) {
+		budgetAlertUnlistener();
+		budgetAlertUnlistener = null;
+	}
 
-	const transcriptProviderSettings =
-		useAppStateStore.getState().transcriptProviderSettings;
+	const { transcriptProviderSettings, llmProviderSettings } =
+		useAppStateStore.getState();
 
-	const normalizeTranscript = async (payload: string) => {
-		if (selectedAsrVendor.toLowerCase() === "gladia") {
+	const normalizeTranscript = async (payload: string, vendor: string) => {
+		// The backend post-processing chain handles conversion when configured.
+		if (
+			vendor.toLowerCase() === "gladia" &&
+			!transcriptProviderSettings.transcriptChineseVariant
+		) {
 			return await convertTraditionalChinese(payload);
 		}
 
@@ -67,7 +145,7 @@ export async function startAudioLoopbackRecognition(
 			logInfo(
 				`transcription_event received vendor=${vendor} kind=${kind} length=${text.length}`,
 			);
-			const normalized = await normalizeTranscript(text);
+			const normalized = await normalizeTranscript(text, vendor);
 			onMessageCapture(normalized);
 			if (kind === "commit") {
 				onFinalMessageCapture(normalized);
@@ -83,12 +161,66 @@ export async function startAudioLoopbackRecognition(
 		}
 		toast.error(`当前 ${selectedAsrVendor} 转录连接异常关闭: ${event.payload}`);
 	});
+	switchUnlistener = await listen<VendorSwitchedEvent>(
+		"vendor_switched",
+		(event) => {
+			const { from, to, reason, replayedMs } = event.payload;
+			logInfo(
+				`vendor_switched from=${from} to=${to} replayedMs=${replayedMs} reason=${reason}`,
+			);
+			toast.warning(`转录供应商 ${from} 异常，已自动切换到 ${to}`);
+		},
+	);
+	latestComparisonReport = null;
+	comparisonUnlistener = await listen<ComparisonUpdate>(
+		"comparison_event",
+		(event) => {
+			const { vendor, report } = event.payload;
+			if (!report) {
+				return;
+			}
+
+			latestComparisonReport = report;
+			const metrics = report.vendors.find((item) => item.vendor === vendor);
+			logInfo(
+				`comparison_event vendor=${vendor} avgFirstDraftMs=${metrics?.avgFirstDraftMs} avgFinalMs=${metrics?.avgFinalMs} disagreements=${JSON.stringify(report.disagreements)}`,
+			);
+		},
+	);
+
+	translationsByUtterance.clear();
+	translationUnlistener = await listen<TranslationEvent>(
+		"translation_event",
+		(event) => {
+			const { utteranceId, engine, targetLanguage, text } = event.payload;
+			const translations = translationsByUtterance.get(utteranceId) ?? [];
+			translations.push(event.payload);
+			translationsByUtterance.set(utteranceId, translations);
+			logInfo(
+				`translation_event utteranceId=${utteranceId} engine=${engine} target=${targetLanguage} length=${text.length}`,
+			);
+		},
+	);
+
+	budgetAlertUnlistener = await listen<BudgetAlert>(
+		"usage_budget_alert",
+		(event) => {
+			const { month, threshold, estimatedCost, budget } = event.payload;
+			logInfo(
+				`usage_budget_alert month=${month} threshold=${threshold} cost=${estimatedCost}`,
+			);
+			toast.warning(
+				`${month} 转录用量已达预算的 ${Math.round(threshold * 100)}%：约 $${estimatedCost.toFixed(2)} / $${budget.toFixed(2)}`,
+			);
+		},
+	);
 
 	await invoke("start_recognize_audio_stream_from_speaker_loopback", {
 		deviceName: audioDevice,
 		selectedAsrVendor,
 		captureInterval,
 		transcriptConfig: transcriptProviderSettings,
+		llmConfig: llmProviderSettings,
 	}).catch((err) => {
 		console.error("invoke start output audio recognition failed", err);
 		logError("invoke start output audio recognition failed", err);
@@ -117,4 +249,20 @@ export async function stopAudioLoopbackRecognition() {
 		errorUnlistener();
 		errorUnlistener = null;
 	}
+	if (switchUnlistener) {
+		switchUnlistener();
+		switchUnlistener = null;
+	}
+	if (comparisonUnlistener) {
+		comparisonUnlistener();
+		comparisonUnlistener = null;
+	}
+	if (translationUnlistener) {
+		translationUnlistener();
+		translationUnlistener = null;
+	}
+	if (budgetAlertUnlistener) {
+		budgetAlertUnlistener();
+		budgetAlertUnlistener = null;
+	}
 }
@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+	TranscriptExport,
+	TranscriptExportFormat,
+	TranscriptSearchHit,
+	TranscriptSession,
+	TranscriptSessionSummary,
+} from "@/types/history.ts";
+
+export function listTranscriptSessions() {
+	return invoke<TranscriptSessionSummary[]>("list_transcript_sessions");
+}
+
+export function loadTranscriptSession(sessionId: string) {
+	return invoke<TranscriptSession>("load_transcript_session", { sessionId });
+}
+
+export function searchTranscriptHistory(
+	query: string,
+	options: { from?: string; to?: string; limit?: number } = {},
+) {
+	return invoke<TranscriptSearchHit[]>("search_transcript_history", {
+		query,
+		from: options.from ?? null,
+		to: options.to ?? null,
+		limit: options.limit ?? null,
+	});
+}
+
+export function deleteTranscriptSession(sessionId: string) {
+	return invoke<void>("delete_transcript_session", { sessionId });
+}
+
+export function exportTranscriptSession(
+	sessionId: string,
+	format: TranscriptExportFormat,
+) {
+	return invoke<TranscriptExport>("export_transcript_session", {
+		sessionId,
+		format,
+	});
+}
@@ -0,0 +1,7 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { LatencyReport } from "@/types/latency.ts";
+
+/** Latency of the running session, or of the last one; null before any. */
+export function getTranscriptionLatency() {
+	return invoke<LatencyReport | null>("get_transcription_latency");
+}
@@ -11,11 +11,27 @@ interface LlmResolvedModelEvent {
 	model: string;
 }
 
+const activeRequestIds = new Set<string>();
+const cancelledRequestIds = new Set<string>();
+
+export async function cancelLlmRequest(requestId: string) {
+	cancelledRequestIds.add(requestId);
+	await invoke<boolean>("cancel_llm_request", { requestId }).catch((err) => {
+		logError(`cancel llm request failed requestId=${requestId}`, err);
+	});
+}
+
+// 提问切换或清空对话时，停止仍在生成的旧回答
+export async function cancelActiveLlmRequests() {
+	await Promise.all([...activeRequestIds].map(cancelLlmRequest));
+}
+
 export async function llmInterviewChatStreamOutput(
 	question: string,
 	llmPrompt: string,
 	currentModel: ModelOption,
 	renderCallback: (chunk: string) => void,
+	threadId?: string,
 ) {
 	let result = "";
 
@@ -38,6 +54,7 @@ export async function llmInterviewChatStreamOutput(
 		},
 	);
 
+	activeRequestIds.add(requestId);
 	try {
 		await invoke("chat_with_llm_provider", {
 			provider: currentModel,
@@ -45,16 +62,23 @@ export async function llmInterviewChatStreamOutput(
 				question,
 				llmPrompt,
 				requestId,
+				threadId,
 			},
 			runtimeConfig: llmProviderSettings,
 		});
 	} catch (err) {
+		if (cancelledRequestIds.has(requestId)) {
+			logInfo(`llm request cancelled requestId=${requestId}`);
+			throw err;
+		}
 		const errorText = serializeError(err);
 		console.error(`invoke llmModel Error model=${currentModel}`, err);
 		logError(`invoke llmModel Error model=${currentModel}`, err);
 		toast.error(`invoke llm err model=${currentModel} ${errorText}`);
 		throw err;
 	} finally {
+		activeRequestIds.delete(requestId);
+		cancelledRequestIds.delete(requestId);
 		unlisten();
 		unlistenMeta();
 	}
@@ -18,11 +18,36 @@ const OPENAI_COMPATIBILITY_LINK: ProviderOfficialLink = {
 	url: "https://developers.openai.com/api/reference/overview",
 };
 
+const OPENAI_TRANSCRIPTION_LINK: ProviderOfficialLink = {
+	label: "兼容规范",
+	url: "https://platform.openai.com/docs/api-reference/audio/createTranscription",
+};
+
+const OPENAI_REALTIME_TRANSCRIPTION_LINK: ProviderOfficialLink = {
+	label: "官网",
+	url: "https://platform.openai.com/docs/guides/realtime-transcription",
+};
+
+const DASHSCOPE_ASR_LINK: ProviderOfficialLink = {
+	label: "官网",
+	url: "https://help.aliyun.com/zh/model-studio/websocket-for-paraformer-real-time-service",
+};
+
+const VOLCENGINE_ASR_LINK: ProviderOfficialLink = {
+	label: "官网",
+	url: "https://www.volcengine.com/docs/6561/1354869",
+};
+
 const GEMINI_REFERENCE_LINK: ProviderOfficialLink = {
 	label: "官网",
 	url: "https://ai.google.dev/gemini-api/docs/openai",
 };
 
+const ANTHROPIC_REFERENCE_LINK: ProviderOfficialLink = {
+	label: "官网",
+	url: "https://docs.anthropic.com/en/api/messages",
+};
+
 export const llmProviderOfficialLinks: Partial<
 	Record<keyof LlmProviderSettings, ProviderOfficialLink>
 > = {
@@ -52,6 +77,9 @@ export const llmProviderOfficialLinks: Partial<
 	geminiApiKey: GEMINI_REFERENCE_LINK,
 	geminiBaseUrl: GEMINI_REFERENCE_LINK,
 	geminiModel: GEMINI_REFERENCE_LINK,
+	anthropicApiKey: ANTHROPIC_REFERENCE_LINK,
+	anthropicBaseUrl: ANTHROPIC_REFERENCE_LINK,
+	anthropicModel: ANTHROPIC_REFERENCE_LINK,
 	customOpenAiName: OPENAI_COMPATIBILITY_LINK,
 	customOpenAiApiKey: OPENAI_COMPATIBILITY_LINK,
 	customOpenAiBaseUrl: OPENAI_COMPATIBILITY_LINK,
@@ -109,4 +137,20 @@ export const transcriptProviderOfficialLinks: Partial<
 		label: "官网",
 		url: "https://docs.rev.ai/api/streaming/get-started/",
 	},
+	openaiCompatibleApiKey: OPENAI_TRANSCRIPTION_LINK,
+	openaiCompatibleBaseUrl: OPENAI_TRANSCRIPTION_LINK,
+	openaiCompatibleModel: OPENAI_TRANSCRIPTION_LINK,
+	openaiCompatibleLanguage: OPENAI_TRANSCRIPTION_LINK,
+	openaiRealtimeApiKey: OPENAI_REALTIME_TRANSCRIPTION_LINK,
+	openaiRealtimeBaseUrl: OPENAI_REALTIME_TRANSCRIPTION_LINK,
+	openaiRealtimeModel: OPENAI_REALTIME_TRANSCRIPTION_LINK,
+	openaiRealtimeLanguage: OPENAI_REALTIME_TRANSCRIPTION_LINK,
+	dashscopeApiKey: DASHSCOPE_ASR_LINK,
+	dashscopeModel: DASHSCOPE_ASR_LINK,
+	dashscopeLanguage: DASHSCOPE_ASR_LINK,
+	dashscopeWsUrl: DASHSCOPE_ASR_LINK,
+	volcengineAppKey: VOLCENGINE_ASR_LINK,
+	volcengineAccessKey: VOLCENGINE_ASR_LINK,
+	volcengineResourceId: VOLCENGINE_ASR_LINK,
+	volcengineWsUrl: VOLCENGINE_ASR_LINK,
 };
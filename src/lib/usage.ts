@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { TranscriptProviderSettings } from "@/types/provider.ts";
+import type { UsageReport } from "@/types/usage.ts";
+
+export function getAsrUsage(
+	transcriptConfig: TranscriptProviderSettings,
+	options: { month?: string; from?: string; to?: string } = {},
+) {
+	return invoke<UsageReport>("get_asr_usage", {
+		transcriptConfig,
+		month: options.month ?? null,
+		from: options.from ?? null,
+		to: options.to ?? null,
+	});
+}
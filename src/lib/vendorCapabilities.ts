@@ -0,0 +1,16 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+	LlmProviderSettings,
+	TranscriptProviderSettings,
+	TranscriptVendorCapabilities,
+} from "@/types/provider.ts";
+
+export function getTranscriptVendorCapabilities(
+	transcriptConfig: TranscriptProviderSettings,
+	llmConfig: LlmProviderSettings,
+) {
+	return invoke<TranscriptVendorCapabilities[]>(
+		"get_transcript_vendor_capabilities",
+		{ transcriptConfig, llmConfig },
+	);
+}
@@ -7,7 +7,7 @@ import {
 } from "@/lib/appConfig.ts";
 import { logError } from "@/lib/logger.ts";
 import type { LicenseStatus } from "@/types/license.ts";
-import { MODEL_OPTIONS, type ModelOption } from "@/types/llm.ts";
+import type { ModelOption } from "@/types/llm.ts";
 import {
 	createDefaultLlmProviderSettings,
 	createDefaultProviderEnvPresets,
@@ -115,10 +115,7 @@ function normalizeCaptureInterval(target: unknown) {
 }
 
 function isModelOption(target: unknown): target is ModelOption {
-	return (
-		typeof target === "string" &&
-		(MODEL_OPTIONS as readonly string[]).includes(target)
-	);
+	return typeof target === "string" && target.trim().length > 0;
 }
 
 function isTranscribeVendor(target: unknown): target is TranscribeVendor {
@@ -0,0 +1,42 @@
+export type TranscriptSegmentKind = "draft" | "commit";
+
+export interface TranscriptSessionMetadata {
+	id: string;
+	device: string;
+	vendor: string;
+	language: string | null;
+	startedAt: string;
+}
+
+export interface TranscriptSegment {
+	kind: TranscriptSegmentKind;
+	vendor: string;
+	text: string;
+	at: string;
+	offsetMs: number;
+	language?: string;
+}
+
+export interface TranscriptSessionSummary extends TranscriptSessionMetadata {
+	endedAt: string | null;
+	commitCount: number;
+	preview: string;
+}
+
+export interface TranscriptSession extends TranscriptSessionMetadata {
+	endedAt: string | null;
+	segments: TranscriptSegment[];
+}
+
+export interface TranscriptSearchHit {
+	session: TranscriptSessionMetadata;
+	segment: TranscriptSegment;
+}
+
+export type TranscriptExportFormat = "srt" | "vtt" | "markdown" | "text" | "json";
+
+export interface TranscriptExport {
+	format: TranscriptExportFormat;
+	fileName: string;
+	content: string;
+}
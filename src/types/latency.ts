@@ -0,0 +1,15 @@
+export interface LatencyStats {
+	samples: number;
+	p50Ms: number | null;
+	p95Ms: number | null;
+}
+
+export interface LatencyReport {
+	sessionId: string;
+	active: boolean;
+	firstDraft: LatencyStats;
+	speechEndToFinal: LatencyStats;
+	captureToSend: LatencyStats;
+	correlatedFinals: number;
+	estimatedFinals: number;
+}
@@ -8,14 +8,18 @@ export const MODEL_OPTIONS = [
 	"ali_qwen_2_5",
 	"ali_qwen_plus_latest",
 	"ali_qwen_max",
+	"anthropic",
 	"openai",
 	"gemini",
 	"custom_openai",
 ] as const;
 
-export type ModelOption = (typeof MODEL_OPTIONS)[number];
+export type BuiltinModelOption = (typeof MODEL_OPTIONS)[number];
 
-export const MODEL_LABELS: Record<ModelOption, string> = {
+// 用户的供应商配置文件可以追加内置列表之外的 ID
+export type ModelOption = BuiltinModelOption | (string & {});
+
+export const MODEL_LABELS: Record<BuiltinModelOption, string> = {
 	siliconflow_pro: "SiliconFlow Pro",
 	siliconflow_minimax_m2_5: "MiniMax M2.5",
 	doubao_lite: "Doubao Lite",
@@ -25,11 +29,19 @@ export const MODEL_LABELS: Record<ModelOption, string> = {
 	ali_qwen_2_5: "Qwen 2.5",
 	ali_qwen_plus_latest: "Qwen Plus",
 	ali_qwen_max: "Qwen Max",
+	anthropic: "Anthropic",
 	openai: "OpenAI",
 	gemini: "Gemini",
 	custom_openai: "自定义 OpenAI 兼容",
 };
 
+export interface LlmProviderEntry {
+	id: string;
+	label: string;
+	models: string[];
+	fallbackGroup?: string | null;
+}
+
 export interface HotkeyHelpItem {
 	label: string;
 	combo: string;
@@ -3,7 +3,11 @@ export type TranscribeVendor =
 	| "deepgram"
 	| "gladia"
 	| "revai"
-	| "speechmatics";
+	| "speechmatics"
+	| "openai_compatible"
+	| "openai_realtime"
+	| "dashscope"
+	| "volcengine";
 
 export type MacosSystemAudioBackend = "swift-helper" | "rust-native";
 
@@ -20,12 +24,73 @@ export const MACOS_SYSTEM_AUDIO_BACKEND_LABELS: Record<
 	"rust-native": "Rust Native",
 };
 
+export type AsrLanguageMode = "" | "fixed" | "auto" | "code_switching";
+
+export const ASR_LANGUAGE_MODE_LABELS: Record<AsrLanguageMode, string> = {
+	"": "各厂商默认",
+	fixed: "固定语言",
+	auto: "自动检测",
+	code_switching: "中英混说 (Code-switching)",
+};
+
+export type TranslationMode = "" | "vendor" | "llm" | "auto";
+
+export const TRANSLATION_MODE_LABELS: Record<TranslationMode, string> = {
+	"": "关闭",
+	auto: "自动 (厂商原生优先，否则 LLM)",
+	vendor: "仅厂商原生",
+	llm: "仅 LLM",
+};
+
+export type AutoQuestionMode = "" | "event" | "answer";
+
+export const AUTO_QUESTION_MODE_LABELS: Record<AutoQuestionMode, string> = {
+	"": "关闭",
+	event: "识别后自动发送到对话",
+	answer: "识别后由后台直接回答",
+};
+
+export type TranscriptChineseVariant = "" | "zh-CN" | "zh-TW" | "zh-HK";
+
+export const TRANSCRIPT_CHINESE_VARIANT_LABELS: Record<
+	TranscriptChineseVariant,
+	string
+> = {
+	"": "不转换",
+	"zh-CN": "简体中文",
+	"zh-TW": "繁体中文 (台湾)",
+	"zh-HK": "繁体中文 (香港)",
+};
+
+export type DeepgramBackend = "" | "api" | "sdk";
+
+export const DEEPGRAM_BACKEND_LABELS: Record<DeepgramBackend, string> = {
+	"": "跟随构建默认",
+	api: "原生 WebSocket",
+	sdk: "Deepgram SDK",
+};
+
+export type TranscriptPunctuationMode = "" | "rule" | "llm";
+
+export const TRANSCRIPT_PUNCTUATION_LABELS: Record<
+	TranscriptPunctuationMode,
+	string
+> = {
+	"": "关闭",
+	rule: "规则补全",
+	llm: "LLM 补全 (失败时回退规则)",
+};
+
 export const TRANSCRIBE_VENDORS: readonly TranscribeVendor[] = [
 	"assemblyai",
 	"deepgram",
 	"gladia",
 	"revai",
 	"speechmatics",
+	"openai_compatible",
+	"openai_realtime",
+	"dashscope",
+	"volcengine",
 ];
 
 export const TRANSCRIBE_VENDOR_LABELS: Record<TranscribeVendor, string> = {
@@ -34,6 +99,10 @@ export const TRANSCRIBE_VENDOR_LABELS: Record<TranscribeVendor, string> = {
 	gladia: "Gladia",
 	revai: "RevAI",
 	speechmatics: "Speechmatics",
+	openai_compatible: "OpenAI 兼容",
+	openai_realtime: "OpenAI Realtime",
+	dashscope: "阿里云百炼",
+	volcengine: "火山引擎",
 };
 
 export interface LlmProviderSettings {
@@ -48,15 +117,23 @@ export interface LlmProviderSettings {
 	geminiApiKey: string;
 	geminiBaseUrl: string;
 	geminiModel: string;
+	anthropicApiKey: string;
+	anthropicBaseUrl: string;
+	anthropicModel: string;
 	customOpenAiName: string;
 	customOpenAiApiKey: string;
 	customOpenAiBaseUrl: string;
 	customOpenAiModel: string;
+	proxyUrl: string;
+	proxyOverrides: string;
+	noProxy: string;
+	contextTokenBudget: string;
 }
 
 export interface TranscriptProviderSettings {
 	deepgramApiKey: string;
 	deepgramLanguage: string;
+	deepgramBackend: DeepgramBackend;
 	assemblyApiKey: string;
 	gladiaApiKey: string;
 	gladiaLanguage: string;
@@ -67,9 +144,74 @@ export interface TranscriptProviderSettings {
 	revaiApiKey: string;
 	revaiLanguage: string;
 	revaiMetadata: string;
+	revaiCustomVocabularyId: string;
+	openaiCompatibleApiKey: string;
+	openaiCompatibleBaseUrl: string;
+	openaiCompatibleModel: string;
+	openaiCompatibleLanguage: string;
+	openaiRealtimeApiKey: string;
+	openaiRealtimeBaseUrl: string;
+	openaiRealtimeModel: string;
+	openaiRealtimeLanguage: string;
+	dashscopeApiKey: string;
+	dashscopeModel: string;
+	dashscopeLanguage: string;
+	dashscopeWsUrl: string;
+	volcengineAppKey: string;
+	volcengineAccessKey: string;
+	volcengineResourceId: string;
+	volcengineWsUrl: string;
+	asrFallbackVendors: string;
+	asrTokenServiceUrl: string;
+	asrTokenServiceAuth: string;
+	asrVendorTokens: boolean;
+	proxyUrl: string;
+	proxyOverrides: string;
+	noProxy: string;
+	asrComparisonVendors: string;
+	asrVocabulary: string;
+	asrLanguageMode: AsrLanguageMode;
+	asrLanguages: string;
+	translationMode: TranslationMode;
+	translationTargetLanguages: string;
+	translationLlmProvider: string;
+	transcriptChineseVariant: TranscriptChineseVariant;
+	transcriptPunctuation: TranscriptPunctuationMode;
+	transcriptPunctuationLlmProvider: string;
+	transcriptRemoveFillers: boolean;
+	transcriptNormalizeNumbers: boolean;
+	transcriptReplacements: string;
+	autoQuestionMode: AutoQuestionMode;
+	autoQuestionSilenceMs: string;
+	autoQuestionLlmProvider: string;
+	autoQuestionClassifierProvider: string;
+	autoQuestionPrompt: string;
+	asrPrices: string;
+	asrMonthlyBudget: string;
 	macosSystemAudioBackend: MacosSystemAudioBackend;
 }
 
+export interface TranscriptVendorCapabilities {
+	vendor: TranscribeVendor;
+	compiledIn: boolean;
+	backend: "api" | "sdk" | null;
+	languages: string[];
+	autoLanguageDetection: boolean;
+	codeSwitching: boolean;
+	resampledRate: number | null;
+	drafts: boolean;
+	punctuation: boolean;
+	forceEndpoint: boolean;
+	diarization: boolean;
+	translation: boolean;
+	vocabulary: boolean;
+	requiredConfig: {
+		field: keyof TranscriptProviderSettings;
+		envKeys: string[];
+	}[];
+	missingConfig: (keyof TranscriptProviderSettings)[];
+}
+
 export interface ProviderEnvPresets {
 	llm: LlmProviderSettings;
 	transcript: TranscriptProviderSettings;
@@ -83,6 +225,7 @@ const LLM_API_KEY_FIELDS = [
 	"aliQwenApiKey",
 	"openaiApiKey",
 	"geminiApiKey",
+	"anthropicApiKey",
 	"customOpenAiApiKey",
 ] as const satisfies readonly (keyof LlmProviderSettings)[];
 
@@ -90,6 +233,44 @@ function readString(value: unknown, fallback = "") {
 	return typeof value === "string" ? value : fallback;
 }
 
+function readAsrLanguageMode(value: unknown): AsrLanguageMode {
+	return typeof value === "string" && value in ASR_LANGUAGE_MODE_LABELS
+		? (value as AsrLanguageMode)
+		: "";
+}
+
+function readTranslationMode(value: unknown): TranslationMode {
+	return typeof value === "string" && value in TRANSLATION_MODE_LABELS
+		? (value as TranslationMode)
+		: "";
+}
+
+function readAutoQuestionMode(value: unknown): AutoQuestionMode {
+	return typeof value === "string" && value in AUTO_QUESTION_MODE_LABELS
+		? (value as AutoQuestionMode)
+		: "";
+}
+
+function readTranscriptChineseVariant(
+	value: unknown,
+): TranscriptChineseVariant {
+	return typeof value === "string" && value in TRANSCRIPT_CHINESE_VARIANT_LABELS
+		? (value as TranscriptChineseVariant)
+		: "";
+}
+
+function readDeepgramBackend(value: unknown): DeepgramBackend {
+	return typeof value === "string" && value in DEEPGRAM_BACKEND_LABELS
+		? (value as DeepgramBackend)
+		: "";
+}
+
+function readTranscriptPunctuation(value: unknown): TranscriptPunctuationMode {
+	return typeof value === "string" && value in TRANSCRIPT_PUNCTUATION_LABELS
+		? (value as TranscriptPunctuationMode)
+		: "";
+}
+
 export function createDefaultLlmProviderSettings(): LlmProviderSettings {
 	return {
 		siliconflowApiKey: "",
@@ -103,10 +284,17 @@ export function createDefaultLlmProviderSettings(): LlmProviderSettings {
 		geminiApiKey: "",
 		geminiBaseUrl: "https://generativelanguage.googleapis.com/v1beta/openai",
 		geminiModel: "gemini-3-flash-preview",
+		anthropicApiKey: "",
+		anthropicBaseUrl: "https://api.anthropic.com/v1",
+		anthropicModel: "claude-sonnet-4-5",
 		customOpenAiName: "自定义 OpenAI 兼容供应商",
 		customOpenAiApiKey: "",
 		customOpenAiBaseUrl: "",
 		customOpenAiModel: "",
+		proxyUrl: "",
+		proxyOverrides: "",
+		noProxy: "",
+		contextTokenBudget: "",
 	};
 }
 
@@ -114,6 +302,7 @@ export function createDefaultTranscriptProviderSettings(): TranscriptProviderSet
 	return {
 		deepgramApiKey: "",
 		deepgramLanguage: "zh",
+		deepgramBackend: "",
 		assemblyApiKey: "",
 		gladiaApiKey: "",
 		gladiaLanguage: "zh",
@@ -124,6 +313,50 @@ export function createDefaultTranscriptProviderSettings(): TranscriptProviderSet
 		revaiApiKey: "",
 		revaiLanguage: "cmn",
 		revaiMetadata: "",
+		revaiCustomVocabularyId: "",
+		openaiCompatibleApiKey: "",
+		openaiCompatibleBaseUrl: "",
+		openaiCompatibleModel: "whisper-1",
+		openaiCompatibleLanguage: "zh",
+		openaiRealtimeApiKey: "",
+		openaiRealtimeBaseUrl: "",
+		openaiRealtimeModel: "gpt-4o-transcribe",
+		openaiRealtimeLanguage: "zh",
+		dashscopeApiKey: "",
+		dashscopeModel: "paraformer-realtime-v2",
+		dashscopeLanguage: "",
+		dashscopeWsUrl: "",
+		volcengineAppKey: "",
+		volcengineAccessKey: "",
+		volcengineResourceId: "",
+		volcengineWsUrl: "",
+		asrFallbackVendors: "",
+		asrTokenServiceUrl: "",
+		asrTokenServiceAuth: "",
+		asrVendorTokens: false,
+		proxyUrl: "",
+		proxyOverrides: "",
+		noProxy: "",
+		asrComparisonVendors: "",
+		asrVocabulary: "",
+		asrLanguageMode: "",
+		asrLanguages: "",
+		translationMode: "",
+		translationTargetLanguages: "",
+		translationLlmProvider: "",
+		transcriptChineseVariant: "",
+		transcriptPunctuation: "",
+		transcriptPunctuationLlmProvider: "",
+		transcriptRemoveFillers: false,
+		transcriptNormalizeNumbers: false,
+		transcriptReplacements: "",
+		autoQuestionMode: "",
+		autoQuestionSilenceMs: "",
+		autoQuestionLlmProvider: "",
+		autoQuestionClassifierProvider: "",
+		autoQuestionPrompt: "",
+		asrPrices: "",
+		asrMonthlyBudget: "",
 		macosSystemAudioBackend: "swift-helper",
 	};
 }
@@ -150,6 +383,12 @@ export function normalizeLlmProviderSettings(
 		geminiApiKey: readString(raw.geminiApiKey),
 		geminiBaseUrl: readString(raw.geminiBaseUrl, defaults.geminiBaseUrl),
 		geminiModel: readString(raw.geminiModel, defaults.geminiModel),
+		anthropicApiKey: readString(raw.anthropicApiKey),
+		anthropicBaseUrl: readString(
+			raw.anthropicBaseUrl,
+			defaults.anthropicBaseUrl,
+		),
+		anthropicModel: readString(raw.anthropicModel, defaults.anthropicModel),
 		customOpenAiName: readString(
 			raw.customOpenAiName,
 			defaults.customOpenAiName,
@@ -157,6 +396,10 @@ export function normalizeLlmProviderSettings(
 		customOpenAiApiKey: readString(raw.customOpenAiApiKey),
 		customOpenAiBaseUrl: readString(raw.customOpenAiBaseUrl),
 		customOpenAiModel: readString(raw.customOpenAiModel),
+		proxyUrl: readString(raw.proxyUrl),
+		proxyOverrides: readString(raw.proxyOverrides),
+		noProxy: readString(raw.noProxy),
+		contextTokenBudget: readString(raw.contextTokenBudget),
 	};
 }
 
@@ -176,6 +419,7 @@ export function normalizeTranscriptProviderSettings(
 			raw.deepgramLanguage,
 			defaults.deepgramLanguage,
 		),
+		deepgramBackend: readDeepgramBackend(raw.deepgramBackend),
 		assemblyApiKey: readString(raw.assemblyApiKey),
 		gladiaApiKey: readString(raw.gladiaApiKey),
 		gladiaLanguage: readString(raw.gladiaLanguage, defaults.gladiaLanguage),
@@ -192,6 +436,68 @@ export function normalizeTranscriptProviderSettings(
 		revaiApiKey: readString(raw.revaiApiKey),
 		revaiLanguage: readString(raw.revaiLanguage, defaults.revaiLanguage),
 		revaiMetadata: readString(raw.revaiMetadata),
+		revaiCustomVocabularyId: readString(raw.revaiCustomVocabularyId),
+		openaiCompatibleApiKey: readString(raw.openaiCompatibleApiKey),
+		openaiCompatibleBaseUrl: readString(raw.openaiCompatibleBaseUrl),
+		openaiCompatibleModel: readString(
+			raw.openaiCompatibleModel,
+			defaults.openaiCompatibleModel,
+		),
+		openaiCompatibleLanguage: readString(
+			raw.openaiCompatibleLanguage,
+			defaults.openaiCompatibleLanguage,
+		),
+		openaiRealtimeApiKey: readString(raw.openaiRealtimeApiKey),
+		openaiRealtimeBaseUrl: readString(raw.openaiRealtimeBaseUrl),
+		openaiRealtimeModel: readString(
+			raw.openaiRealtimeModel,
+			defaults.openaiRealtimeModel,
+		),
+		openaiRealtimeLanguage: readString(
+			raw.openaiRealtimeLanguage,
+			defaults.openaiRealtimeLanguage,
+		),
+		dashscopeApiKey: readString(raw.dashscopeApiKey),
+		dashscopeModel: readString(raw.dashscopeModel, defaults.dashscopeModel),
+		dashscopeLanguage: readString(raw.dashscopeLanguage),
+		dashscopeWsUrl: readString(raw.dashscopeWsUrl),
+		volcengineAppKey: readString(raw.volcengineAppKey),
+		volcengineAccessKey: readString(raw.volcengineAccessKey),
+		volcengineResourceId: readString(raw.volcengineResourceId),
+		volcengineWsUrl: readString(raw.volcengineWsUrl),
+		asrFallbackVendors: readString(raw.asrFallbackVendors),
+		asrTokenServiceUrl: readString(raw.asrTokenServiceUrl),
+		asrTokenServiceAuth: readString(raw.asrTokenServiceAuth),
+		asrVendorTokens: raw.asrVendorTokens === true,
+		proxyUrl: readString(raw.proxyUrl),
+		proxyOverrides: readString(raw.proxyOverrides),
+		noProxy: readString(raw.noProxy),
+		asrComparisonVendors: readString(raw.asrComparisonVendors),
+		asrVocabulary: readString(raw.asrVocabulary),
+		asrLanguageMode: readAsrLanguageMode(raw.asrLanguageMode),
+		asrLanguages: readString(raw.asrLanguages),
+		translationMode: readTranslationMode(raw.translationMode),
+		translationTargetLanguages: readString(raw.translationTargetLanguages),
+		translationLlmProvider: readString(raw.translationLlmProvider),
+		transcriptChineseVariant: readTranscriptChineseVariant(
+			raw.transcriptChineseVariant,
+		),
+		transcriptPunctuation: readTranscriptPunctuation(raw.transcriptPunctuation),
+		transcriptPunctuationLlmProvider: readString(
+			raw.transcriptPunctuationLlmProvider,
+		),
+		transcriptRemoveFillers: raw.transcriptRemoveFillers === true,
+		transcriptNormalizeNumbers: raw.transcriptNormalizeNumbers === true,
+		transcriptReplacements: readString(raw.transcriptReplacements),
+		autoQuestionMode: readAutoQuestionMode(raw.autoQuestionMode),
+		autoQuestionSilenceMs: readString(raw.autoQuestionSilenceMs),
+		autoQuestionLlmProvider: readString(raw.autoQuestionLlmProvider),
+		autoQuestionClassifierProvider: readString(
+			raw.autoQuestionClassifierProvider,
+		),
+		autoQuestionPrompt: readString(raw.autoQuestionPrompt),
+		asrPrices: readString(raw.asrPrices),
+		asrMonthlyBudget: readString(raw.asrMonthlyBudget),
 		macosSystemAudioBackend:
 			raw.macosSystemAudioBackend === "rust-native"
 				? "rust-native"
@@ -279,11 +585,19 @@ export function hasAnyTranscriptApiKeyConfigured(
 		settings.gladiaApiKey,
 		settings.speechmaticsApiKey,
 		settings.revaiApiKey,
+		settings.openaiCompatibleBaseUrl,
+		settings.openaiRealtimeApiKey,
+		settings.dashscopeApiKey,
+		settings.volcengineAccessKey,
 		presets?.deepgramApiKey,
 		presets?.assemblyApiKey,
 		presets?.gladiaApiKey,
 		presets?.speechmaticsApiKey,
 		presets?.revaiApiKey,
+		presets?.openaiCompatibleBaseUrl,
+		presets?.openaiRealtimeApiKey,
+		presets?.dashscopeApiKey,
+		presets?.volcengineAccessKey,
 	].some(hasConfiguredValue);
 }
 
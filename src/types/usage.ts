@@ -0,0 +1,41 @@
+export interface UsageTotals {
+	audioSeconds: number;
+	keepaliveSeconds: number;
+}
+
+export interface VendorUsage extends UsageTotals {
+	vendor: string;
+	estimatedCost: number | null;
+}
+
+export interface DailyUsage {
+	date: string;
+	vendors: VendorUsage[];
+	estimatedCost: number;
+}
+
+export interface SessionUsage {
+	sessionId: string;
+	startedAt: string;
+	endedAt: string | null;
+	vendors: VendorUsage[];
+	estimatedCost: number;
+}
+
+export interface UsageReport {
+	currency: string;
+	month: string;
+	monthVendors: VendorUsage[];
+	monthEstimatedCost: number;
+	monthlyBudget: number | null;
+	days: DailyUsage[];
+	sessions: SessionUsage[];
+	unpricedVendors: string[];
+}
+
+export interface BudgetAlert {
+	month: string;
+	threshold: number;
+	estimatedCost: number;
+	budget: number;
+}